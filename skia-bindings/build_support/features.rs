@@ -61,6 +61,9 @@ impl Features {
         if cfg!(feature = "skottie") {
             features += feature::SKOTTIE;
         }
+        if cfg!(feature = "cpu-effects") {
+            features += feature::CPU_EFFECTS;
+        }
         if cfg!(feature = "webp-encode") {
             features += feature::WEBP_ENCODE;
         }
@@ -217,6 +220,8 @@ pub mod feature {
     pub const SVG: &str = "svg";
     /// Support for Lottie animations via Skottie
     pub const SKOTTIE: &str = "skottie";
+    /// Support for image filters, shaders, and color filters implemented in Rust
+    pub const CPU_EFFECTS: &str = "cpu-effects";
     /// Support the encoding of bitmap data to the WEBP image format
     pub const WEBP_ENCODE: &str = "webp-encode";
     /// Support the decoding of the WEBP image format to bitmap data
@@ -241,6 +246,7 @@ pub mod feature {
     ];
 
     pub const KEY_REPLACEMENTS: &[(&str, &str)] = &[
+        (CPU_EFFECTS, "cpufx"),
         (WEBP_ENCODE, "webpe"),
         (WEBP_DECODE, "webpd"),
         (EMBED_FREETYPE, "ftembed"),
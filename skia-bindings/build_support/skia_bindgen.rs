@@ -78,6 +78,9 @@ impl Configuration {
             if features[feature::SVG] || features[feature::SKOTTIE] {
                 sources.push("src/skresources.cpp".into());
             }
+            if features[feature::CPU_EFFECTS] {
+                sources.push("src/cpu-effects.cpp".into());
+            }
             if features[feature::WEBP_ENCODE] {
                 sources.push("src/webp-encode.cpp".into());
             }
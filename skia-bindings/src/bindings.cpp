@@ -53,6 +53,8 @@
 #include "include/core/SkImageInfo.h"
 #include "include/core/SkM44.h"
 #include "include/core/SkMaskFilter.h"
+#include "include/core/SkMesh.h"
+#include "include/core/SkOverdrawCanvas.h"
 #include "include/core/SkPaint.h"
 #include "include/core/SkPath.h"
 #include "include/core/SkPathBuilder.h"
@@ -82,6 +84,7 @@
 #include "include/core/SkTypes.h"
 #include "include/core/SkVertices.h"
 // docs/
+#include "include/docs/SkMultiPictureDocument.h"
 #include "include/docs/SkPDFDocument.h"
 #include "include/docs/SkPDFJpegHelpers.h"
 
@@ -115,18 +118,33 @@
 // pathops/
 #include "include/pathops/SkPathOps.h"
 
+// sksl/
+#include "include/sksl/SkSLDebugTrace.h"
+
 // svg/
 #include "include/svg/SkSVGCanvas.h"
 
 // utils/
 #include "include/utils/SkCamera.h"
 #include "include/utils/SkCustomTypeface.h"
+#include "include/utils/SkNoDrawCanvas.h"
 #include "include/utils/SkNullCanvas.h"
+#include "include/utils/SkNWayCanvas.h"
 #include "include/utils/SkOrderedFontMgr.h"
+#include "include/utils/SkPaintFilterCanvas.h"
 #include "include/utils/SkParsePath.h"
 #include "include/utils/SkShadowUtils.h"
 #include "include/utils/SkTextUtils.h"
 
+// SkPicture: Partial playback and the bounding box hierarchy are only available through
+// SkBigPicture, which SkPictureRecorder records pictures with more than one op into.
+#include "src/core/SkBigPicture.h"
+#include "src/core/SkPicturePriv.h"
+
+// RustCanvas: SkCanvas only forward declares sktext::GlyphRunList, and there is no public API to
+// convert text drawn with drawString(), drawGlyphs(), etc. into a text blob.
+#include "src/text/GlyphRun.h"
+
 extern "C" void C_Bindings_Types(Sink<bool>) {}
 
 //
@@ -644,6 +662,14 @@ extern "C" void C_SkPaint_setImageFilter(SkPaint* self, SkImageFilter* imageFilt
     self->setImageFilter(sp(imageFilter));
 }
 
+extern "C" bool C_SkPaint_canComputeFastBounds(const SkPaint* self) {
+    return self->canComputeFastBounds();
+}
+
+extern "C" void C_SkPaint_computeFastBounds(const SkPaint* self, const SkRect* orig, SkRect* bounds) {
+    *bounds = self->computeFastBounds(*orig, bounds);
+}
+
 //
 // core/SkPathIter.h
 //
@@ -1154,6 +1180,10 @@ extern "C" void C_SkCanvas_drawAtlas(
         mode, *sampling, cullRect, paint);
 }
 
+extern "C" void C_SkCanvas_drawMesh(SkCanvas* self, const SkMesh* mesh, SkBlender* blender, const SkPaint* paint) {
+    self->drawMesh(*mesh, sp(blender), *paint);
+}
+
 //
 // core/SkAutoCanvasRestore.h
 //
@@ -1170,6 +1200,14 @@ extern "C" void C_SkAutoCanvasRestore_restore(SkAutoCanvasRestore* self) {
     self->restore();
 }
 
+//
+// core/SkOverdrawCanvas.h
+//
+
+extern "C" SkCanvas* C_SkOverdrawCanvas_new(SkCanvas* canvas) {
+    return new SkOverdrawCanvas(canvas);
+}
+
 //
 // core/SkImageInfo.h
 //
@@ -1315,6 +1353,23 @@ extern "C" SkColorSpace* C_SkColorSpace_Deserialize(const void* data, size_t len
     return SkColorSpace::Deserialize(data, length).release();
 }
 
+// `toXYZD50` receives the 9 values of the matrix in row-major order.
+extern "C" void C_SkColorSpace_toXYZD50(const SkColorSpace* self, float* toXYZD50) {
+    skcms_Matrix3x3 matrix;
+    self->toXYZD50(&matrix);
+    for (int i = 0; i < 9; ++i) {
+        toXYZD50[i] = matrix.vals[i / 3][i % 3];
+    }
+}
+
+extern "C" SkColorSpace* C_SkColorSpace_MakeRGB(const skcms_TransferFunction* transferFn, const float* toXYZD50) {
+    skcms_Matrix3x3 matrix;
+    for (int i = 0; i < 9; ++i) {
+        matrix.vals[i / 3][i % 3] = toXYZD50[i];
+    }
+    return SkColorSpace::MakeRGB(*transferFn, matrix).release();
+}
+
 extern "C" uint32_t C_SkColorSpace_transferFnHash(const SkColorSpace* self) {
     return self->transferFnHash();
 }
@@ -1534,6 +1589,46 @@ extern "C" void C_SkPicture_playback(const SkPicture* self, SkCanvas* canvas) {
     self->playback(canvas);
 }
 
+class RustAbortCallback final : public SkPicture::AbortCallback {
+public:
+    RustAbortCallback(void* data, bool (*abort)(void*))
+    : _data(data), _abort(abort)
+    { }
+
+    bool abort() override {
+        return _abort(_data);
+    }
+
+private:
+    void* _data;
+    bool (*_abort)(void*);
+};
+
+extern "C" void C_SkPicture_playbackWithCallback(
+    const SkPicture* self, SkCanvas* canvas, void* data, bool (*abort)(void*)) {
+    RustAbortCallback callback(data, abort);
+    self->playback(canvas, &callback);
+}
+
+extern "C" void C_SkPicture_partialPlayback(const SkPicture* self, SkCanvas* canvas, int start, int stop) {
+    auto bigPicture = SkPicturePriv::AsSkBigPicture(sk_ref_sp(self));
+    if (bigPicture) {
+        bigPicture->partialPlayback(canvas, start, stop, canvas->getLocalToDevice());
+    } else if (start < stop && start < self->approximateOpCount()) {
+        // Pictures that are not backed by a SkRecord contain at most one op.
+        SkAutoCanvasRestore acr(canvas, true);
+        self->playback(canvas);
+    }
+}
+
+extern "C" SkBBoxHierarchy* C_SkPicture_bbh(const SkPicture* self) {
+    auto bigPicture = SkPicturePriv::AsSkBigPicture(sk_ref_sp(self));
+    if (!bigPicture) {
+        return nullptr;
+    }
+    return SkSafeRef(const_cast<SkBBoxHierarchy*>(bigPicture->bbh()));
+}
+
 extern "C" void C_SkPicture_cullRect(const SkPicture* self, SkRect* uninitialized) {
     new (uninitialized) SkRect(self->cullRect());
 }
@@ -2278,6 +2373,205 @@ extern "C" SkVertices* C_SkVertices_Builder_detach(SkVertices::Builder* builder)
     return builder->detach().release();
 }
 
+//
+// core/SkMesh.h
+//
+
+extern "C" SkMeshSpecification* C_SkMeshSpecification_Make(
+    const SkMeshSpecification::Attribute::Type* attributeTypes,
+    const size_t* attributeOffsets,
+    const SkString* attributeNames,
+    size_t attributeCount,
+    size_t vertexStride,
+    const SkMeshSpecification::Varying::Type* varyingTypes,
+    const SkString* varyingNames,
+    size_t varyingCount,
+    const SkString* vs,
+    const SkString* fs,
+    SkColorSpace* cs,
+    SkAlphaType at,
+    SkString* error) {
+    std::vector<SkMeshSpecification::Attribute> attributes;
+    for (size_t i = 0; i < attributeCount; ++i) {
+        attributes.push_back({attributeTypes[i], attributeOffsets[i], attributeNames[i]});
+    }
+    std::vector<SkMeshSpecification::Varying> varyings;
+    for (size_t i = 0; i < varyingCount; ++i) {
+        varyings.push_back({varyingTypes[i], varyingNames[i]});
+    }
+    auto r = SkMeshSpecification::Make(attributes, vertexStride, varyings, *vs, *fs, sp(cs), at);
+    *error = r.error;
+    return r.specification.release();
+}
+
+extern "C" void C_SkMeshSpecification_ref(const SkMeshSpecification* self) {
+    self->ref();
+}
+
+extern "C" void C_SkMeshSpecification_unref(const SkMeshSpecification* self) {
+    self->unref();
+}
+
+extern "C" bool C_SkMeshSpecification_unique(const SkMeshSpecification* self) {
+    return self->unique();
+}
+
+extern "C" const SkMeshSpecification::Attribute* C_SkMeshSpecification_attributes(const SkMeshSpecification* self, size_t* count) {
+    auto attributes = self->attributes();
+    *count = attributes.size();
+    return attributes.data();
+}
+
+extern "C" const SkRuntimeEffect::Uniform* C_SkMeshSpecification_uniforms(const SkMeshSpecification* self, size_t* count) {
+    auto uniforms = self->uniforms();
+    *count = uniforms.size();
+    return uniforms.data();
+}
+
+extern "C" const SkRuntimeEffect::Child* C_SkMeshSpecification_children(const SkMeshSpecification* self, size_t* count) {
+    auto children = self->children();
+    *count = children.size();
+    return children.data();
+}
+
+extern "C" const SkRuntimeEffect::Uniform* C_SkMeshSpecification_findUniform(const SkMeshSpecification* self, const char* name, size_t count) {
+    return self->findUniform(std::string_view(name, count));
+}
+
+extern "C" const SkRuntimeEffect::Child* C_SkMeshSpecification_findChild(const SkMeshSpecification* self, const char* name, size_t count) {
+    return self->findChild(std::string_view(name, count));
+}
+
+extern "C" const SkMeshSpecification::Attribute* C_SkMeshSpecification_findAttribute(const SkMeshSpecification* self, const char* name, size_t count) {
+    return self->findAttribute(std::string_view(name, count));
+}
+
+extern "C" size_t C_SkMeshSpecification_uniformSize(const SkMeshSpecification* self) {
+    return self->uniformSize();
+}
+
+extern "C" size_t C_SkMeshSpecification_stride(const SkMeshSpecification* self) {
+    return self->stride();
+}
+
+extern "C" SkColorSpace* C_SkMeshSpecification_colorSpace(const SkMeshSpecification* self) {
+    return SkSafeRef(self->colorSpace());
+}
+
+extern "C" SkMesh::VertexBuffer* C_SkMeshes_MakeVertexBuffer(const void* data, size_t size) {
+    return SkMeshes::MakeVertexBuffer(data, size).release();
+}
+
+extern "C" SkMesh::IndexBuffer* C_SkMeshes_MakeIndexBuffer(const void* data, size_t size) {
+    return SkMeshes::MakeIndexBuffer(data, size).release();
+}
+
+extern "C" size_t C_SkMesh_VertexBuffer_size(const SkMesh::VertexBuffer* self) {
+    return self->size();
+}
+
+extern "C" size_t C_SkMesh_IndexBuffer_size(const SkMesh::IndexBuffer* self) {
+    return self->size();
+}
+
+extern "C" void C_SkMesh_Construct(SkMesh* uninitialized) {
+    new (uninitialized) SkMesh();
+}
+
+extern "C" void C_SkMesh_CopyConstruct(SkMesh* uninitialized, const SkMesh* mesh) {
+    new (uninitialized) SkMesh(*mesh);
+}
+
+extern "C" void C_SkMesh_destruct(SkMesh* self) {
+    self->~SkMesh();
+}
+
+extern "C" bool C_SkMesh_Make(
+    SkMeshSpecification* spec,
+    SkMesh::Mode mode,
+    SkMesh::VertexBuffer* vertexBuffer,
+    size_t vertexCount,
+    size_t vertexOffset,
+    SkMesh::IndexBuffer* indexBuffer,
+    size_t indexCount,
+    size_t indexOffset,
+    SkData* uniforms,
+    SkRuntimeEffect::ChildPtr* children,
+    size_t childCount,
+    const SkRect* bounds,
+    SkMesh* uninitialized,
+    SkString* error) {
+    auto r = indexBuffer
+        ? SkMesh::MakeIndexed(
+            sp(spec), mode, sp(vertexBuffer), vertexCount, vertexOffset,
+            sp(indexBuffer), indexCount, indexOffset,
+            sp(uniforms), SkSpan(children, childCount), *bounds)
+        : SkMesh::Make(
+            sp(spec), mode, sp(vertexBuffer), vertexCount, vertexOffset,
+            sp(uniforms), SkSpan(children, childCount), *bounds);
+    *error = r.error;
+    new (uninitialized) SkMesh(std::move(r.mesh));
+    return uninitialized->isValid();
+}
+
+extern "C" SkMeshSpecification* C_SkMesh_refSpec(const SkMesh* self) {
+    return self->refSpec().release();
+}
+
+extern "C" SkMesh::VertexBuffer* C_SkMesh_refVertexBuffer(const SkMesh* self) {
+    return self->refVertexBuffer().release();
+}
+
+extern "C" SkMesh::IndexBuffer* C_SkMesh_refIndexBuffer(const SkMesh* self) {
+    return self->refIndexBuffer().release();
+}
+
+extern "C" SkData* C_SkMesh_refUniforms(const SkMesh* self) {
+    return const_cast<SkData*>(self->refUniforms().release());
+}
+
+extern "C" bool C_SkMesh_isValid(const SkMesh* self) {
+    return self->isValid();
+}
+
+extern "C" SkMesh::Mode C_SkMesh_mode(const SkMesh* self) {
+    return self->mode();
+}
+
+extern "C" size_t C_SkMesh_vertexOffset(const SkMesh* self) {
+    return self->vertexOffset();
+}
+
+extern "C" size_t C_SkMesh_vertexCount(const SkMesh* self) {
+    return self->vertexCount();
+}
+
+extern "C" size_t C_SkMesh_indexOffset(const SkMesh* self) {
+    return self->indexOffset();
+}
+
+extern "C" size_t C_SkMesh_indexCount(const SkMesh* self) {
+    return self->indexCount();
+}
+
+extern "C" void C_SkMesh_bounds(const SkMesh* self, SkRect* bounds) {
+    *bounds = self->bounds();
+}
+
+//
+// core/SkBBHFactory.h
+//
+
+extern "C" void C_SkBBoxHierarchy_search(const SkBBoxHierarchy* self, const SkRect* query, VecSink<int>* results) {
+    std::vector<int> r;
+    self->search(*query, &r);
+    results->set(r);
+}
+
+extern "C" size_t C_SkBBoxHierarchy_bytesUsed(const SkBBoxHierarchy* self) {
+    return self->bytesUsed();
+}
+
 //
 // SkPictureRecorder
 //
@@ -3386,6 +3680,21 @@ bool C_SkRuntimeEffect_allowBlender(const SkRuntimeEffect* self) {
     return self->allowBlender();
 }
 
+SkShader *C_SkRuntimeEffect_MakeTraced(SkShader *shader, const SkIPoint *traceCoord,
+                                       SkSL::DebugTrace **debugTrace) {
+    auto r = SkRuntimeEffect::MakeTraced(sp(shader), *traceCoord);
+    *debugTrace = r.debugTrace.release();
+    return r.shader.release();
+}
+
+void C_SkSL_DebugTrace_writeTrace(const SkSL::DebugTrace *self, SkWStream *stream) {
+    self->writeTrace(stream);
+}
+
+void C_SkSL_DebugTrace_dump(const SkSL::DebugTrace *self, SkWStream *stream) {
+    self->dump(stream);
+}
+
 void C_SkRuntimeShaderBuilder_Construct(SkRuntimeShaderBuilder *uninitialized,
                                         SkRuntimeEffect *effect) {
   new (uninitialized) SkRuntimeShaderBuilder(sp(effect));
@@ -3407,7 +3716,7 @@ enum class ShaderBuilderUniformResult {
   Error
 };
 
-ShaderBuilderUniformResult C_SkRuntimeShaderBuilder_setUniformFloat(SkRuntimeShaderBuilder *self,
+ShaderBuilderUniformResult C_SkRuntimeEffectBuilder_setUniformFloat(SkRuntimeEffectBuilder *self,
                                               const char *name, size_t count,
                                               const float *const values,
                                               size_t len) {
@@ -3446,7 +3755,7 @@ ShaderBuilderUniformResult C_SkRuntimeShaderBuilder_setUniformFloat(SkRuntimeSha
   return ShaderBuilderUniformResult::Error;
 }
 
-ShaderBuilderUniformResult C_SkRuntimeShaderBuilder_setUniformInt(SkRuntimeShaderBuilder *self,
+ShaderBuilderUniformResult C_SkRuntimeEffectBuilder_setUniformInt(SkRuntimeEffectBuilder *self,
                                             const char *name, size_t count,
                                             const int *const values,
                                             size_t len) {
@@ -3472,6 +3781,68 @@ ShaderBuilderUniformResult C_SkRuntimeShaderBuilder_setUniformInt(SkRuntimeShade
   }
   return ShaderBuilderUniformResult::Error;
 }
+
+enum class BuilderChildResult {
+  Ok,
+  NotFound,
+  TypeMismatch
+};
+
+BuilderChildResult C_SkRuntimeEffectBuilder_setChild(SkRuntimeEffectBuilder *self,
+                                                     const char *name, size_t count,
+                                                     const SkRuntimeEffect::ChildPtr *child) {
+  auto builderChild = self->child(std::string_view(name, count));
+  if (!builderChild.fChild) {
+    return BuilderChildResult::NotFound;
+  }
+  auto type = child ? child->type() : std::nullopt;
+  if (!type.has_value()) {
+    builderChild = nullptr;
+    return BuilderChildResult::Ok;
+  }
+  if (*type != builderChild.fChild->type) {
+    return BuilderChildResult::TypeMismatch;
+  }
+  switch (*type) {
+  case SkRuntimeEffect::ChildType::kShader:
+    builderChild = sk_ref_sp(child->shader());
+    break;
+  case SkRuntimeEffect::ChildType::kColorFilter:
+    builderChild = sk_ref_sp(child->colorFilter());
+    break;
+  case SkRuntimeEffect::ChildType::kBlender:
+    builderChild = sk_ref_sp(child->blender());
+    break;
+  }
+  return BuilderChildResult::Ok;
+}
+
+void C_SkRuntimeColorFilterBuilder_Construct(SkRuntimeColorFilterBuilder *uninitialized,
+                                             SkRuntimeEffect *effect) {
+  new (uninitialized) SkRuntimeColorFilterBuilder(sp(effect));
+}
+
+void C_SkRuntimeColorFilterBuilder_destruct(SkRuntimeColorFilterBuilder *self) {
+  self->~SkRuntimeColorFilterBuilder();
+}
+
+SkColorFilter *
+C_SkRuntimeColorFilterBuilder_makeColorFilter(const SkRuntimeColorFilterBuilder *self) {
+  return self->makeColorFilter().release();
+}
+
+void C_SkRuntimeBlendBuilder_Construct(SkRuntimeBlendBuilder *uninitialized,
+                                       SkRuntimeEffect *effect) {
+  new (uninitialized) SkRuntimeBlendBuilder(sp(effect));
+}
+
+void C_SkRuntimeBlendBuilder_destruct(SkRuntimeBlendBuilder *self) {
+  self->~SkRuntimeBlendBuilder();
+}
+
+SkBlender *C_SkRuntimeBlendBuilder_makeBlender(const SkRuntimeBlendBuilder *self) {
+  return self->makeBlender().release();
+}
 }
 
 //
@@ -3930,6 +4301,33 @@ extern "C" void C_SkPDF_SetNodeId(SkCanvas* dst, int nodeID) {
 }
 #endif
 
+//
+// docs/SkMultiPictureDocument.h
+//
+
+extern "C" SkDocument* C_SkMultiPictureDocument_Make(SkWStream* stream) {
+    return SkMultiPictureDocument::Make(stream).release();
+}
+
+extern "C" int C_SkMultiPictureDocument_ReadPageCount(const void* data, size_t length) {
+    SkMemoryStream stream(data, length, false);
+    return SkMultiPictureDocument::ReadPageCount(&stream);
+}
+
+extern "C" bool C_SkMultiPictureDocument_Read(
+    const void* data, size_t length, SkPicture** pictures, SkSize* sizes, int count) {
+    SkMemoryStream stream(data, length, false);
+    std::vector<SkDocumentPage> pages(count);
+    if (!SkMultiPictureDocument::Read(&stream, pages.data(), count)) {
+        return false;
+    }
+    for (int i = 0; i < count; ++i) {
+        pictures[i] = pages[i].fPicture.release();
+        sizes[i] = pages[i].fSize;
+    }
+    return true;
+}
+
 //
 // pathops/
 //
@@ -4012,6 +4410,58 @@ extern "C" SkCanvas* C_SkMakeNullCanvas() {
     return SkMakeNullCanvas().release();
 }
 
+extern "C" SkCanvas* C_SkNWayCanvas_new(int width, int height) {
+    return new SkNWayCanvas(width, height);
+}
+
+extern "C" void C_SkNWayCanvas_addCanvas(SkCanvas* self, SkCanvas* canvas) {
+    static_cast<SkNWayCanvas*>(self)->addCanvas(canvas);
+}
+
+extern "C" void C_SkNWayCanvas_removeCanvas(SkCanvas* self, SkCanvas* canvas) {
+    static_cast<SkNWayCanvas*>(self)->removeCanvas(canvas);
+}
+
+extern "C" void C_SkNWayCanvas_removeAll(SkCanvas* self) {
+    static_cast<SkNWayCanvas*>(self)->removeAll();
+}
+
+namespace PaintFilter {
+    extern "C" {
+        typedef void (*Drop)(TraitObject);
+        typedef bool (*Filter)(TraitObject, SkPaint*);
+    }
+}
+
+class RustPaintFilterCanvas final : public SkPaintFilterCanvas {
+public:
+    struct Param {
+        TraitObject trait;
+        ::PaintFilter::Drop drop;
+        ::PaintFilter::Filter filter;
+    };
+
+    RustPaintFilterCanvas(SkCanvas* canvas, const Param& param)
+    : SkPaintFilterCanvas(canvas), _param(param)
+    { }
+
+    ~RustPaintFilterCanvas() override {
+        _param.drop(_param.trait);
+    }
+
+protected:
+    bool onFilter(SkPaint& paint) const override {
+        return _param.filter(_param.trait, &paint);
+    }
+
+private:
+    Param _param;
+};
+
+extern "C" SkCanvas* C_RustPaintFilterCanvas_new(SkCanvas* canvas, const RustPaintFilterCanvas::Param* param) {
+    return new RustPaintFilterCanvas(canvas, *param);
+}
+
 extern "C" SkOrderedFontMgr* C_SkOrderedFontMgr_new() {
     return new SkOrderedFontMgr();
 }
@@ -4191,3 +4641,274 @@ extern "C" void C_RustWStream_construct(
 extern "C" void C_RustWStream_destruct(RustWStream *stream) {
     stream->~RustWStream();
 }
+
+//
+// SkCanvas <-> RustCanvas interop
+//
+
+namespace RustCanvasBackend {
+    extern "C" {
+        typedef void (*Drop)(TraitObject);
+        typedef void (*WillSave)(TraitObject, const SkCanvas*);
+        typedef void (*SaveLayer)(TraitObject, const SkCanvas*, const SkRect*, const SkPaint*, SkCanvas::SaveLayerFlags);
+        typedef void (*WillRestore)(TraitObject, const SkCanvas*);
+        typedef void (*DidConcat)(TraitObject, const SkCanvas*, const SkM44*);
+        typedef void (*DidSetMatrix)(TraitObject, const SkCanvas*, const SkM44*);
+        typedef void (*ClipRect)(TraitObject, const SkCanvas*, const SkRect*, SkClipOp, bool);
+        typedef void (*ClipRRect)(TraitObject, const SkCanvas*, const SkRRect*, SkClipOp, bool);
+        typedef void (*ClipPath)(TraitObject, const SkCanvas*, const SkPath*, SkClipOp, bool);
+        typedef void (*ClipShader)(TraitObject, const SkCanvas*, SkShader*, SkClipOp);
+        typedef void (*ClipRegion)(TraitObject, const SkCanvas*, const SkRegion*, SkClipOp);
+        typedef void (*DrawPaint)(TraitObject, const SkCanvas*, const SkPaint*);
+        typedef void (*DrawPoints)(TraitObject, const SkCanvas*, SkCanvas::PointMode, const SkPoint*, size_t, const SkPaint*);
+        typedef void (*DrawRect)(TraitObject, const SkCanvas*, const SkRect*, const SkPaint*);
+        typedef void (*DrawRRect)(TraitObject, const SkCanvas*, const SkRRect*, const SkPaint*);
+        typedef void (*DrawDRRect)(TraitObject, const SkCanvas*, const SkRRect*, const SkRRect*, const SkPaint*);
+        typedef void (*DrawOval)(TraitObject, const SkCanvas*, const SkRect*, const SkPaint*);
+        typedef void (*DrawArc)(TraitObject, const SkCanvas*, const SkRect*, SkScalar, SkScalar, bool, const SkPaint*);
+        typedef void (*DrawPath)(TraitObject, const SkCanvas*, const SkPath*, const SkPaint*);
+        typedef void (*DrawRegion)(TraitObject, const SkCanvas*, const SkRegion*, const SkPaint*);
+        typedef void (*DrawTextBlob)(TraitObject, const SkCanvas*, const SkTextBlob*, SkScalar, SkScalar, const SkPaint*);
+        typedef void (*DrawImage)(TraitObject, const SkCanvas*, const SkImage*, SkScalar, SkScalar, const SkSamplingOptions*, const SkPaint*);
+        typedef void (*DrawImageRect)(TraitObject, const SkCanvas*, const SkImage*, const SkRect*, const SkRect*, const SkSamplingOptions*, const SkPaint*, SkCanvas::SrcRectConstraint);
+        typedef void (*DrawVertices)(TraitObject, const SkCanvas*, const SkVertices*, SkBlendMode, const SkPaint*);
+        typedef void (*DrawAnnotation)(TraitObject, const SkCanvas*, const SkRect*, const char*, SkData*);
+        typedef bool (*DrawPicture)(TraitObject, const SkCanvas*, const SkPicture*, const SkMatrix*, const SkPaint*);
+        typedef bool (*DrawDrawable)(TraitObject, const SkCanvas*, SkDrawable*, const SkMatrix*);
+    }
+}
+
+class RustCanvas final : public SkNoDrawCanvas {
+public:
+    // Draws that are not forwarded with their arguments.
+    enum class UnsupportedDraw {
+        Atlas,
+        ImageLattice,
+        Patch,
+        EdgeAAQuad,
+        EdgeAAImageSet,
+        Shadow,
+        Mesh,
+        Behind,
+    };
+
+    typedef void (*UnsupportedDrawFn)(TraitObject, const SkCanvas*, UnsupportedDraw);
+
+    struct Param {
+        TraitObject trait;
+        ::RustCanvasBackend::Drop drop;
+        ::RustCanvasBackend::WillSave willSave;
+        ::RustCanvasBackend::SaveLayer saveLayer;
+        ::RustCanvasBackend::WillRestore willRestore;
+        ::RustCanvasBackend::DidConcat didConcat;
+        ::RustCanvasBackend::DidSetMatrix didSetMatrix;
+        ::RustCanvasBackend::ClipRect clipRect;
+        ::RustCanvasBackend::ClipRRect clipRRect;
+        ::RustCanvasBackend::ClipPath clipPath;
+        ::RustCanvasBackend::ClipShader clipShader;
+        ::RustCanvasBackend::ClipRegion clipRegion;
+        ::RustCanvasBackend::DrawPaint drawPaint;
+        ::RustCanvasBackend::DrawPoints drawPoints;
+        ::RustCanvasBackend::DrawRect drawRect;
+        ::RustCanvasBackend::DrawRRect drawRRect;
+        ::RustCanvasBackend::DrawDRRect drawDRRect;
+        ::RustCanvasBackend::DrawOval drawOval;
+        ::RustCanvasBackend::DrawArc drawArc;
+        ::RustCanvasBackend::DrawPath drawPath;
+        ::RustCanvasBackend::DrawRegion drawRegion;
+        ::RustCanvasBackend::DrawTextBlob drawTextBlob;
+        ::RustCanvasBackend::DrawImage drawImage;
+        ::RustCanvasBackend::DrawImageRect drawImageRect;
+        ::RustCanvasBackend::DrawVertices drawVertices;
+        ::RustCanvasBackend::DrawAnnotation drawAnnotation;
+        ::RustCanvasBackend::DrawPicture drawPicture;
+        ::RustCanvasBackend::DrawDrawable drawDrawable;
+        UnsupportedDrawFn unsupportedDraw;
+    };
+
+    RustCanvas(const SkIRect& bounds, const Param& param)
+    : SkNoDrawCanvas(bounds), _param(param)
+    { }
+
+    ~RustCanvas() override {
+        _param.drop(_param.trait);
+    }
+
+protected:
+    void willSave() override {
+        _param.willSave(_param.trait, this);
+    }
+
+    SaveLayerStrategy getSaveLayerStrategy(const SaveLayerRec& rec) override {
+        _param.saveLayer(_param.trait, this, rec.fBounds, rec.fPaint, rec.fSaveLayerFlags);
+        return SkNoDrawCanvas::getSaveLayerStrategy(rec);
+    }
+
+    void willRestore() override {
+        _param.willRestore(_param.trait, this);
+    }
+
+    void didConcat44(const SkM44& m) override {
+        _param.didConcat(_param.trait, this, &m);
+    }
+
+    void didSetM44(const SkM44& m) override {
+        _param.didSetMatrix(_param.trait, this, &m);
+    }
+
+    void didTranslate(SkScalar dx, SkScalar dy) override {
+        auto m = SkM44::Translate(dx, dy);
+        _param.didConcat(_param.trait, this, &m);
+    }
+
+    void didScale(SkScalar sx, SkScalar sy) override {
+        auto m = SkM44::Scale(sx, sy);
+        _param.didConcat(_param.trait, this, &m);
+    }
+
+    void onClipRect(const SkRect& rect, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        _param.clipRect(_param.trait, this, &rect, op, edgeStyle == kSoft_ClipEdgeStyle);
+        SkNoDrawCanvas::onClipRect(rect, op, edgeStyle);
+    }
+
+    void onClipRRect(const SkRRect& rrect, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        _param.clipRRect(_param.trait, this, &rrect, op, edgeStyle == kSoft_ClipEdgeStyle);
+        SkNoDrawCanvas::onClipRRect(rrect, op, edgeStyle);
+    }
+
+    void onClipPath(const SkPath& path, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        _param.clipPath(_param.trait, this, &path, op, edgeStyle == kSoft_ClipEdgeStyle);
+        SkNoDrawCanvas::onClipPath(path, op, edgeStyle);
+    }
+
+    void onClipShader(sk_sp<SkShader> shader, SkClipOp op) override {
+        _param.clipShader(_param.trait, this, shader.get(), op);
+        SkNoDrawCanvas::onClipShader(std::move(shader), op);
+    }
+
+    void onClipRegion(const SkRegion& deviceRgn, SkClipOp op) override {
+        _param.clipRegion(_param.trait, this, &deviceRgn, op);
+        SkNoDrawCanvas::onClipRegion(deviceRgn, op);
+    }
+
+    void onDrawPaint(const SkPaint& paint) override {
+        _param.drawPaint(_param.trait, this, &paint);
+    }
+
+    void onDrawPoints(PointMode mode, SkSpan<const SkPoint> pts, const SkPaint& paint) override {
+        _param.drawPoints(_param.trait, this, mode, pts.data(), pts.size(), &paint);
+    }
+
+    void onDrawRect(const SkRect& rect, const SkPaint& paint) override {
+        _param.drawRect(_param.trait, this, &rect, &paint);
+    }
+
+    void onDrawRRect(const SkRRect& rrect, const SkPaint& paint) override {
+        _param.drawRRect(_param.trait, this, &rrect, &paint);
+    }
+
+    void onDrawDRRect(const SkRRect& outer, const SkRRect& inner, const SkPaint& paint) override {
+        _param.drawDRRect(_param.trait, this, &outer, &inner, &paint);
+    }
+
+    void onDrawOval(const SkRect& rect, const SkPaint& paint) override {
+        _param.drawOval(_param.trait, this, &rect, &paint);
+    }
+
+    void onDrawArc(const SkRect& rect, SkScalar startAngle, SkScalar sweepAngle, bool useCenter, const SkPaint& paint) override {
+        _param.drawArc(_param.trait, this, &rect, startAngle, sweepAngle, useCenter, &paint);
+    }
+
+    void onDrawPath(const SkPath& path, const SkPaint& paint) override {
+        _param.drawPath(_param.trait, this, &path, &paint);
+    }
+
+    void onDrawRegion(const SkRegion& region, const SkPaint& paint) override {
+        _param.drawRegion(_param.trait, this, &region, &paint);
+    }
+
+    void onDrawTextBlob(const SkTextBlob* blob, SkScalar x, SkScalar y, const SkPaint& paint) override {
+        _param.drawTextBlob(_param.trait, this, blob, x, y, &paint);
+    }
+
+    // Text drawn with drawString(), drawGlyphs(), etc. is forwarded as a text blob, too.
+    void onDrawGlyphRunList(const sktext::GlyphRunList& glyphRunList, const SkPaint& paint) override {
+        sk_sp<SkTextBlob> blob = sk_ref_sp(glyphRunList.blob());
+        if (!blob) {
+            blob = glyphRunList.makeBlob();
+        }
+        if (blob) {
+            this->onDrawTextBlob(blob.get(), glyphRunList.origin().x(), glyphRunList.origin().y(), paint);
+        }
+    }
+
+    void onDrawImage2(const SkImage* image, SkScalar dx, SkScalar dy, const SkSamplingOptions& sampling, const SkPaint* paint) override {
+        _param.drawImage(_param.trait, this, image, dx, dy, &sampling, paint);
+    }
+
+    void onDrawImageRect2(const SkImage* image, const SkRect& src, const SkRect& dst, const SkSamplingOptions& sampling, const SkPaint* paint, SrcRectConstraint constraint) override {
+        _param.drawImageRect(_param.trait, this, image, &src, &dst, &sampling, paint, constraint);
+    }
+
+    void onDrawVerticesObject(const SkVertices* vertices, SkBlendMode mode, const SkPaint& paint) override {
+        _param.drawVertices(_param.trait, this, vertices, mode, &paint);
+    }
+
+    void onDrawAnnotation(const SkRect& rect, const char key[], SkData* value) override {
+        _param.drawAnnotation(_param.trait, this, &rect, key, value);
+    }
+
+    void onDrawAtlas2(const SkImage*, SkSpan<const SkRSXform>, SkSpan<const SkRect>, SkSpan<const SkColor>, SkBlendMode, const SkSamplingOptions&, const SkRect*, const SkPaint*) override {
+        _param.unsupportedDraw(_param.trait, this, UnsupportedDraw::Atlas);
+    }
+
+    // Also receives drawImageNine().
+    void onDrawImageLattice2(const SkImage*, const Lattice&, const SkRect&, SkFilterMode, const SkPaint*) override {
+        _param.unsupportedDraw(_param.trait, this, UnsupportedDraw::ImageLattice);
+    }
+
+    void onDrawPatch(const SkPoint[12], const SkColor[4], const SkPoint[4], SkBlendMode, const SkPaint&) override {
+        _param.unsupportedDraw(_param.trait, this, UnsupportedDraw::Patch);
+    }
+
+    void onDrawEdgeAAQuad(const SkRect&, const SkPoint[4], QuadAAFlags, const SkColor4f&, SkBlendMode) override {
+        _param.unsupportedDraw(_param.trait, this, UnsupportedDraw::EdgeAAQuad);
+    }
+
+    void onDrawEdgeAAImageSet2(const ImageSetEntry[], int, const SkPoint[], const SkMatrix[], const SkSamplingOptions&, const SkPaint*, SrcRectConstraint) override {
+        _param.unsupportedDraw(_param.trait, this, UnsupportedDraw::EdgeAAImageSet);
+    }
+
+    void onDrawShadowRec(const SkPath&, const SkDrawShadowRec&) override {
+        _param.unsupportedDraw(_param.trait, this, UnsupportedDraw::Shadow);
+    }
+
+    void onDrawMesh(const SkMesh&, sk_sp<SkBlender>, const SkPaint&) override {
+        _param.unsupportedDraw(_param.trait, this, UnsupportedDraw::Mesh);
+    }
+
+    void onDrawBehind(const SkPaint&) override {
+        _param.unsupportedDraw(_param.trait, this, UnsupportedDraw::Behind);
+    }
+
+    void onDrawPicture(const SkPicture* picture, const SkMatrix* matrix, const SkPaint* paint) override {
+        if (!_param.drawPicture(_param.trait, this, picture, matrix, paint)) {
+            // Plays back the picture into this canvas.
+            SkCanvas::onDrawPicture(picture, matrix, paint);
+        }
+    }
+
+    void onDrawDrawable(SkDrawable* drawable, const SkMatrix* matrix) override {
+        if (!_param.drawDrawable(_param.trait, this, drawable, matrix)) {
+            // Draws the drawable into this canvas.
+            SkCanvas::onDrawDrawable(drawable, matrix);
+        }
+    }
+
+private:
+    Param _param;
+};
+
+extern "C" SkCanvas* C_RustCanvas_new(const SkIRect* bounds, const RustCanvas::Param* param) {
+    return new RustCanvas(*bounds, *param);
+}
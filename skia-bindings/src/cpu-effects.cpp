@@ -0,0 +1,257 @@
+#include "bindings.h"
+
+#include "include/core/SkBitmap.h"
+#include "include/core/SkColorFilter.h"
+#include "include/core/SkColorSpace.h"
+#include "include/core/SkImageFilter.h"
+#include "include/core/SkPixmap.h"
+#include "include/core/SkShader.h"
+
+// Effects implemented in Rust are subclasses of Skia's internal effect base classes and run as
+// callbacks in the CPU raster pipeline. Skia does not offer a public extension point for this,
+// so these headers may change with every Skia release.
+#include "src/core/SkColorSpaceXformSteps.h"
+#include "src/core/SkEffectPriv.h"
+#include "src/core/SkImageFilter_Base.h"
+#include "src/core/SkRasterPipeline.h"
+#include "src/core/SkRasterPipelineOpContexts.h"
+#include "src/core/SkSpecialImage.h"
+#include "src/effects/colorfilters/SkColorFilterBase.h"
+#include "src/shaders/SkShaderBase.h"
+
+
+namespace CpuEffect {
+    extern "C" {
+        typedef void (*Drop)(TraitObject);
+        typedef bool (*FilterImage)(TraitObject, const SkMatrix* layerMatrix, const SkPixmap* source, const SkIPoint* sourceOrigin, SkPixmap* output, const SkIPoint* outputOrigin);
+        typedef void (*InputBounds)(TraitObject, const SkMatrix* layerMatrix, const SkIRect* desiredOutput, SkIRect* input);
+        typedef bool (*OutputBounds)(TraitObject, const SkMatrix* layerMatrix, const SkIRect* contentBounds, SkIRect* output);
+        typedef void (*Shade)(TraitObject, const SkPoint* points, SkColor4f* colors, size_t count);
+        typedef void (*FilterColors)(TraitObject, SkColor4f* colors, size_t count);
+    }
+
+    inline SkIRect toIRect(const skif::LayerSpace<SkIRect>& r) {
+        return SkIRect::MakeLTRB(r.left(), r.top(), r.right(), r.bottom());
+    }
+
+    // Calls back into Rust with unpremultiplied sRGB colors. Pipelines that contain callbacks
+    // always run in high precision.
+    struct CallbackCtx : SkRasterPipeline_CallbackCtx {
+        const void* effect;
+    };
+
+    inline void appendToSRGB(const SkStageRec& rec) {
+        rec.fPipeline->append(SkRasterPipelineOp::unpremul);
+        SkColorSpaceXformSteps(rec.fDstCS, kUnpremul_SkAlphaType, sk_srgb_singleton(), kUnpremul_SkAlphaType)
+            .apply(rec.fPipeline);
+    }
+
+    inline void appendFromSRGB(const SkStageRec& rec) {
+        SkColorSpaceXformSteps(sk_srgb_singleton(), kUnpremul_SkAlphaType, rec.fDstCS, kUnpremul_SkAlphaType)
+            .apply(rec.fPipeline);
+        rec.fPipeline->append(SkRasterPipelineOp::premul);
+    }
+}
+
+class RustImageFilter final : public SkImageFilter_Base {
+public:
+    struct Param {
+        TraitObject trait;
+        ::CpuEffect::Drop drop;
+        ::CpuEffect::FilterImage filter;
+        ::CpuEffect::InputBounds inputBounds;
+        ::CpuEffect::OutputBounds outputBounds;
+    };
+
+    RustImageFilter(sk_sp<SkImageFilter> input, const Param& param)
+    : SkImageFilter_Base(&input, 1), _param(param)
+    { }
+
+    ~RustImageFilter() override {
+        _param.drop(_param.trait);
+    }
+
+    // Rust filters can not be serialized.
+    Factory getFactory() const override { return nullptr; }
+    const char* getTypeName() const override { return "RustImageFilter"; }
+
+private:
+    skif::FilterResult onFilterImage(const skif::Context& ctx) const override {
+        skif::FilterResult childOutput =
+            this->getChildOutput(0, ctx.withNewDesiredOutput(this->requiredInput(ctx.mapping(), ctx.desiredOutput())));
+        auto [image, origin] = childOutput.imageAndOffset(ctx);
+
+        sk_sp<SkColorSpace> colorSpace = sk_ref_sp(ctx.colorSpace());
+        SkBitmap source;
+        if (image) {
+            SkBitmap bitmap;
+            // Texture backed images are not supported.
+            if (!SkSpecialImages::AsBitmap(image.get(), &bitmap)) {
+                return {};
+            }
+            if (!source.tryAllocPixels(SkImageInfo::MakeN32Premul(bitmap.dimensions(), colorSpace))
+                || !bitmap.readPixels(source.pixmap())) {
+                return {};
+            }
+        }
+
+        SkIRect outputBounds = ::CpuEffect::toIRect(ctx.desiredOutput());
+        SkBitmap output;
+        if (!output.tryAllocPixels(SkImageInfo::MakeN32Premul(outputBounds.size(), colorSpace))) {
+            return {};
+        }
+        output.eraseColor(SK_ColorTRANSPARENT);
+
+        SkMatrix layerMatrix = SkMatrix(ctx.mapping().layerMatrix());
+        SkPixmap sourcePixmap = source.pixmap();
+        SkPixmap outputPixmap = output.pixmap();
+        SkIPoint sourceOrigin = SkIPoint::Make(origin.x(), origin.y());
+        SkIPoint outputOrigin = outputBounds.topLeft();
+        if (!_param.filter(_param.trait, &layerMatrix, &sourcePixmap, &sourceOrigin, &outputPixmap, &outputOrigin)) {
+            return {};
+        }
+        output.setImmutable();
+
+        return skif::FilterResult(
+            SkSpecialImages::MakeFromRaster(SkIRect::MakeSize(output.dimensions()), output, ctx.surfaceProps()),
+            skif::LayerSpace<SkIPoint>(outputOrigin));
+    }
+
+    skif::LayerSpace<SkIRect> onGetInputLayerBounds(
+        const skif::Mapping& mapping,
+        const skif::LayerSpace<SkIRect>& desiredOutput,
+        std::optional<skif::LayerSpace<SkIRect>> contentBounds) const override {
+        return this->getChildInputLayerBounds(0, mapping, this->requiredInput(mapping, desiredOutput), contentBounds);
+    }
+
+    std::optional<skif::LayerSpace<SkIRect>> onGetOutputLayerBounds(
+        const skif::Mapping& mapping,
+        std::optional<skif::LayerSpace<SkIRect>> contentBounds) const override {
+        auto childOutput = this->getChildOutputLayerBounds(0, mapping, contentBounds);
+        SkMatrix layerMatrix = SkMatrix(mapping.layerMatrix());
+        std::optional<SkIRect> content;
+        if (childOutput) {
+            content = ::CpuEffect::toIRect(*childOutput);
+        }
+        SkIRect output;
+        if (!_param.outputBounds(_param.trait, &layerMatrix, content ? &*content : nullptr, &output)) {
+            return std::nullopt;
+        }
+        return skif::LayerSpace<SkIRect>(output);
+    }
+
+    skif::LayerSpace<SkIRect> requiredInput(
+        const skif::Mapping& mapping, const skif::LayerSpace<SkIRect>& desiredOutput) const {
+        SkMatrix layerMatrix = SkMatrix(mapping.layerMatrix());
+        SkIRect output = ::CpuEffect::toIRect(desiredOutput);
+        SkIRect input;
+        _param.inputBounds(_param.trait, &layerMatrix, &output, &input);
+        return skif::LayerSpace<SkIRect>(input);
+    }
+
+    Param _param;
+};
+
+extern "C" SkImageFilter* C_RustImageFilter_new(SkImageFilter* input, const RustImageFilter::Param* param) {
+    return new RustImageFilter(sp(input), *param);
+}
+
+class RustShader final : public SkShaderBase {
+public:
+    struct Param {
+        TraitObject trait;
+        ::CpuEffect::Drop drop;
+        ::CpuEffect::Shade shade;
+    };
+
+    explicit RustShader(const Param& param)
+    : _param(param)
+    { }
+
+    ~RustShader() override {
+        _param.drop(_param.trait);
+    }
+
+    // There is no shader type for shaders implemented outside of Skia. GPU backends downcast
+    // shaders based on their type, and kEmpty is the only one without state, so GPU draws treat
+    // the shader as empty instead of accessing an unrelated class.
+    ShaderType type() const override { return ShaderType::kEmpty; }
+
+    // Rust shaders can not be serialized.
+    Factory getFactory() const override { return nullptr; }
+    const char* getTypeName() const override { return "RustShader"; }
+
+protected:
+    bool appendStages(const SkStageRec& rec, const SkShaders::MatrixRec& mRec) const override {
+        // Leaves the local coordinates in r and g.
+        if (!mRec.apply(rec).has_value()) {
+            return false;
+        }
+        auto ctx = rec.fAlloc->make<::CpuEffect::CallbackCtx>();
+        ctx->effect = this;
+        ctx->fn = [](SkRasterPipeline_CallbackCtx* self, int active) {
+            auto shader = static_cast<const RustShader*>(static_cast<::CpuEffect::CallbackCtx*>(self)->effect);
+            SkPoint points[SkRasterPipeline_kMaxStride_highp];
+            for (int i = 0; i < active; ++i) {
+                points[i] = SkPoint::Make(self->rgba[4 * i], self->rgba[4 * i + 1]);
+            }
+            shader->_param.shade(shader->_param.trait, points, reinterpret_cast<SkColor4f*>(self->rgba), active);
+        };
+        rec.fPipeline->append(SkRasterPipelineOp::callback, ctx);
+        ::CpuEffect::appendFromSRGB(rec);
+        return true;
+    }
+
+private:
+    Param _param;
+};
+
+extern "C" SkShader* C_RustShader_new(const RustShader::Param* param) {
+    return new RustShader(*param);
+}
+
+class RustColorFilter final : public SkColorFilterBase {
+public:
+    struct Param {
+        TraitObject trait;
+        ::CpuEffect::Drop drop;
+        ::CpuEffect::FilterColors filterColors;
+    };
+
+    explicit RustColorFilter(const Param& param)
+    : _param(param)
+    { }
+
+    ~RustColorFilter() override {
+        _param.drop(_param.trait);
+    }
+
+    // There is no color filter type for color filters implemented outside of Skia. GPU backends
+    // downcast color filters based on their type, and kNoop is the only one without state, so
+    // GPU draws skip the color filter instead of accessing an unrelated class.
+    SkColorFilterBase::Type type() const override { return SkColorFilterBase::Type::kNoop; }
+
+    // Rust color filters can not be serialized.
+    Factory getFactory() const override { return nullptr; }
+    const char* getTypeName() const override { return "RustColorFilter"; }
+
+    bool appendStages(const SkStageRec& rec, bool shaderIsOpaque) const override {
+        ::CpuEffect::appendToSRGB(rec);
+        auto ctx = rec.fAlloc->make<::CpuEffect::CallbackCtx>();
+        ctx->effect = this;
+        ctx->fn = [](SkRasterPipeline_CallbackCtx* self, int active) {
+            auto filter = static_cast<const RustColorFilter*>(static_cast<::CpuEffect::CallbackCtx*>(self)->effect);
+            filter->_param.filterColors(filter->_param.trait, reinterpret_cast<SkColor4f*>(self->rgba), active);
+        };
+        rec.fPipeline->append(SkRasterPipelineOp::callback, ctx);
+        ::CpuEffect::appendFromSRGB(rec);
+        return true;
+    }
+
+private:
+    Param _param;
+};
+
+extern "C" SkColorFilter* C_RustColorFilter_new(const RustColorFilter::Param* param) {
+    return new RustColorFilter(*param);
+}
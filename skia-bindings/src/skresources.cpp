@@ -13,3 +13,26 @@ extern "C" skresources::ImageAsset* C_MultiFrameImageAsset_Make(
 extern "C" RustResourceProvider* C_RustResourceProvider_New(const RustResourceProvider::Param* param) {
     return new RustResourceProvider(*param);
 }
+
+extern "C" SkData* C_RustResourceProvider_load(
+    const RustResourceProvider* self, const char* resourcePath, const char* resourceName)
+{
+    return self->load(resourcePath, resourceName).release();
+}
+
+extern "C" skresources::ResourceProvider* C_CachingResourceProvider_Make(RustResourceProvider* provider) {
+    return skresources::CachingResourceProvider::Make(sp(provider)).release();
+}
+
+extern "C" skresources::ImageAsset* C_ResourceProvider_loadImageAsset(
+    const skresources::ResourceProvider* self,
+    const char* resourcePath,
+    const char* resourceName,
+    const char* resourceId)
+{
+    return self->loadImageAsset(resourcePath, resourceName, resourceId).release();
+}
+
+extern "C" SkFontMgr* C_RustResourceProvider_fontMgr(const RustResourceProvider* self) {
+    return self->fontMgr().release();
+}
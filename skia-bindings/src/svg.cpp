@@ -2,8 +2,15 @@
 #include "rust_resource_provider.h"
 
 #include "include/core/SkCanvas.h"
+#include "include/core/SkPathBuilder.h"
+#include "include/core/SkPathUtils.h"
+#include "include/core/SkPicture.h"
+#include "include/utils/SkNoDrawCanvas.h"
+#include "include/utils/SkPaintFilterCanvas.h"
 #include "include/svg/SkSVGCanvas.h"
 
+#include "modules/skshaper/include/SkShaper_factory.h"
+#include "modules/svg/include/SkSVGAttributeParser.h"
 #include "modules/svg/include/SkSVGCircle.h"
 #include "modules/svg/include/SkSVGClipPath.h"
 #include "modules/svg/include/SkSVGContainer.h"
@@ -49,14 +56,328 @@
 
 #include "include/core/SkStream.h"
 
+// The XML parser Skia's SVG parser is built on, either bundled with Skia or provided by the system.
+#if __has_include("third_party/externals/expat/expat/lib/expat.h")
+    #define XML_STATIC
+    #include "third_party/externals/expat/expat/lib/expat.h"
+#else
+    #include <expat.h>
+#endif
+
+#include <algorithm>
+#include <cstring>
+#include <functional>
+#include <vector>
+
+//
+// Loading
+//
+
+// Receives the elements of a document while it is loaded. Lines and columns are one-based.
+extern "C" struct SvgXmlObserver {
+    void* data;
+    void (*startElement)(
+        void* data, const char* name, const char* const* attributes,
+        size_t offset, size_t line, size_t column);
+    void (*endElement)(void* data);
+    void (*text)(void* data, const char* text, size_t length);
+};
+
+// The error that stopped the XML parser. code is XML_ERROR_NONE if the document is well-formed.
+extern "C" struct SvgXmlError {
+    int code;
+    // The parser was stopped because the document declares an entity, which SkXMLParser rejects.
+    bool entityDeclaration;
+    size_t offset;
+    size_t line;
+    size_t column;
+};
+
+namespace {
+    // Parses the bytes Skia reads a second time, with an expat parser that is configured like the
+    // one of SkXMLParser, and forwards the elements and the first error to a SvgXmlObserver.
+    class XmlObserver {
+    public:
+        explicit XmlObserver(const SvgXmlObserver& observer)
+        : fObserver(observer), fParser(XML_ParserCreate(nullptr)) {
+            XML_SetUserData(fParser, this);
+            XML_SetElementHandler(fParser, StartElement, EndElement);
+            XML_SetCharacterDataHandler(fParser, Text);
+            XML_SetEntityDeclHandler(fParser, EntityDecl);
+        }
+
+        ~XmlObserver() {
+            XML_ParserFree(fParser);
+        }
+
+        void parse(const void* buffer, size_t length, bool isFinal) {
+            if (fError.code != XML_ERROR_NONE) {
+                return;
+            }
+            if (XML_Parse(fParser, static_cast<const char*>(buffer), static_cast<int>(length), isFinal)
+                == XML_STATUS_ERROR) {
+                fError.code = XML_GetErrorCode(fParser);
+                fError.entityDeclaration = fEntityDeclaration;
+                position(&fError.offset, &fError.line, &fError.column);
+            }
+        }
+
+        SvgXmlError finish() {
+            parse(nullptr, 0, true);
+            return fError;
+        }
+
+    private:
+        void position(size_t* offset, size_t* line, size_t* column) const {
+            *offset = static_cast<size_t>(XML_GetCurrentByteIndex(fParser));
+            *line = static_cast<size_t>(XML_GetCurrentLineNumber(fParser));
+            *column = static_cast<size_t>(XML_GetCurrentColumnNumber(fParser)) + 1;
+        }
+
+        static void XMLCALL StartElement(void* data, const XML_Char* name, const XML_Char** attributes) {
+            auto self = static_cast<XmlObserver*>(data);
+            size_t offset, line, column;
+            self->position(&offset, &line, &column);
+            self->fObserver.startElement(self->fObserver.data, name, attributes, offset, line, column);
+        }
+
+        static void XMLCALL EndElement(void* data, const XML_Char*) {
+            auto self = static_cast<XmlObserver*>(data);
+            self->fObserver.endElement(self->fObserver.data);
+        }
+
+        static void XMLCALL Text(void* data, const XML_Char* text, int length) {
+            auto self = static_cast<XmlObserver*>(data);
+            self->fObserver.text(self->fObserver.data, text, static_cast<size_t>(length));
+        }
+
+        // Mirrors SkXMLParser, which stops at entity declarations to prevent entity expansion
+        // attacks.
+        static void XMLCALL EntityDecl(
+            void* data, const XML_Char*, int, const XML_Char*, int, const XML_Char*,
+            const XML_Char*, const XML_Char*, const XML_Char*) {
+            auto self = static_cast<XmlObserver*>(data);
+            self->fEntityDeclaration = true;
+            XML_StopParser(self->fParser, XML_FALSE);
+        }
+
+        SvgXmlObserver fObserver;
+        XML_Parser fParser;
+        bool fEntityDeclaration = false;
+        SvgXmlError fError = {XML_ERROR_NONE, false, 0, 0, 0};
+    };
+
+    // Passes the bytes that are read from a stream to a XmlObserver.
+    class ObservedStream final : public SkStream {
+    public:
+        ObservedStream(SkStream& stream, XmlObserver& observer)
+        : fStream(stream), fObserver(observer) {}
+
+        size_t read(void* buffer, size_t size) override {
+            size_t bytesRead = fStream.read(buffer, size);
+            if (buffer) {
+                fObserver.parse(buffer, bytesRead, false);
+            }
+            return bytesRead;
+        }
+
+        bool isAtEnd() const override {
+            return fStream.isAtEnd();
+        }
+
+    private:
+        SkStream& fStream;
+        XmlObserver& fObserver;
+    };
+
+    // The DOMs are built with this factory, so that the render contexts of the bindings shape text
+    // like the ones of SkSVGDOM.
+    sk_sp<SkShapers::Factory> textShapingFactory() {
+        return SkShapers::Primitive::Factory();
+    }
+}
+
 extern "C" SkSVGDOM* C_SkSVGDOM_MakeFromStream(
     SkStream& stream,
-    RustResourceProvider* provider) 
+    SkFontMgr* fontMgr,
+    skresources::ResourceProvider* resourceProvider,
+    const SvgXmlObserver* observer,
+    SvgXmlError* error)
 {
+    XmlObserver xmlObserver(*observer);
+    ObservedStream observedStream(stream, xmlObserver);
     auto builder = SkSVGDOM::Builder();
-    builder.setFontManager(provider->fontMgr());
-    builder.setResourceProvider(sp(provider));
-    return builder.make(stream).release();
+    builder.setFontManager(sp(fontMgr));
+    builder.setResourceProvider(sp(resourceProvider));
+    builder.setTextShapingFactory(textShapingFactory());
+    auto dom = builder.make(observedStream);
+    *error = xmlObserver.finish();
+    return dom.release();
+}
+
+extern "C" const char* C_SvgXmlError_message(int code) {
+    return XML_ErrorString(static_cast<XML_Error>(code));
+}
+
+extern "C" SkSVGNode* C_SkSVGDOM_findNodeById(SkSVGDOM* self, const char* id) {
+    auto node = self->findNodeById(id);
+    return node ? SkSafeRef(node->get()) : nullptr;
+}
+
+// Makes `id` refer to `node`, or to no node if `node` is null. The id mapper of the DOM can not be
+// extended, ids the document was not parsed with are ignored.
+extern "C" void C_SkSVGDOM_setNodeById(SkSVGDOM* self, const char* id, SkSVGNode* node) {
+    if (auto mapped = self->findNodeById(id)) {
+        *mapped = sk_ref_sp(node);
+    }
+}
+
+//
+// SVG documents referenced by <image> elements
+//
+
+namespace {
+    class SvgImageAsset final : public skresources::ImageAsset {
+    public:
+        explicit SvgImageAsset(sk_sp<SkImage> image) : fImage(std::move(image)) {}
+
+        bool isMultiFrame() override {
+            return false;
+        }
+
+        FrameData getFrameData(float) override {
+            return {fImage, SkSamplingOptions(SkFilterMode::kLinear), SkMatrix::I(), SizeFit::kCenter};
+        }
+
+    private:
+        sk_sp<SkImage> fImage;
+    };
+
+    // Serves the images that stand in for the SVG documents referenced by <image> elements, so
+    // that Skia lays them out like raster images, and forwards everything else.
+    class SvgImageResourceProvider final : public skresources::ResourceProviderProxyBase {
+    public:
+        explicit SvgImageResourceProvider(sk_sp<skresources::ResourceProvider> provider)
+            : ResourceProviderProxyBase(std::move(provider)) {}
+
+        void addImage(const char path[], const char name[], sk_sp<SkImage> image) {
+            fImages.push_back({SkString(path), SkString(name), sk_make_sp<SvgImageAsset>(std::move(image))});
+        }
+
+        sk_sp<skresources::ImageAsset> loadImageAsset(const char path[], const char name[], const char id[]) const override {
+            for (const auto& image : fImages) {
+                if (image.path.equals(path) && image.name.equals(name)) {
+                    return image.asset;
+                }
+            }
+            return ResourceProviderProxyBase::loadImageAsset(path, name, id);
+        }
+
+    private:
+        struct Image {
+            SkString path;
+            SkString name;
+            sk_sp<skresources::ImageAsset> asset;
+        };
+
+        std::vector<Image> fImages;
+    };
+}
+
+extern "C" skresources::ResourceProvider* C_SvgImageResourceProvider_Make(skresources::ResourceProvider* provider) {
+    return new SvgImageResourceProvider(sp(provider));
+}
+
+// `self` must be created by C_SvgImageResourceProvider_Make().
+extern "C" void C_SvgImageResourceProvider_addImage(
+    skresources::ResourceProvider* self, const char* path, const char* name, SkImage* image)
+{
+    static_cast<SvgImageResourceProvider*>(self)->addImage(path, name, sp(image));
+}
+
+//
+// Rendering
+//
+
+namespace {
+    // Draws the pictures of the SVG documents referenced by <image> elements in place of the
+    // images that stand in for them, so that the documents are rendered as vector graphics.
+    class SvgImageCanvas final : public SkPaintFilterCanvas {
+    public:
+        SvgImageCanvas(SkCanvas* canvas, const uint32_t* imageIds, SkPicture* const* pictures, size_t count)
+            : SkPaintFilterCanvas(canvas)
+            , fImageIds(imageIds)
+            , fPictures(pictures)
+            , fCount(count)
+        {}
+
+    protected:
+        bool onFilter(SkPaint&) const override {
+            return true;
+        }
+
+        void onDrawImageRect2(const SkImage* image, const SkRect& src, const SkRect& dst,
+                              const SkSamplingOptions& sampling, const SkPaint* paint,
+                              SrcRectConstraint constraint) override {
+            auto picture = this->picture(image);
+            if (!picture) {
+                SkPaintFilterCanvas::onDrawImageRect2(image, src, dst, sampling, paint, constraint);
+                return;
+            }
+            SkAutoCanvasRestore acr(this, true);
+            this->clipRect(dst, true);
+            this->concat(SkMatrix::RectToRect(src, dst));
+            this->drawPicture(picture, nullptr, paint);
+        }
+
+    private:
+        const SkPicture* picture(const SkImage* image) const {
+            for (size_t i = 0; i < fCount; ++i) {
+                if (fImageIds[i] == image->uniqueID()) {
+                    return fPictures[i];
+                }
+            }
+            return nullptr;
+        }
+
+        const uint32_t* fImageIds;
+        SkPicture* const* fPictures;
+        size_t fCount;
+    };
+}
+
+// Renders the document. `imageIds` are the unique ids of the images that stand in for the SVG
+// documents referenced by <image> elements, and `pictures` the recorded documents.
+extern "C" void C_SkSVGDOM_render(
+    const SkSVGDOM* self,
+    SkCanvas* canvas,
+    const uint32_t* imageIds,
+    SkPicture* const* pictures,
+    size_t count)
+{
+    if (count == 0) {
+        self->render(canvas);
+        return;
+    }
+    SvgImageCanvas imageCanvas(canvas, imageIds, pictures, count);
+    self->render(&imageCanvas);
+}
+
+extern "C" void C_SkSVGDOM_renderNode(
+    const SkSVGDOM* self,
+    SkCanvas* canvas,
+    const char* id,
+    const uint32_t* imageIds,
+    SkPicture* const* pictures,
+    size_t count)
+{
+    SkSVGPresentationContext pctx;
+    if (count == 0) {
+        self->renderNode(canvas, pctx, id);
+        return;
+    }
+    SvgImageCanvas imageCanvas(canvas, imageIds, pictures, count);
+    self->renderNode(&imageCanvas, pctx, id);
 }
 
 extern "C" void C_SkSVGDOM_setContainerSize(SkSVGDOM* self, const SkSize& size){
@@ -341,6 +662,15 @@ extern "C" void C_SkSVGColor_Construct1(SkSVGColor* uninitialized, const SkSVGCo
     new(uninitialized)SkSVGColor(color);
 }
 
+extern "C" bool C_SkSVGAttributeParser_parseColor(const char* value, SkColor* color) {
+    auto result = SkSVGAttributeParser::parse<SkSVGColorType>(value);
+    if (!result) {
+        return false;
+    }
+    *color = *result;
+    return true;
+}
+
 // Hacky way to access the SkSVGContainer::fChildren property (should be safe)
 class SkSVGContainerAccessor : public SkSVGContainer {
     public:
@@ -351,12 +681,43 @@ class SkSVGContainerAccessor : public SkSVGContainer {
         const sk_sp<SkSVGNode>* children() const {
             return fChildren.data();
         }
+
+        using Children = decltype(fChildren);
+
+        void swapChildren(Children& children) {
+            fChildren.swap(children);
+        }
+
+        // fChildren does not support ordered insertion and removal, so we rotate the elements.
+        void insertChild(size_t index, sk_sp<SkSVGNode> node) {
+            fChildren.push_back(std::move(node));
+            for (size_t i = fChildren.size() - 1; i > index; --i) {
+                std::swap(fChildren[i], fChildren[i - 1]);
+            }
+        }
+
+        SkSVGNode* removeChild(size_t index) {
+            for (size_t i = index; i + 1 < fChildren.size(); ++i) {
+                std::swap(fChildren[i], fChildren[i + 1]);
+            }
+            auto node = fChildren.back().release();
+            fChildren.pop_back();
+            return node;
+        }
 };
 
 extern "C" void C_SkSVGContainer_appendChild(SkSVGContainer* self, SkSVGNode* node) {
     self->appendChild(sk_sp<SkSVGNode>(node));
 }
 
+extern "C" void C_SkSVGContainer_insertChild(SkSVGContainer* self, size_t index, SkSVGNode* node) {
+    static_cast<SkSVGContainerAccessor*>(self)->insertChild(index, sp(node));
+}
+
+extern "C" SkSVGNode* C_SkSVGContainer_removeChild(SkSVGContainer* self, size_t index) {
+    return static_cast<SkSVGContainerAccessor*>(self)->removeChild(index);
+}
+
 extern "C" int C_SkSVGContainer_childrenCount(const SkSVGContainer& self) {
     return static_cast<const SkSVGContainerAccessor&>(self).childrenCount();
 }
@@ -366,6 +727,290 @@ extern "C" const sk_sp<SkSVGNode>* C_SkSVGContainer_children(const SkSVGContaine
     return static_cast<const SkSVGContainerAccessor&>(self).children();
 }
 
+extern "C" void C_SkSVGTextContainer_appendChild(SkSVGTextContainer* self, SkSVGNode* node) {
+    self->appendChild(sk_sp<SkSVGNode>(node));
+}
+
+//
+// Node geometry and hit testing
+//
+
+namespace {
+    // Exposes SkSVGNode::onPrepareToRender(), which applies the presentation attributes and the
+    // transformation of a node to a render context.
+    class SkSVGNodeAccessor : public SkSVGNode {
+    public:
+        static bool prepareToRender(const SkSVGNode* node, SkSVGRenderContext* ctx) {
+            return static_cast<const SkSVGNodeAccessor*>(node)->onPrepareToRender(ctx);
+        }
+    };
+
+    // Limits the nesting of <use> elements that reference each other.
+    constexpr int kMaxRenderDepth = 256;
+
+    bool isGraphicsElement(const SkSVGNode* node) {
+        switch (node->tag()) {
+            case SkSVGTag::kCircle:
+            case SkSVGTag::kEllipse:
+            case SkSVGTag::kImage:
+            case SkSVGTag::kLine:
+            case SkSVGTag::kPath:
+            case SkSVGTag::kPolygon:
+            case SkSVGTag::kPolyline:
+            case SkSVGTag::kRect:
+            case SkSVGTag::kText:
+                return true;
+            default:
+                return false;
+        }
+    }
+
+    template <typename Visit>
+    bool walkRenderTree(const SkSVGNode* node, const SkSVGRenderContext& parentCtx, int depth, Visit& visit);
+
+    // Walks `children` and their rendered descendants, see walkRenderTree().
+    template <typename Visit>
+    bool walkChildren(
+        const sk_sp<SkSVGNode>* children, int count, const SkSVGRenderContext& ctx, int depth, Visit& visit)
+    {
+        for (int i = 0; i < count; ++i) {
+            if (walkRenderTree(children[i].get(), ctx, depth, visit)) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    // Walks the rendered nodes in paint order and calls `visit` with every node, the render
+    // context of its parent, and its depth. Stops and returns true as soon as `visit` returns true.
+    template <typename Visit>
+    bool walkRenderTree(const SkSVGNode* node, const SkSVGRenderContext& parentCtx, int depth, Visit& visit) {
+        if (visit(node, parentCtx, depth)) {
+            return true;
+        }
+        if (depth >= kMaxRenderDepth) {
+            return false;
+        }
+
+        switch (node->tag()) {
+            case SkSVGTag::kSvg:
+            case SkSVGTag::kG: {
+                SkSVGRenderContext ctx(parentCtx, node);
+                if (!SkSVGNodeAccessor::prepareToRender(node, &ctx)) {
+                    return false;
+                }
+                auto container = static_cast<const SkSVGContainerAccessor*>(
+                    static_cast<const SkSVGContainer*>(node));
+                return walkChildren(
+                    container->children(), container->childrenCount(), ctx, depth + 1, visit);
+            }
+            case SkSVGTag::kUse: {
+                SkSVGRenderContext ctx(parentCtx, node);
+                if (!SkSVGNodeAccessor::prepareToRender(node, &ctx)) {
+                    return false;
+                }
+                auto ref = ctx.findNodeById(static_cast<const SkSVGUse*>(node)->getHref());
+                return ref && walkRenderTree(ref.get(), ctx, depth + 1, visit);
+            }
+            default:
+                return false;
+        }
+    }
+
+    // Stands in for the children of the root while the DOM is rendered for a query, and runs the
+    // query with the render context it is rendered with.
+    class QueryProbe final : public SkSVGContainer {
+    public:
+        using Query = std::function<void(const SkSVGRenderContext&)>;
+
+        explicit QueryProbe(Query query)
+            : SkSVGContainer(SkSVGTag::kG)
+            , fQuery(std::move(query)) {}
+
+    protected:
+        void onRender(const SkSVGRenderContext& ctx) const override {
+            fQuery(ctx);
+        }
+
+    private:
+        Query fQuery;
+    };
+
+    // Calls `f` with the root of the document, its children, and the render context
+    // SkSVGDOM::render() sets up for the children, which refers to the font manager, the resource
+    // provider and the ids of the DOM. The DOM is rendered to a canvas that does not draw, with
+    // the children of the root temporarily replaced by a QueryProbe.
+    template <typename F>
+    void withQueryContext(const SkSVGDOM* dom, F&& f) {
+        SkSVGSVG* root = dom->getRoot().get();
+        if (!root) {
+            return;
+        }
+        auto container = static_cast<SkSVGContainerAccessor*>(static_cast<SkSVGContainer*>(root));
+        SkSVGContainerAccessor::Children children;
+        children.push_back(sk_make_sp<QueryProbe>([&](const SkSVGRenderContext& ctx) {
+            f(root, children.data(), children.size(), ctx);
+        }));
+        container->swapChildren(children);
+
+        auto size = dom->containerSize();
+        SkNoDrawCanvas canvas(SkScalarCeilToInt(size.width()), SkScalarCeilToInt(size.height()));
+        dom->render(&canvas);
+
+        container->swapChildren(children);
+    }
+
+    // The outlines of a graphics element in document coordinates.
+    struct Outline {
+        SkPath fill;
+        SkPath stroke;
+        bool filled = false;
+    };
+
+    Outline graphicsElementOutline(const SkSVGNode* node, const SkSVGRenderContext& parentCtx) {
+        Outline outline;
+        SkSVGRenderContext ctx(parentCtx, node);
+        if (!SkSVGNodeAccessor::prepareToRender(node, &ctx)) {
+            return outline;
+        }
+        const SkMatrix& toDocument = ctx.canvas()->getTotalMatrix();
+
+        if (node->tag() == SkSVGTag::kImage) {
+            auto image = static_cast<const SkSVGImage*>(node);
+            auto rect = ctx.lengthContext().resolveRect(
+                image->getX(), image->getY(), image->getWidth(), image->getHeight());
+            outline.fill = SkPath::Rect(rect).makeTransform(toDocument);
+            outline.filled = true;
+            return outline;
+        }
+
+        // asPath() includes the transformation of the node and its clip path.
+        outline.fill = node->asPath(parentCtx).makeTransform(parentCtx.canvas()->getTotalMatrix());
+        outline.filled = ctx.fillPaint().has_value();
+
+        SkMatrix toLocal;
+        if (auto strokePaint = ctx.strokePaint(); strokePaint && toDocument.invert(&toLocal)) {
+            SkPathBuilder builder;
+            auto local = outline.fill.makeTransform(toLocal);
+            if (skpathutils::FillPathWithPaint(local, *strokePaint, &builder, nullptr, toDocument)) {
+                outline.stroke = builder.detach().makeTransform(toDocument);
+            }
+        }
+        return outline;
+    }
+}
+
+// Computes the outline of `target` and the bounds of its painted area, including strokes, in
+// document coordinates. For containers, the outlines of all rendered descendants are combined.
+// Returns false and leaves `path` uninitialized if `target` is not rendered.
+extern "C" bool C_SkSVGDOM_nodeGeometry(
+    const SkSVGDOM* self,
+    const SkSVGNode* target,
+    SkPath* path,
+    SkRect* renderedBounds)
+{
+    bool found = false;
+    withQueryContext(self, [&](const SkSVGSVG* root, const sk_sp<SkSVGNode>* children, int count,
+                               const SkSVGRenderContext& ctx) {
+        SkPathBuilder builder;
+        SkRect bounds = SkRect::MakeEmpty();
+        auto collect = [&](const SkSVGNode* node, const SkSVGRenderContext& parentCtx, int) {
+            if (isGraphicsElement(node)) {
+                auto outline = graphicsElementOutline(node, parentCtx);
+                builder.addPath(outline.fill);
+                bounds.join(outline.fill.computeTightBounds());
+                bounds.join(outline.stroke.computeTightBounds());
+            }
+            return false;
+        };
+        auto findTarget = [&](const SkSVGNode* node, const SkSVGRenderContext& parentCtx, int depth) {
+            if (node != target) {
+                return false;
+            }
+            walkRenderTree(node, parentCtx, depth, collect);
+            found = true;
+            return true;
+        };
+        if (root == target) {
+            walkChildren(children, count, ctx, 1, collect);
+            found = true;
+        } else {
+            walkChildren(children, count, ctx, 1, findTarget);
+        }
+        if (found) {
+            new (path) SkPath(builder.detach());
+            *renderedBounds = bounds;
+        }
+    });
+    return found;
+}
+
+// Returns the topmost graphics element whose fill or stroke contains `point`, and sets `id` to
+// the id of that element or its nearest ancestor that has one. `ids` and `nodes` are the ids of
+// the nodes of the tree.
+extern "C" SkSVGNode* C_SkSVGDOM_nodeAt(
+    const SkSVGDOM* self,
+    const char* const* ids,
+    SkSVGNode* const* nodes,
+    size_t idCount,
+    SkPoint point,
+    SkString* id)
+{
+    std::vector<const SkSVGNode*> ancestors;
+    std::vector<const SkSVGNode*> hit;
+    withQueryContext(self, [&](const SkSVGSVG* root, const sk_sp<SkSVGNode>* children, int count,
+                               const SkSVGRenderContext& ctx) {
+        auto hitTest = [&](const SkSVGNode* node, const SkSVGRenderContext& parentCtx, int depth) {
+            ancestors.resize(depth);
+            ancestors.push_back(node);
+            if (isGraphicsElement(node)) {
+                auto outline = graphicsElementOutline(node, parentCtx);
+                if ((outline.filled && outline.fill.contains(point.x(), point.y())) ||
+                    outline.stroke.contains(point.x(), point.y())) {
+                    hit = ancestors;
+                }
+            }
+            return false;
+        };
+        ancestors.push_back(root);
+        walkChildren(children, count, ctx, 1, hitTest);
+    });
+    if (hit.empty()) {
+        return nullptr;
+    }
+
+    for (auto node = hit.rbegin(); node != hit.rend(); ++node) {
+        auto end = nodes + idCount;
+        auto found = std::find(nodes, end, *node);
+        if (found != end) {
+            *id = SkString(ids[found - nodes]);
+            break;
+        }
+    }
+    return SkRef(const_cast<SkSVGNode*>(hit.back()));
+}
+
+// Builds the image filter of `filter` for an element with the bounding box `objectBounds`. The
+// bounding box resolves the filter region and the primitive subregions that use
+// objectBoundingBox units.
+extern "C" SkImageFilter* C_SkSVGDOM_filterImageFilter(
+    const SkSVGDOM* self, const SkSVGFilter* filter, const SkRect& objectBounds)
+{
+    sk_sp<SkImageFilter> imageFilter;
+    withQueryContext(self, [&](const SkSVGSVG*, const sk_sp<SkSVGNode>*, int,
+                               const SkSVGRenderContext& ctx) {
+        // A rect with the object bounds stands in for the element the filter is applied to.
+        auto element = SkSVGRect::Make();
+        element->setX(SkSVGLength(objectBounds.x()));
+        element->setY(SkSVGLength(objectBounds.y()));
+        element->setWidth(SkSVGLength(objectBounds.width()));
+        element->setHeight(SkSVGLength(objectBounds.height()));
+        SkSVGRenderContext elementCtx(ctx, element.get());
+        imageFilter = filter->buildFilterDAG(elementCtx);
+    });
+    return imageFilter.release();
+}
+
 extern "C" void C_SkSVGTransformableNode_setTransform(SkSVGTransformableNode* self, const SkMatrix& value) {
     self->setTransform(value);
 }
@@ -374,6 +1019,72 @@ extern "C" SkSVGTag C_SkSVGNode_tag(const SkSVGNode& self) {
     return self.tag();
 }
 
+extern "C" bool C_SkSVGNode_parseAndSetAttribute(SkSVGNode* self, const char* name, const char* value) {
+    return self->parseAndSetAttribute(name, value);
+}
+
+// Mirrors the element factories of SkSVGDOM.cpp
+extern "C" SkSVGNode* C_SkSVGNode_MakeFromElementName(const char* name) {
+    using Factory = sk_sp<SkSVGNode> (*)();
+    static const struct {
+        const char* name;
+        Factory factory;
+    } factories[] = {
+        { "a"                  , []() -> sk_sp<SkSVGNode> { return SkSVGG::Make();                   }},
+        { "circle"             , []() -> sk_sp<SkSVGNode> { return SkSVGCircle::Make();              }},
+        { "clipPath"           , []() -> sk_sp<SkSVGNode> { return SkSVGClipPath::Make();            }},
+        { "defs"               , []() -> sk_sp<SkSVGNode> { return SkSVGDefs::Make();                }},
+        { "ellipse"            , []() -> sk_sp<SkSVGNode> { return SkSVGEllipse::Make();             }},
+        { "feBlend"            , []() -> sk_sp<SkSVGNode> { return SkSVGFeBlend::Make();             }},
+        { "feColorMatrix"      , []() -> sk_sp<SkSVGNode> { return SkSVGFeColorMatrix::Make();       }},
+        { "feComponentTransfer", []() -> sk_sp<SkSVGNode> { return SkSVGFeComponentTransfer::Make(); }},
+        { "feComposite"        , []() -> sk_sp<SkSVGNode> { return SkSVGFeComposite::Make();         }},
+        { "feDiffuseLighting"  , []() -> sk_sp<SkSVGNode> { return SkSVGFeDiffuseLighting::Make();   }},
+        { "feDisplacementMap"  , []() -> sk_sp<SkSVGNode> { return SkSVGFeDisplacementMap::Make();   }},
+        { "feDistantLight"     , []() -> sk_sp<SkSVGNode> { return SkSVGFeDistantLight::Make();      }},
+        { "feFlood"            , []() -> sk_sp<SkSVGNode> { return SkSVGFeFlood::Make();             }},
+        { "feFuncA"            , []() -> sk_sp<SkSVGNode> { return SkSVGFeFunc::MakeFuncA();         }},
+        { "feFuncB"            , []() -> sk_sp<SkSVGNode> { return SkSVGFeFunc::MakeFuncB();         }},
+        { "feFuncG"            , []() -> sk_sp<SkSVGNode> { return SkSVGFeFunc::MakeFuncG();         }},
+        { "feFuncR"            , []() -> sk_sp<SkSVGNode> { return SkSVGFeFunc::MakeFuncR();         }},
+        { "feGaussianBlur"     , []() -> sk_sp<SkSVGNode> { return SkSVGFeGaussianBlur::Make();      }},
+        { "feImage"            , []() -> sk_sp<SkSVGNode> { return SkSVGFeImage::Make();             }},
+        { "feMerge"            , []() -> sk_sp<SkSVGNode> { return SkSVGFeMerge::Make();             }},
+        { "feMergeNode"        , []() -> sk_sp<SkSVGNode> { return SkSVGFeMergeNode::Make();         }},
+        { "feMorphology"       , []() -> sk_sp<SkSVGNode> { return SkSVGFeMorphology::Make();        }},
+        { "feOffset"           , []() -> sk_sp<SkSVGNode> { return SkSVGFeOffset::Make();            }},
+        { "fePointLight"       , []() -> sk_sp<SkSVGNode> { return SkSVGFePointLight::Make();        }},
+        { "feSpecularLighting" , []() -> sk_sp<SkSVGNode> { return SkSVGFeSpecularLighting::Make();  }},
+        { "feSpotLight"        , []() -> sk_sp<SkSVGNode> { return SkSVGFeSpotLight::Make();         }},
+        { "feTurbulence"       , []() -> sk_sp<SkSVGNode> { return SkSVGFeTurbulence::Make();        }},
+        { "filter"             , []() -> sk_sp<SkSVGNode> { return SkSVGFilter::Make();              }},
+        { "g"                  , []() -> sk_sp<SkSVGNode> { return SkSVGG::Make();                   }},
+        { "image"              , []() -> sk_sp<SkSVGNode> { return SkSVGImage::Make();               }},
+        { "line"               , []() -> sk_sp<SkSVGNode> { return SkSVGLine::Make();                }},
+        { "linearGradient"     , []() -> sk_sp<SkSVGNode> { return SkSVGLinearGradient::Make();      }},
+        { "mask"               , []() -> sk_sp<SkSVGNode> { return SkSVGMask::Make();                }},
+        { "path"               , []() -> sk_sp<SkSVGNode> { return SkSVGPath::Make();                }},
+        { "pattern"            , []() -> sk_sp<SkSVGNode> { return SkSVGPattern::Make();             }},
+        { "polygon"            , []() -> sk_sp<SkSVGNode> { return SkSVGPoly::MakePolygon();         }},
+        { "polyline"           , []() -> sk_sp<SkSVGNode> { return SkSVGPoly::MakePolyline();        }},
+        { "radialGradient"     , []() -> sk_sp<SkSVGNode> { return SkSVGRadialGradient::Make();      }},
+        { "rect"               , []() -> sk_sp<SkSVGNode> { return SkSVGRect::Make();                }},
+        { "stop"               , []() -> sk_sp<SkSVGNode> { return SkSVGStop::Make();                }},
+        { "svg"                , []() -> sk_sp<SkSVGNode> { return SkSVGSVG::Make(SkSVGSVG::Type::kInner); }},
+        { "text"               , []() -> sk_sp<SkSVGNode> { return SkSVGText::Make();                }},
+        { "textPath"           , []() -> sk_sp<SkSVGNode> { return SkSVGTextPath::Make();            }},
+        { "tspan"              , []() -> sk_sp<SkSVGNode> { return SkSVGTSpan::Make();               }},
+        { "use"                , []() -> sk_sp<SkSVGNode> { return SkSVGUse::Make();                 }},
+    };
+
+    for (const auto& f : factories) {
+        if (!strcmp(f.name, name)) {
+            return f.factory().release();
+        }
+    }
+    return nullptr;
+}
+
 extern "C" void C_SkSVGIRI_destruct(SkSVGIRI* self) {
     self->~SkSVGIRI();
 }
@@ -44,8 +44,9 @@ mod image_info;
 mod m44;
 mod mask_filter;
 pub mod matrix;
-mod mesh;
+pub mod mesh;
 mod milestone;
+mod overdraw_canvas;
 pub mod paint;
 pub mod path;
 pub mod path_builder;
@@ -55,6 +56,7 @@ pub mod path_measure;
 pub mod path_types;
 pub mod path_utils;
 mod picture;
+mod picture_op;
 pub mod picture_recorder;
 mod pixel_ref;
 mod pixmap;
@@ -124,7 +126,9 @@ pub use image_info::*;
 pub use m44::*;
 pub use mask_filter::*;
 pub use matrix::Matrix;
+pub use mesh::{Mesh, MeshSpecification};
 pub use milestone::*;
+pub use overdraw_canvas::*;
 pub use paint::Paint;
 pub use tile_mode::*;
 // We keep these around for the time being.
@@ -140,6 +144,7 @@ pub use path_iter::*;
 pub use path_measure::PathMeasure;
 pub use path_types::*;
 pub use picture::*;
+pub use picture_op::*;
 pub use picture_recorder::PictureRecorder;
 pub use pixel_ref::*;
 pub use pixmap::*;
@@ -1,9 +1,46 @@
-use crate::prelude::*;
-use skia_bindings::{SkBBHFactory, SkBBoxHierarchy};
+use crate::{Rect, interop::VecSink, prelude::*};
+use skia_bindings::{self as sb, SkBBHFactory, SkBBoxHierarchy, SkRefCntBase};
+use std::fmt;
 
-// TODO: complete the wrapper
+/// A spatial index over the bounds of a picture's ops, see [`crate::Picture::bbh()`].
 pub type BBoxHierarchy = RCHandle<SkBBoxHierarchy>;
 
+impl NativeRefCountedBase for SkBBoxHierarchy {
+    type Base = SkRefCntBase;
+}
+
+impl fmt::Debug for BBoxHierarchy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BBoxHierarchy")
+            .field("bytes_used", &self.bytes_used())
+            .finish()
+    }
+}
+
+impl BBoxHierarchy {
+    /// Returns the indices of the ops whose bounds intersect `query`, in ascending order.
+    ///
+    /// The result includes the save, restore, and matrix ops that enclose the drawing ops found.
+    pub fn search(&self, query: impl AsRef<Rect>) -> Vec<usize> {
+        let mut results = Vec::new();
+        let mut set_results = |ops: &[i32]| {
+            results.extend(ops.iter().map(|&op| op as usize));
+        };
+        unsafe {
+            sb::C_SkBBoxHierarchy_search(
+                self.native(),
+                query.as_ref().native(),
+                VecSink::new(&mut set_results).native_mut(),
+            )
+        }
+        results
+    }
+
+    pub fn bytes_used(&self) -> usize {
+        unsafe { sb::C_SkBBoxHierarchy_bytesUsed(self.native()) }
+    }
+}
+
 // TODO: complete the wrapper
 pub type BBHFactory = Handle<SkBBHFactory>;
 
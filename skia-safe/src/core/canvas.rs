@@ -12,10 +12,10 @@ use crate::gpu;
 use crate::graphite;
 use crate::{Arc, ColorSpace};
 use crate::{
-    Bitmap, BlendMode, ClipOp, Color, Color4f, Data, Drawable, FilterMode, Font, GlyphId, IPoint,
-    IRect, ISize, Image, ImageFilter, ImageInfo, M44, Matrix, Paint, Path, Picture, Pixmap, Point,
-    QuickReject, RRect, RSXform, Rect, Region, SamplingOptions, Shader, Surface, SurfaceProps,
-    TextBlob, TextEncoding, TileMode, Vector, Vertices, prelude::*, scalar,
+    Bitmap, BlendMode, Blender, ClipOp, Color, Color4f, Data, Drawable, FilterMode, Font, GlyphId,
+    IPoint, IRect, ISize, Image, ImageFilter, ImageInfo, M44, Matrix, Mesh, Paint, Path, Picture,
+    Pixmap, Point, QuickReject, RRect, RSXform, Rect, Region, SamplingOptions, Shader, Surface,
+    SurfaceProps, TextBlob, TextEncoding, TileMode, Vector, Vertices, prelude::*, scalar,
 };
 
 pub use lattice::Lattice;
@@ -2033,6 +2033,28 @@ impl Canvas {
         self
     }
 
+    /// Draws a [`Mesh`] using clip and [`Matrix`].
+    ///
+    /// The color returned by the fragment program of the mesh is blended with the shader or
+    /// color of `paint` by `blender`, where the mesh color is the destination. If `blender` is
+    /// `None`, [`BlendMode::Modulate`] is used.
+    pub fn draw_mesh(
+        &self,
+        mesh: &Mesh,
+        blender: impl Into<Option<Blender>>,
+        paint: &Paint,
+    ) -> &Self {
+        unsafe {
+            sb::C_SkCanvas_drawMesh(
+                self.native_mut(),
+                mesh.native(),
+                blender.into().into_ptr_or_null(),
+                paint.native(),
+            )
+        }
+        self
+    }
+
     /// Draws a Coons patch: the interpolation of four cubics with shared corners,
     /// associating a color, and optionally a texture [`Point`], with each corner.
     ///
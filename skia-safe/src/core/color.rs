@@ -210,6 +210,7 @@ bitflags! {
 // TODO: SkRGBA4f
 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Color4f {
     pub r: f32,
@@ -20,6 +20,7 @@ pub struct ColorSpacePrimaries {
 native_transmutable!(SkColorSpacePrimaries, ColorSpacePrimaries);
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct ColorSpaceTransferFn {
     pub g: f32,
@@ -16,6 +16,7 @@ use skia_bindings as sb;
 /// Note: By default, Skia operates with the assumption of a little-Endian system. The bit patterns
 /// shown in the documentation assume LE byte order.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum ColorType {
     /// Unknown or unrepresentable as an SkColorType.
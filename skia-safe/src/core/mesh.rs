@@ -1 +1,617 @@
-// TODO
+use std::{fmt, mem, ptr};
+
+use skia_bindings::{
+    self as sb, SkMesh, SkMesh_IndexBuffer, SkMesh_VertexBuffer, SkMeshSpecification,
+    SkMeshSpecification_Attribute, SkNVRefCnt, SkRefCntBase,
+};
+
+use crate::{
+    AlphaType, ColorSpace, Data, Rect, interop,
+    prelude::*,
+    runtime_effect::{Child, ChildPtr, Uniform},
+};
+
+pub use sb::SkMeshSpecification_Attribute_Type as AttributeType;
+variant_name!(AttributeType::UByte4_unorm);
+
+pub use sb::SkMeshSpecification_Varying_Type as VaryingType;
+variant_name!(VaryingType::Half4);
+
+pub use sb::SkMesh_Mode as Mode;
+variant_name!(Mode::TriangleStrip);
+
+/// A vertex attribute of a [`MeshSpecification`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Attribute {
+    pub ty: AttributeType,
+    /// The offset of the attribute in the vertex, must be a multiple of
+    /// [`MeshSpecification::OFFSET_ALIGNMENT`].
+    pub offset: usize,
+    pub name: String,
+}
+
+impl Attribute {
+    pub fn new(ty: AttributeType, offset: usize, name: impl Into<String>) -> Self {
+        Self {
+            ty,
+            offset,
+            name: name.into(),
+        }
+    }
+
+    fn from_native(attribute: &SkMeshSpecification_Attribute) -> Self {
+        Self::new(
+            attribute.type_,
+            attribute.offset,
+            interop::String::from_native_ref(&attribute.name).as_str(),
+        )
+    }
+}
+
+/// A value passed from the vertex program to the fragment program of a [`MeshSpecification`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Varying {
+    pub ty: VaryingType,
+    pub name: String,
+}
+
+impl Varying {
+    pub fn new(ty: VaryingType, name: impl Into<String>) -> Self {
+        Self {
+            ty,
+            name: name.into(),
+        }
+    }
+}
+
+/// Describes the vertex layout and the SkSL vertex and fragment programs of a [`Mesh`].
+///
+/// The vertex program declares the function `Varyings main(const Attributes attributes)` and the
+/// fragment program `float2 main(const Varyings varyings)` or
+/// `float2 main(const Varyings varyings, out (half4|float4) color)`, where the structs
+/// `Attributes` and `Varyings` are generated from the attributes and varyings of the
+/// specification. The returned `float2` is the local position used to evaluate the shader of
+/// the paint. `Varyings` always contains `float2 position`, which is the position of the vertex.
+pub type MeshSpecification = RCHandle<SkMeshSpecification>;
+unsafe_send_sync!(MeshSpecification);
+require_base_type!(SkMeshSpecification, SkNVRefCnt);
+
+impl NativeRefCounted for SkMeshSpecification {
+    fn _ref(&self) {
+        unsafe { sb::C_SkMeshSpecification_ref(self) }
+    }
+
+    fn _unref(&self) {
+        unsafe { sb::C_SkMeshSpecification_unref(self) }
+    }
+
+    fn unique(&self) -> bool {
+        unsafe { sb::C_SkMeshSpecification_unique(self) }
+    }
+}
+
+impl fmt::Debug for MeshSpecification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MeshSpecification")
+            .field("attributes", &self.attributes())
+            .field("stride", &self.stride())
+            .field("uniform_size", &self.uniform_size())
+            .field("uniforms", &self.uniforms())
+            .field("children", &self.children())
+            .field("color_space", &self.color_space())
+            .finish()
+    }
+}
+
+impl MeshSpecification {
+    pub const MAX_STRIDE: usize = 1024;
+    pub const MAX_ATTRIBUTES: usize = 8;
+    pub const STRIDE_ALIGNMENT: usize = 4;
+    pub const OFFSET_ALIGNMENT: usize = 4;
+    pub const MAX_VARYINGS: usize = 6;
+
+    /// Creates a [`MeshSpecification`] and returns the SkSL compilation errors if it fails.
+    ///
+    /// `color_space` is the color space of the color returned by the fragment program and
+    /// defaults to sRGB. `alpha_type` is its alpha type and defaults to [`AlphaType::Premul`].
+    pub fn make(
+        attributes: &[Attribute],
+        vertex_stride: usize,
+        varyings: &[Varying],
+        vs: impl AsRef<str>,
+        fs: impl AsRef<str>,
+        color_space: impl Into<Option<ColorSpace>>,
+        alpha_type: impl Into<Option<AlphaType>>,
+    ) -> Result<MeshSpecification, String> {
+        let attribute_types: Vec<_> = attributes.iter().map(|a| a.ty).collect();
+        let attribute_offsets: Vec<_> = attributes.iter().map(|a| a.offset).collect();
+        let attribute_names: Vec<_> = attributes
+            .iter()
+            .map(|a| interop::String::from_str(&a.name))
+            .collect();
+        let varying_types: Vec<_> = varyings.iter().map(|v| v.ty).collect();
+        let varying_names: Vec<_> = varyings
+            .iter()
+            .map(|v| interop::String::from_str(&v.name))
+            .collect();
+        let vs = interop::String::from_str(vs);
+        let fs = interop::String::from_str(fs);
+        let color_space = color_space.into().unwrap_or_else(ColorSpace::new_srgb);
+        let mut error = interop::String::default();
+        MeshSpecification::from_ptr(unsafe {
+            sb::C_SkMeshSpecification_Make(
+                attribute_types.as_ptr(),
+                attribute_offsets.as_ptr(),
+                attribute_names.native().as_ptr(),
+                attributes.len(),
+                vertex_stride,
+                varying_types.as_ptr(),
+                varying_names.native().as_ptr(),
+                varyings.len(),
+                vs.native(),
+                fs.native(),
+                color_space.into_ptr(),
+                alpha_type.into().unwrap_or(AlphaType::Premul),
+                error.native_mut(),
+            )
+        })
+        .ok_or_else(|| error.to_string())
+    }
+
+    pub fn attributes(&self) -> Vec<Attribute> {
+        let mut count = 0;
+        let ptr = unsafe { sb::C_SkMeshSpecification_attributes(self.native(), &mut count) };
+        unsafe { safer::from_raw_parts(ptr, count) }
+            .iter()
+            .map(Attribute::from_native)
+            .collect()
+    }
+
+    pub fn uniform_size(&self) -> usize {
+        unsafe { sb::C_SkMeshSpecification_uniformSize(self.native()) }
+    }
+
+    pub fn uniforms(&self) -> &[Uniform] {
+        unsafe {
+            let mut count = 0;
+            let ptr = sb::C_SkMeshSpecification_uniforms(self.native(), &mut count);
+            safer::from_raw_parts(Uniform::from_native_ptr(ptr), count)
+        }
+    }
+
+    pub fn children(&self) -> &[Child] {
+        unsafe {
+            let mut count = 0;
+            let ptr = sb::C_SkMeshSpecification_children(self.native(), &mut count);
+            safer::from_raw_parts(Child::from_native_ptr(ptr), count)
+        }
+    }
+
+    pub fn find_uniform(&self, name: impl AsRef<str>) -> Option<&Uniform> {
+        let name = name.as_ref().as_bytes();
+        unsafe {
+            sb::C_SkMeshSpecification_findUniform(self.native(), name.as_ptr() as _, name.len())
+        }
+        .into_non_null()
+        .map(|ptr| Uniform::from_native_ref(unsafe { ptr.as_ref() }))
+    }
+
+    pub fn find_child(&self, name: impl AsRef<str>) -> Option<&Child> {
+        let name = name.as_ref().as_bytes();
+        unsafe {
+            sb::C_SkMeshSpecification_findChild(self.native(), name.as_ptr() as _, name.len())
+        }
+        .into_non_null()
+        .map(|ptr| Child::from_native_ref(unsafe { ptr.as_ref() }))
+    }
+
+    pub fn find_attribute(&self, name: impl AsRef<str>) -> Option<Attribute> {
+        let name = name.as_ref().as_bytes();
+        unsafe {
+            sb::C_SkMeshSpecification_findAttribute(self.native(), name.as_ptr() as _, name.len())
+                .as_ref()
+        }
+        .map(Attribute::from_native)
+    }
+
+    pub fn stride(&self) -> usize {
+        unsafe { sb::C_SkMeshSpecification_stride(self.native()) }
+    }
+
+    pub fn color_space(&self) -> Option<ColorSpace> {
+        ColorSpace::from_ptr(unsafe { sb::C_SkMeshSpecification_colorSpace(self.native()) })
+    }
+}
+
+/// The vertices of a [`Mesh`] in the layout described by its [`MeshSpecification`].
+pub type VertexBuffer = RCHandle<SkMesh_VertexBuffer>;
+unsafe_send_sync!(VertexBuffer);
+
+impl NativeRefCountedBase for SkMesh_VertexBuffer {
+    type Base = SkRefCntBase;
+}
+
+impl fmt::Debug for VertexBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VertexBuffer")
+            .field("size", &self.size())
+            .finish()
+    }
+}
+
+impl VertexBuffer {
+    /// Creates a CPU backed vertex buffer with a copy of `vertices`.
+    pub fn new<T: Copy>(vertices: &[T]) -> Option<Self> {
+        Self::from_ptr(unsafe {
+            sb::C_SkMeshes_MakeVertexBuffer(vertices.as_ptr() as _, mem::size_of_val(vertices))
+        })
+    }
+
+    /// The size of the buffer in bytes.
+    pub fn size(&self) -> usize {
+        unsafe { sb::C_SkMesh_VertexBuffer_size(self.native()) }
+    }
+}
+
+/// The 16 bit vertex indices of an indexed [`Mesh`].
+pub type IndexBuffer = RCHandle<SkMesh_IndexBuffer>;
+unsafe_send_sync!(IndexBuffer);
+
+impl NativeRefCountedBase for SkMesh_IndexBuffer {
+    type Base = SkRefCntBase;
+}
+
+impl fmt::Debug for IndexBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IndexBuffer")
+            .field("size", &self.size())
+            .finish()
+    }
+}
+
+impl IndexBuffer {
+    /// Creates a CPU backed index buffer with a copy of `indices`.
+    pub fn new(indices: &[u16]) -> Option<Self> {
+        Self::from_ptr(unsafe {
+            sb::C_SkMeshes_MakeIndexBuffer(indices.as_ptr() as _, mem::size_of_val(indices))
+        })
+    }
+
+    /// The size of the buffer in bytes.
+    pub fn size(&self) -> usize {
+        unsafe { sb::C_SkMesh_IndexBuffer_size(self.native()) }
+    }
+}
+
+/// A triangle mesh with custom vertex attributes, drawn with [`crate::Canvas::draw_mesh()`].
+pub type Mesh = Handle<SkMesh>;
+unsafe_send_sync!(Mesh);
+
+impl NativeDrop for SkMesh {
+    fn drop(&mut self) {
+        unsafe { sb::C_SkMesh_destruct(self) }
+    }
+}
+
+impl NativeClone for SkMesh {
+    fn clone(&self) -> Self {
+        construct(|mesh| unsafe { sb::C_SkMesh_CopyConstruct(mesh, self) })
+    }
+}
+
+impl Default for Mesh {
+    /// Creates an invalid mesh.
+    fn default() -> Self {
+        Self::construct(|mesh| unsafe { sb::C_SkMesh_Construct(mesh) })
+    }
+}
+
+impl fmt::Debug for Mesh {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mesh")
+            .field("spec", &self.spec())
+            .field("mode", &self.mode())
+            .field("vertex_buffer", &self.vertex_buffer())
+            .field("vertex_offset", &self.vertex_offset())
+            .field("vertex_count", &self.vertex_count())
+            .field("index_buffer", &self.index_buffer())
+            .field("index_offset", &self.index_offset())
+            .field("index_count", &self.index_count())
+            .field("bounds", &self.bounds())
+            .field("is_valid", &self.is_valid())
+            .finish()
+    }
+}
+
+impl Mesh {
+    /// Creates a mesh of `vertex_count` vertices starting at the byte offset `vertex_offset` in
+    /// `vertex_buffer`.
+    ///
+    /// `uniforms` must match the uniforms of `spec` in size and layout, and `children` its
+    /// children. `bounds` must contain the positions returned by the vertex program.
+    ///
+    /// Returns the reason if the mesh is invalid.
+    #[allow(clippy::too_many_arguments)]
+    pub fn make(
+        spec: &MeshSpecification,
+        mode: Mode,
+        vertex_buffer: &VertexBuffer,
+        vertex_count: usize,
+        vertex_offset: usize,
+        uniforms: impl Into<Option<Data>>,
+        children: &[ChildPtr],
+        bounds: impl AsRef<Rect>,
+    ) -> Result<Mesh, String> {
+        Self::make_internal(
+            spec,
+            mode,
+            vertex_buffer,
+            vertex_count,
+            vertex_offset,
+            None,
+            uniforms.into(),
+            children,
+            bounds.as_ref(),
+        )
+    }
+
+    /// Creates a mesh like [`Self::make()`] with `index_count` indices starting at the byte
+    /// offset `index_offset` in `index_buffer`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn make_indexed(
+        spec: &MeshSpecification,
+        mode: Mode,
+        vertex_buffer: &VertexBuffer,
+        vertex_count: usize,
+        vertex_offset: usize,
+        index_buffer: &IndexBuffer,
+        index_count: usize,
+        index_offset: usize,
+        uniforms: impl Into<Option<Data>>,
+        children: &[ChildPtr],
+        bounds: impl AsRef<Rect>,
+    ) -> Result<Mesh, String> {
+        Self::make_internal(
+            spec,
+            mode,
+            vertex_buffer,
+            vertex_count,
+            vertex_offset,
+            Some((index_buffer, index_count, index_offset)),
+            uniforms.into(),
+            children,
+            bounds.as_ref(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn make_internal(
+        spec: &MeshSpecification,
+        mode: Mode,
+        vertex_buffer: &VertexBuffer,
+        vertex_count: usize,
+        vertex_offset: usize,
+        indices: Option<(&IndexBuffer, usize, usize)>,
+        uniforms: Option<Data>,
+        children: &[ChildPtr],
+        bounds: &Rect,
+    ) -> Result<Mesh, String> {
+        let mut children: Vec<_> = children.iter().map(|child| child.native()).collect();
+        let children_ptr = children
+            .first_mut()
+            .map(|c| &mut **c as *mut _)
+            .unwrap_or(ptr::null_mut());
+        let (index_buffer, index_count, index_offset) = match indices {
+            Some((buffer, count, offset)) => (buffer.clone().into_ptr(), count, offset),
+            None => (ptr::null_mut(), 0, 0),
+        };
+        let mut error = interop::String::default();
+        Mesh::try_construct(|mesh| unsafe {
+            sb::C_SkMesh_Make(
+                spec.clone().into_ptr(),
+                mode,
+                vertex_buffer.clone().into_ptr(),
+                vertex_count,
+                vertex_offset,
+                index_buffer,
+                index_count,
+                index_offset,
+                uniforms.into_ptr_or_null(),
+                children_ptr,
+                children.len(),
+                bounds.native(),
+                mesh,
+                error.native_mut(),
+            )
+        })
+        .ok_or_else(|| error.to_string())
+    }
+
+    pub fn spec(&self) -> Option<MeshSpecification> {
+        MeshSpecification::from_ptr(unsafe { sb::C_SkMesh_refSpec(self.native()) })
+    }
+
+    pub fn mode(&self) -> Mode {
+        unsafe { sb::C_SkMesh_mode(self.native()) }
+    }
+
+    pub fn vertex_buffer(&self) -> Option<VertexBuffer> {
+        VertexBuffer::from_ptr(unsafe { sb::C_SkMesh_refVertexBuffer(self.native()) })
+    }
+
+    pub fn vertex_offset(&self) -> usize {
+        unsafe { sb::C_SkMesh_vertexOffset(self.native()) }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        unsafe { sb::C_SkMesh_vertexCount(self.native()) }
+    }
+
+    pub fn index_buffer(&self) -> Option<IndexBuffer> {
+        IndexBuffer::from_ptr(unsafe { sb::C_SkMesh_refIndexBuffer(self.native()) })
+    }
+
+    pub fn index_offset(&self) -> usize {
+        unsafe { sb::C_SkMesh_indexOffset(self.native()) }
+    }
+
+    pub fn index_count(&self) -> usize {
+        unsafe { sb::C_SkMesh_indexCount(self.native()) }
+    }
+
+    pub fn uniforms(&self) -> Option<Data> {
+        Data::from_ptr(unsafe { sb::C_SkMesh_refUniforms(self.native()) })
+    }
+
+    pub fn bounds(&self) -> Rect {
+        let mut bounds = Rect::default();
+        unsafe { sb::C_SkMesh_bounds(self.native(), bounds.native_mut()) };
+        bounds
+    }
+
+    pub fn is_valid(&self) -> bool {
+        unsafe { sb::C_SkMesh_isValid(self.native()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Attribute, AttributeType, IndexBuffer, Mode, Varying, VaryingType, VertexBuffer};
+    use crate::{
+        AlphaType, BlendMode, Blender, Color, Data, Mesh, MeshSpecification, Paint, Rect, surfaces,
+    };
+
+    const VS: &str = r#"
+        uniform float2 offset;
+        Varyings main(const Attributes attributes) {
+            Varyings varyings;
+            varyings.position = attributes.position + offset;
+            varyings.color = attributes.color;
+            return varyings;
+        }
+    "#;
+
+    const FS: &str = r#"
+        float2 main(const Varyings varyings, out half4 color) {
+            color = varyings.color;
+            return varyings.position;
+        }
+    "#;
+
+    fn specification() -> MeshSpecification {
+        MeshSpecification::make(
+            &[
+                Attribute::new(AttributeType::Float2, 0, "position"),
+                Attribute::new(AttributeType::UByte4_unorm, 8, "color"),
+            ],
+            12,
+            &[Varying::new(VaryingType::Half4, "color")],
+            VS,
+            FS,
+            None,
+            AlphaType::Premul,
+        )
+        .unwrap()
+    }
+
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+        color: [u8; 4],
+    }
+
+    #[test]
+    fn specification_reflects_layout() {
+        let spec = specification();
+        assert_eq!(spec.stride(), 12);
+        assert_eq!(spec.attributes().len(), 2);
+        assert_eq!(
+            spec.find_attribute("color"),
+            Some(Attribute::new(AttributeType::UByte4_unorm, 8, "color"))
+        );
+        assert_eq!(spec.uniform_size(), 8);
+        assert!(spec.find_uniform("offset").is_some());
+    }
+
+    #[test]
+    fn compile_errors_are_returned() {
+        let error = MeshSpecification::make(
+            &[Attribute::new(AttributeType::Float2, 0, "position")],
+            8,
+            &[],
+            "Varyings main(const Attributes attributes) { return undefined; }",
+            FS,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(!error.is_empty());
+    }
+
+    #[test]
+    fn draw_indexed_mesh() {
+        let spec = specification();
+        let red = [255, 0, 0, 255];
+        let vertices = [
+            Vertex {
+                position: [0.0, 0.0],
+                color: red,
+            },
+            Vertex {
+                position: [8.0, 0.0],
+                color: red,
+            },
+            Vertex {
+                position: [8.0, 8.0],
+                color: red,
+            },
+            Vertex {
+                position: [0.0, 8.0],
+                color: red,
+            },
+        ];
+        let vertex_buffer = VertexBuffer::new(&vertices).unwrap();
+        assert_eq!(vertex_buffer.size(), 48);
+        let index_buffer = IndexBuffer::new(&[0, 1, 2, 0, 2, 3]).unwrap();
+        let uniforms = Data::new_copy(&[0f32.to_ne_bytes(), 0f32.to_ne_bytes()].concat());
+        let mesh = Mesh::make_indexed(
+            &spec,
+            Mode::Triangles,
+            &vertex_buffer,
+            4,
+            0,
+            &index_buffer,
+            6,
+            0,
+            uniforms,
+            &[],
+            Rect::new(0.0, 0.0, 8.0, 8.0),
+        )
+        .unwrap();
+        assert!(mesh.is_valid());
+        assert_eq!(mesh.index_count(), 6);
+
+        let mut surface = surfaces::raster_n32_premul((8, 8)).unwrap();
+        surface.canvas().clear(Color::WHITE);
+        surface
+            .canvas()
+            .draw_mesh(&mesh, Blender::mode(BlendMode::Dst), &Paint::default());
+        let image = surface.image_snapshot();
+        assert_eq!(image.peek_pixels().unwrap().get_color((4, 4)), Color::RED);
+
+        assert!(
+            Mesh::make(
+                &spec,
+                Mode::Triangles,
+                &vertex_buffer,
+                4,
+                0,
+                None,
+                &[],
+                Rect::new(0.0, 0.0, 8.0, 8.0),
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::{fmt, ops::Deref};
+
+use skia_bindings as sb;
+
+use crate::{
+    Canvas, Color, ISize, Image, ImageInfo, OwnedCanvas, Picture, overdraw_color_filter, surfaces,
+};
+
+/// A [`Canvas`] that counts the draws per pixel instead of drawing.
+///
+/// Every draw call increments the alpha channel of the pixels it covers in the canvas it wraps,
+/// which is usually an alpha8 canvas. The counts can be turned into a heatmap with
+/// [`overdraw_color_filter::heatmap()`].
+pub struct OverdrawCanvas<'a>(OwnedCanvas<'a>);
+
+impl Deref for OverdrawCanvas<'_> {
+    type Target = Canvas;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Debug for OverdrawCanvas<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OverdrawCanvas").field(&self.0).finish()
+    }
+}
+
+impl<'a> OverdrawCanvas<'a> {
+    /// Creates an [`OverdrawCanvas`] that counts the draws into `canvas`.
+    pub fn new(canvas: &'a Canvas) -> Self {
+        Self(
+            Canvas::own_from_native_ptr(unsafe { sb::C_SkOverdrawCanvas_new(canvas.native_mut()) })
+                .unwrap(),
+        )
+    }
+}
+
+impl Picture {
+    /// Plays back the picture into an [`OverdrawCanvas`] and returns the draws per pixel as an
+    /// alpha8 [`Image`] of the given size.
+    pub fn overdraw_counts(&self, size: impl Into<ISize>) -> Option<Image> {
+        let mut surface = surfaces::raster(&ImageInfo::new_a8(size), None, None)?;
+        OverdrawCanvas::new(surface.canvas()).draw_picture(self, None, None);
+        Some(surface.image_snapshot())
+    }
+
+    /// Plays back the picture into an [`OverdrawCanvas`] and returns a heatmap of the draws per
+    /// pixel. If `colors` is `None`, [`overdraw_color_filter::DEFAULT_COLORS`] are used.
+    pub fn overdraw_heatmap(
+        &self,
+        size: impl Into<ISize>,
+        colors: Option<&[Color; overdraw_color_filter::NUM_COLORS]>,
+    ) -> Option<Image> {
+        let counts = self.overdraw_counts(size)?;
+        overdraw_color_filter::heatmap(
+            &counts,
+            colors.unwrap_or(&overdraw_color_filter::DEFAULT_COLORS),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Paint, PictureRecorder, Rect, overdraw_color_filter::DEFAULT_COLORS};
+
+    #[test]
+    fn counts_overlapping_draws() {
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(Rect::new(0.0, 0.0, 4.0, 4.0), false);
+        let paint = Paint::default();
+        canvas.draw_rect(Rect::new(0.0, 0.0, 4.0, 4.0), &paint);
+        canvas.draw_rect(Rect::new(0.0, 0.0, 2.0, 2.0), &paint);
+        canvas.draw_rect(Rect::new(0.0, 0.0, 1.0, 1.0), &paint);
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+        let counts = picture.overdraw_counts((4, 4)).unwrap();
+        let pixmap = counts.peek_pixels().unwrap();
+        assert_eq!((pixmap.get_alpha_f((0, 0)) * 255.0).round(), 3.0);
+        assert_eq!((pixmap.get_alpha_f((1, 1)) * 255.0).round(), 2.0);
+        assert_eq!((pixmap.get_alpha_f((3, 3)) * 255.0).round(), 1.0);
+
+        let heatmap = picture.overdraw_heatmap((4, 4), None).unwrap();
+        let pixmap = heatmap.peek_pixels().unwrap();
+        assert_eq!(pixmap.get_color((3, 3)), DEFAULT_COLORS[1]);
+        assert_eq!(pixmap.get_color((0, 0)).a(), DEFAULT_COLORS[3].a());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::Blender;
 use crate::{
-    BlendMode, Color, Color4f, ColorFilter, ColorSpace, ImageFilter, MaskFilter, PathEffect,
+    BlendMode, Color, Color4f, ColorFilter, ColorSpace, ImageFilter, MaskFilter, PathEffect, Rect,
     Shader, prelude::*, scalar,
 };
 use core::fmt;
@@ -305,6 +305,29 @@ impl Paint {
     pub fn nothing_to_draw(&self) -> bool {
         unsafe { self.native().nothingToDraw() }
     }
+
+    /// Returns `true` if [`Self::compute_fast_bounds()`] can compute the bounds of drawing
+    /// with this paint. This is not the case for paints with some effects, like certain path
+    /// effects.
+    pub fn can_compute_fast_bounds(&self) -> bool {
+        unsafe { sb::C_SkPaint_canComputeFastBounds(self.native()) }
+    }
+
+    /// Returns the conservative bounds of drawing geometry with the bounds `orig` with this
+    /// paint, including strokes, mask filters and image filters.
+    ///
+    /// Only valid if [`Self::can_compute_fast_bounds()`] returns `true`.
+    pub fn compute_fast_bounds(&self, orig: impl AsRef<Rect>) -> Rect {
+        let mut bounds = Rect::default();
+        unsafe {
+            sb::C_SkPaint_computeFastBounds(
+                self.native(),
+                orig.as_ref().native(),
+                bounds.native_mut(),
+            )
+        };
+        bounds
+    }
 }
 
 #[test]
@@ -1,6 +1,10 @@
-use crate::{Canvas, Data, FilterMode, Matrix, Rect, Shader, TileMode, prelude::*};
+use crate::{BBoxHierarchy, Canvas, Data, FilterMode, Matrix, Rect, Shader, TileMode, prelude::*};
 use skia_bindings::{self as sb, SkPicture, SkRefCntBase};
-use std::fmt;
+use std::{
+    ffi::c_void,
+    fmt,
+    ops::{Bound, RangeBounds},
+};
 
 pub type Picture = RCHandle<SkPicture>;
 unsafe_send_sync!(Picture);
@@ -35,12 +39,71 @@ impl Picture {
         })
     }
 
-    // TODO: AbortCallback and the function that use it.
-
     pub fn playback(&self, canvas: &Canvas) {
         unsafe { sb::C_SkPicture_playback(self.native(), canvas.native_mut()) }
     }
 
+    /// Replays the picture like [`Self::playback()`], but calls `abort` before each op. Playback
+    /// stops as soon as `abort` returns `true`.
+    ///
+    /// Stopping early does not restore the saves made by the ops that were already replayed, so
+    /// it is advisable to surround the call with [`Canvas::save()`] and
+    /// [`Canvas::restore_to_count()`].
+    pub fn playback_with_callback(&self, canvas: &Canvas, mut abort: impl FnMut() -> bool) {
+        unsafe extern "C" fn abort_trampoline<F: FnMut() -> bool>(data: *mut c_void) -> bool {
+            unsafe { (*(data as *mut F))() }
+        }
+
+        unsafe {
+            sb::C_SkPicture_playbackWithCallback(
+                self.native(),
+                canvas.native_mut(),
+                &mut abort as *mut _ as *mut c_void,
+                Some(abort_trampoline::<_>),
+            )
+        }
+    }
+
+    /// Replays the ops in `range` only.
+    ///
+    /// The ops are replayed in a save / restore block relative to the canvas's current matrix.
+    /// State changes of the ops before the range are not replayed, so a range should start at a
+    /// position where the picture's matrix and clip are at their initial values, for example at
+    /// the beginning of a [`Canvas::save()`] block.
+    ///
+    /// The op indices are the same as the ones returned by [`BBoxHierarchy::search()`].
+    ///
+    /// Skia does not offer this in its public API. It is implemented with the internal
+    /// `SkBigPicture`, so its behavior may change with future Skia versions.
+    pub fn playback_range(&self, canvas: &Canvas, range: impl RangeBounds<usize>) {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let stop = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => usize::MAX,
+        };
+        let start: i32 = start.try_into().unwrap_or(i32::MAX);
+        let stop: i32 = stop.try_into().unwrap_or(i32::MAX);
+        if start >= stop {
+            return;
+        }
+        unsafe { sb::C_SkPicture_partialPlayback(self.native(), canvas.native_mut(), start, stop) }
+    }
+
+    /// Returns the bounding box hierarchy of the picture's ops.
+    ///
+    /// Only available if the picture was recorded with `use_bbh` set in
+    /// [`crate::PictureRecorder::begin_recording()`] and consists of more than one op.
+    ///
+    /// Like [`Self::playback_range()`], this relies on Skia's internal `SkBigPicture`.
+    pub fn bbh(&self) -> Option<BBoxHierarchy> {
+        BBoxHierarchy::from_ptr(unsafe { sb::C_SkPicture_bbh(self.native()) })
+    }
+
     pub fn cull_rect(&self) -> Rect {
         Rect::construct(|r| unsafe { sb::C_SkPicture_cullRect(self.native(), r) })
     }
@@ -107,3 +170,68 @@ impl Picture {
         .unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Paint, Picture, PictureRecorder, Rect, surfaces};
+
+    fn three_rects(use_bbh: bool) -> Picture {
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(Rect::from_wh(100.0, 100.0), use_bbh);
+        let mut paint = Paint::default();
+        paint.set_color(Color::RED);
+        canvas.draw_rect(Rect::new(0.0, 0.0, 10.0, 10.0), &paint);
+        canvas.draw_rect(Rect::new(50.0, 50.0, 60.0, 60.0), &paint);
+        canvas.draw_rect(Rect::new(90.0, 90.0, 100.0, 100.0), &paint);
+        recorder.finish_recording_as_picture(None).unwrap()
+    }
+
+    fn drawn(draw: impl FnOnce(&crate::Canvas)) -> [bool; 3] {
+        let mut surface = surfaces::raster_n32_premul((100, 100)).unwrap();
+        draw(surface.canvas());
+        let pixmap = surface.peek_pixels().unwrap();
+        [(5, 5), (55, 55), (95, 95)].map(|p| pixmap.get_color(p) == Color::RED)
+    }
+
+    #[test]
+    fn playback_with_callback_aborts() {
+        let picture = three_rects(false);
+        let mut calls = 0;
+        let drawn = drawn(|canvas| {
+            picture.playback_with_callback(canvas, || {
+                calls += 1;
+                calls > 2
+            })
+        });
+        assert_eq!(drawn, [true, true, false]);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn playback_range_replays_the_range_only() {
+        let picture = three_rects(false);
+        assert_eq!(
+            drawn(|canvas| picture.playback_range(canvas, 1..2)),
+            [false, true, false]
+        );
+        assert_eq!(
+            drawn(|canvas| picture.playback_range(canvas, 1..)),
+            [false, true, true]
+        );
+        assert_eq!(
+            drawn(|canvas| picture.playback_range(canvas, 2..1)),
+            [false, false, false]
+        );
+    }
+
+    #[test]
+    fn bbh_search_finds_ops() {
+        assert!(three_rects(false).bbh().is_none());
+
+        let picture = three_rects(true);
+        let bbh = picture.bbh().unwrap();
+        assert_eq!(bbh.search(Rect::new(45.0, 45.0, 65.0, 65.0)), [1]);
+        assert_eq!(bbh.search(Rect::new(0.0, 0.0, 100.0, 100.0)), [0, 1, 2]);
+        assert!(bbh.search(Rect::new(20.0, 20.0, 30.0, 30.0)).is_empty());
+    }
+}
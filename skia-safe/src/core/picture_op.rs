@@ -0,0 +1,564 @@
+use crate::{
+    BlendMode, Canvas, ClipOp, Data, IRect, Image, M44, Paint, Path, Picture, Point, RRect, Rect,
+    Region, RoundOut, SamplingOptions, Shader, TextBlob, Vertices,
+    canvas::{PointMode, SaveLayerFlags, SrcRectConstraint},
+    scalar,
+    utils::{CanvasBackend, CanvasState},
+};
+
+/// A drawing operation recorded in a [`Picture`], see [`Picture::ops()`].
+#[derive(Clone, Debug)]
+pub enum PictureOp {
+    Save,
+    SaveLayer {
+        bounds: Option<Rect>,
+        paint: Option<Paint>,
+        flags: SaveLayerFlags,
+    },
+    Restore,
+    Concat(M44),
+    SetMatrix(M44),
+    ClipRect {
+        rect: Rect,
+        op: ClipOp,
+        anti_alias: bool,
+    },
+    ClipRRect {
+        rrect: RRect,
+        op: ClipOp,
+        anti_alias: bool,
+    },
+    ClipPath {
+        path: Path,
+        op: ClipOp,
+        anti_alias: bool,
+    },
+    ClipShader {
+        shader: Shader,
+        op: ClipOp,
+    },
+    /// `device_region` is in device coordinates.
+    ClipRegion {
+        device_region: Region,
+        op: ClipOp,
+    },
+    DrawPaint(Paint),
+    DrawPoints {
+        mode: PointMode,
+        points: Vec<Point>,
+        paint: Paint,
+    },
+    DrawRect {
+        rect: Rect,
+        paint: Paint,
+    },
+    DrawRRect {
+        rrect: RRect,
+        paint: Paint,
+    },
+    DrawDRRect {
+        outer: RRect,
+        inner: RRect,
+        paint: Paint,
+    },
+    DrawOval {
+        oval: Rect,
+        paint: Paint,
+    },
+    DrawArc {
+        oval: Rect,
+        start_angle: scalar,
+        sweep_angle: scalar,
+        use_center: bool,
+        paint: Paint,
+    },
+    DrawPath {
+        path: Path,
+        paint: Paint,
+    },
+    DrawRegion {
+        region: Region,
+        paint: Paint,
+    },
+    DrawTextBlob {
+        blob: TextBlob,
+        origin: Point,
+        paint: Paint,
+    },
+    DrawImage {
+        image: Image,
+        left_top: Point,
+        sampling: SamplingOptions,
+        paint: Option<Paint>,
+    },
+    DrawImageRect {
+        image: Image,
+        src: Rect,
+        dst: Rect,
+        sampling: SamplingOptions,
+        paint: Option<Paint>,
+        constraint: SrcRectConstraint,
+    },
+    DrawVertices {
+        vertices: Vertices,
+        mode: BlendMode,
+        paint: Paint,
+    },
+    DrawAnnotation {
+        rect: Rect,
+        key: String,
+        value: Option<Data>,
+    },
+}
+
+impl PictureOp {
+    /// Returns `true` for all operations that draw, including annotations.
+    pub fn is_draw(&self) -> bool {
+        !matches!(
+            self,
+            PictureOp::Save
+                | PictureOp::SaveLayer { .. }
+                | PictureOp::Restore
+                | PictureOp::Concat(_)
+                | PictureOp::SetMatrix(_)
+                | PictureOp::ClipRect { .. }
+                | PictureOp::ClipRRect { .. }
+                | PictureOp::ClipPath { .. }
+                | PictureOp::ClipShader { .. }
+                | PictureOp::ClipRegion { .. }
+        )
+    }
+}
+
+/// A [`PictureOp`] together with the canvas state it was drawn with.
+#[derive(Clone, Debug)]
+pub struct RecordedOp {
+    pub op: PictureOp,
+    /// The local to device matrix at the time of the operation. For [`PictureOp::Concat`] and
+    /// [`PictureOp::SetMatrix`], this is the resulting matrix.
+    pub matrix: M44,
+    /// The conservative device space bounds of a drawing operation, including the effects of
+    /// its paint, and limited to the clip. Empty if the operation is clipped out, and `None` for
+    /// operations that do not draw.
+    pub bounds: Option<Rect>,
+}
+
+impl Picture {
+    /// Plays back the picture and returns its operations.
+    ///
+    /// Nested pictures and drawables are expanded into their operations. Saves that are restored
+    /// without changing the matrix or the clip are not reported. Patches, atlases, lattices,
+    /// meshes, and shadows are not reported.
+    pub fn ops(&self) -> Vec<RecordedOp> {
+        let mut recorder = OpRecorder::default();
+        let bounds: IRect = self.cull_rect().round_out();
+        {
+            let canvas = Canvas::from_backend_with_bounds(bounds, &mut recorder);
+            self.playback(&canvas);
+        }
+        recorder.ops
+    }
+}
+
+#[derive(Default)]
+struct OpRecorder {
+    ops: Vec<RecordedOp>,
+}
+
+impl OpRecorder {
+    fn push(&mut self, state: &CanvasState, op: PictureOp) {
+        self.ops.push(RecordedOp {
+            op,
+            matrix: state.local_to_device(),
+            bounds: None,
+        })
+    }
+
+    /// Pushes a drawing operation. `local_bounds` is `None` if the operation fills the clip.
+    fn push_draw(
+        &mut self,
+        state: &CanvasState,
+        op: PictureOp,
+        local_bounds: Option<Rect>,
+        paint: Option<&Paint>,
+    ) {
+        let clip = state
+            .device_clip_bounds()
+            .map(Rect::from)
+            .unwrap_or_else(Rect::new_empty);
+
+        let local_bounds = match (local_bounds, paint) {
+            (Some(bounds), Some(paint)) if paint.can_compute_fast_bounds() => {
+                Some(paint.compute_fast_bounds(bounds))
+            }
+            (Some(_), Some(_)) => None,
+            (bounds, None) => bounds,
+            (None, Some(_)) => None,
+        };
+        let bounds = match local_bounds {
+            Some(bounds) => {
+                let (bounds, _) = state.local_to_device_as_3x3().map_rect(bounds);
+                Rect::intersect(&bounds, &clip).unwrap_or_else(Rect::new_empty)
+            }
+            None => clip,
+        };
+
+        self.ops.push(RecordedOp {
+            op,
+            matrix: state.local_to_device(),
+            bounds: Some(bounds),
+        })
+    }
+}
+
+impl CanvasBackend for OpRecorder {
+    fn will_save(&mut self, state: &CanvasState) {
+        self.push(state, PictureOp::Save)
+    }
+
+    fn on_save_layer(
+        &mut self,
+        state: &CanvasState,
+        bounds: Option<&Rect>,
+        paint: Option<&Paint>,
+        flags: SaveLayerFlags,
+    ) {
+        self.push(
+            state,
+            PictureOp::SaveLayer {
+                bounds: bounds.copied(),
+                paint: paint.cloned(),
+                flags,
+            },
+        )
+    }
+
+    fn will_restore(&mut self, state: &CanvasState) {
+        self.push(state, PictureOp::Restore)
+    }
+
+    fn did_concat(&mut self, state: &CanvasState, matrix: &M44) {
+        self.push(state, PictureOp::Concat(matrix.clone()))
+    }
+
+    fn did_set_matrix(&mut self, state: &CanvasState, matrix: &M44) {
+        self.push(state, PictureOp::SetMatrix(matrix.clone()))
+    }
+
+    fn on_clip_rect(&mut self, state: &CanvasState, rect: &Rect, op: ClipOp, anti_alias: bool) {
+        self.push(
+            state,
+            PictureOp::ClipRect {
+                rect: *rect,
+                op,
+                anti_alias,
+            },
+        )
+    }
+
+    fn on_clip_rrect(&mut self, state: &CanvasState, rrect: &RRect, op: ClipOp, anti_alias: bool) {
+        self.push(
+            state,
+            PictureOp::ClipRRect {
+                rrect: *rrect,
+                op,
+                anti_alias,
+            },
+        )
+    }
+
+    fn on_clip_path(&mut self, state: &CanvasState, path: &Path, op: ClipOp, anti_alias: bool) {
+        self.push(
+            state,
+            PictureOp::ClipPath {
+                path: path.clone(),
+                op,
+                anti_alias,
+            },
+        )
+    }
+
+    fn on_clip_shader(&mut self, state: &CanvasState, shader: &Shader, op: ClipOp) {
+        self.push(
+            state,
+            PictureOp::ClipShader {
+                shader: shader.clone(),
+                op,
+            },
+        )
+    }
+
+    fn on_clip_region(&mut self, state: &CanvasState, device_region: &Region, op: ClipOp) {
+        self.push(
+            state,
+            PictureOp::ClipRegion {
+                device_region: device_region.clone(),
+                op,
+            },
+        )
+    }
+
+    fn on_draw_paint(&mut self, state: &CanvasState, paint: &Paint) {
+        self.push_draw(state, PictureOp::DrawPaint(paint.clone()), None, None)
+    }
+
+    fn on_draw_points(
+        &mut self,
+        state: &CanvasState,
+        mode: PointMode,
+        points: &[Point],
+        paint: &Paint,
+    ) {
+        let bounds = Rect::from_bounds(points);
+        self.push_draw(
+            state,
+            PictureOp::DrawPoints {
+                mode,
+                points: points.to_vec(),
+                paint: paint.clone(),
+            },
+            bounds,
+            Some(paint),
+        )
+    }
+
+    fn on_draw_rect(&mut self, state: &CanvasState, rect: &Rect, paint: &Paint) {
+        self.push_draw(
+            state,
+            PictureOp::DrawRect {
+                rect: *rect,
+                paint: paint.clone(),
+            },
+            Some(*rect),
+            Some(paint),
+        )
+    }
+
+    fn on_draw_rrect(&mut self, state: &CanvasState, rrect: &RRect, paint: &Paint) {
+        self.push_draw(
+            state,
+            PictureOp::DrawRRect {
+                rrect: *rrect,
+                paint: paint.clone(),
+            },
+            Some(*rrect.rect()),
+            Some(paint),
+        )
+    }
+
+    fn on_draw_drrect(&mut self, state: &CanvasState, outer: &RRect, inner: &RRect, paint: &Paint) {
+        self.push_draw(
+            state,
+            PictureOp::DrawDRRect {
+                outer: *outer,
+                inner: *inner,
+                paint: paint.clone(),
+            },
+            Some(*outer.rect()),
+            Some(paint),
+        )
+    }
+
+    fn on_draw_oval(&mut self, state: &CanvasState, oval: &Rect, paint: &Paint) {
+        self.push_draw(
+            state,
+            PictureOp::DrawOval {
+                oval: *oval,
+                paint: paint.clone(),
+            },
+            Some(*oval),
+            Some(paint),
+        )
+    }
+
+    fn on_draw_arc(
+        &mut self,
+        state: &CanvasState,
+        oval: &Rect,
+        start_angle: scalar,
+        sweep_angle: scalar,
+        use_center: bool,
+        paint: &Paint,
+    ) {
+        self.push_draw(
+            state,
+            PictureOp::DrawArc {
+                oval: *oval,
+                start_angle,
+                sweep_angle,
+                use_center,
+                paint: paint.clone(),
+            },
+            Some(*oval),
+            Some(paint),
+        )
+    }
+
+    fn on_draw_path(&mut self, state: &CanvasState, path: &Path, paint: &Paint) {
+        let bounds = (!path.is_inverse_fill_type()).then(|| *path.bounds());
+        self.push_draw(
+            state,
+            PictureOp::DrawPath {
+                path: path.clone(),
+                paint: paint.clone(),
+            },
+            bounds,
+            Some(paint),
+        )
+    }
+
+    fn on_draw_region(&mut self, state: &CanvasState, region: &Region, paint: &Paint) {
+        self.push_draw(
+            state,
+            PictureOp::DrawRegion {
+                region: region.clone(),
+                paint: paint.clone(),
+            },
+            Some(Rect::from(*region.bounds())),
+            Some(paint),
+        )
+    }
+
+    fn on_draw_text_blob(
+        &mut self,
+        state: &CanvasState,
+        blob: &TextBlob,
+        origin: Point,
+        paint: &Paint,
+    ) {
+        self.push_draw(
+            state,
+            PictureOp::DrawTextBlob {
+                blob: blob.clone(),
+                origin,
+                paint: paint.clone(),
+            },
+            Some(blob.bounds().with_offset(origin)),
+            Some(paint),
+        )
+    }
+
+    fn on_draw_image(
+        &mut self,
+        state: &CanvasState,
+        image: &Image,
+        left_top: Point,
+        sampling: &SamplingOptions,
+        paint: Option<&Paint>,
+    ) {
+        self.push_draw(
+            state,
+            PictureOp::DrawImage {
+                image: image.clone(),
+                left_top,
+                sampling: *sampling,
+                paint: paint.cloned(),
+            },
+            Some(Rect::from_point_and_size(left_top, image.dimensions())),
+            paint,
+        )
+    }
+
+    fn on_draw_image_rect(
+        &mut self,
+        state: &CanvasState,
+        image: &Image,
+        src: &Rect,
+        dst: &Rect,
+        sampling: &SamplingOptions,
+        paint: Option<&Paint>,
+        constraint: SrcRectConstraint,
+    ) {
+        self.push_draw(
+            state,
+            PictureOp::DrawImageRect {
+                image: image.clone(),
+                src: *src,
+                dst: *dst,
+                sampling: *sampling,
+                paint: paint.cloned(),
+                constraint,
+            },
+            Some(*dst),
+            paint,
+        )
+    }
+
+    fn on_draw_vertices(
+        &mut self,
+        state: &CanvasState,
+        vertices: &Vertices,
+        mode: BlendMode,
+        paint: &Paint,
+    ) {
+        self.push_draw(
+            state,
+            PictureOp::DrawVertices {
+                vertices: vertices.clone(),
+                mode,
+                paint: paint.clone(),
+            },
+            Some(*vertices.bounds()),
+            Some(paint),
+        )
+    }
+
+    fn on_draw_annotation(
+        &mut self,
+        state: &CanvasState,
+        rect: &Rect,
+        key: &str,
+        value: Option<&Data>,
+    ) {
+        self.push_draw(
+            state,
+            PictureOp::DrawAnnotation {
+                rect: *rect,
+                key: key.to_owned(),
+                value: value.cloned(),
+            },
+            Some(*rect),
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PictureOp;
+    use crate::{Paint, PictureRecorder, Rect, paint};
+
+    #[test]
+    fn lists_ops_with_bounds() {
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(Rect::from_wh(100.0, 100.0), false);
+        canvas.save();
+        canvas.translate((10.0, 20.0));
+        canvas.draw_rect(Rect::from_wh(10.0, 10.0), &Paint::default());
+        canvas.restore();
+        let mut stroke = Paint::default();
+        stroke
+            .set_style(paint::Style::Stroke)
+            .set_stroke_width(4.0)
+            .set_stroke_join(paint::Join::Round);
+        canvas.draw_circle((50.0, 50.0), 10.0, &stroke);
+        canvas.draw_rect(Rect::new(90.0, 90.0, 200.0, 200.0), &Paint::default());
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+        let ops = picture.ops();
+        assert_eq!(ops.len(), 6);
+        assert!(matches!(ops[0].op, PictureOp::Save));
+        assert!(matches!(ops[1].op, PictureOp::Concat(_)));
+        assert!(matches!(ops[2].op, PictureOp::DrawRect { .. }));
+        assert!(matches!(ops[3].op, PictureOp::Restore));
+        assert!(matches!(ops[4].op, PictureOp::DrawOval { .. }));
+        assert_eq!(ops.iter().filter(|op| op.op.is_draw()).count(), 3);
+
+        assert_eq!(ops[1].matrix.row(0).w, 10.0);
+        assert_eq!(ops[0].bounds, None);
+        assert_eq!(ops[2].bounds, Some(Rect::new(10.0, 20.0, 20.0, 30.0)));
+        assert_eq!(ops[4].bounds, Some(Rect::new(38.0, 38.0, 62.0, 62.0)));
+        // Limited to the cull rect.
+        assert_eq!(ops[5].bounds, Some(Rect::new(90.0, 90.0, 100.0, 100.0)));
+    }
+}
@@ -291,6 +291,12 @@ impl<'pixels> Pixmap<'pixels> {
         unsafe { transmute_ref(n) }
     }
 
+    #[cfg(feature = "cpu-effects")]
+    #[must_use]
+    pub(crate) fn from_native_ref_mut(n: &mut SkPixmap) -> &mut Self {
+        unsafe { transmute_ref_mut(n) }
+    }
+
     #[must_use]
     pub(crate) fn from_native_ptr(np: *const SkPixmap) -> *const Self {
         // Should be safe as long `Pixmap` is represented with repr(Transparent).
@@ -340,6 +340,7 @@ impl Contains<Rect> for IRect {
 
 #[repr(C)]
 #[derive(Copy, Clone, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rect {
     /// The x coordinate of the rectangle's left edge.
     pub left: f32,
@@ -1,2 +1,7 @@
+mod multi_picture_document;
+#[cfg(feature = "pdf")]
 mod pdf_document;
+
+pub use multi_picture_document::*;
+#[cfg(feature = "pdf")]
 pub use pdf_document::*;
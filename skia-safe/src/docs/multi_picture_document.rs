@@ -0,0 +1,89 @@
+pub mod multi_picture_document {
+    //! Skia's multi-picture document format, also known as MSKP.
+    //!
+    //! Every page of the document is recorded into a [`Picture`]. The pages are serialized into a
+    //! single stream that can be read back with [`read()`], for example to replay render jobs in
+    //! another process.
+
+    use std::{io, ptr};
+
+    use skia_bindings as sb;
+
+    use crate::{Document, Picture, Size, interop::RustWStream, prelude::*};
+
+    /// Creates a document that writes its pages to `writer` when it is closed.
+    pub fn new_document(writer: &mut impl io::Write) -> Document<'_> {
+        let mut stream = RustWStream::new(writer);
+        let document =
+            RCHandle::from_ptr(unsafe { sb::C_SkMultiPictureDocument_Make(stream.stream_mut()) })
+                .unwrap();
+
+        Document::new(stream, document)
+    }
+
+    /// Returns the number of pages of the document in `bytes`, or `None` if `bytes` does not
+    /// contain a multi-picture document or the document has no pages.
+    pub fn read_page_count(bytes: &[u8]) -> Option<usize> {
+        let count =
+            unsafe { sb::C_SkMultiPictureDocument_ReadPageCount(bytes.as_ptr() as _, bytes.len()) };
+        (count > 0).then_some(count as usize)
+    }
+
+    /// Reads the pages of the document in `bytes` and their sizes.
+    ///
+    /// Returns `None` if `bytes` does not contain a multi-picture document or a page can not be
+    /// deserialized.
+    pub fn read(bytes: &[u8]) -> Option<Vec<(Picture, Size)>> {
+        let count = read_page_count(bytes)?;
+        let mut pictures = vec![ptr::null_mut(); count];
+        let mut sizes = vec![Size::default(); count];
+        let read = unsafe {
+            sb::C_SkMultiPictureDocument_Read(
+                bytes.as_ptr() as _,
+                bytes.len(),
+                pictures.as_mut_ptr(),
+                sizes.native_mut().as_mut_ptr(),
+                count.try_into().unwrap(),
+            )
+        };
+        if !read {
+            return None;
+        }
+
+        // Take ownership of all pictures before checking them, so that none of them leaks.
+        let pictures: Vec<Option<Picture>> = pictures.into_iter().map(Picture::from_ptr).collect();
+        pictures
+            .into_iter()
+            .zip(sizes)
+            .map(|(picture, size)| Some((picture?, size)))
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::{Color, Paint, Rect};
+
+        #[test]
+        fn pages_round_trip() {
+            let mut bytes = Vec::new();
+            let mut document = new_document(&mut bytes).begin_page((100, 50), None);
+            document
+                .canvas()
+                .draw_rect(Rect::new(10.0, 10.0, 20.0, 20.0), &Paint::default());
+            let mut document = document.end_page().begin_page((30, 40), None);
+            document.canvas().clear(Color::RED);
+            document.end_page().close();
+
+            assert_eq!(read_page_count(&bytes), Some(2));
+            let pages = read(&bytes).unwrap();
+            assert_eq!(pages.len(), 2);
+            assert_eq!(pages[0].1, Size::new(100.0, 50.0));
+            assert_eq!(pages[1].1, Size::new(30.0, 40.0));
+            assert!(pages[0].0.approximate_op_count() > 0);
+
+            assert_eq!(read_page_count(b"not a document"), None);
+            assert!(read(b"not a document").is_none());
+        }
+    }
+}
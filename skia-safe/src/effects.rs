@@ -4,12 +4,15 @@ pub mod blenders;
 pub mod color_matrix;
 pub mod color_matrix_filter;
 pub mod corner_path_effect;
+#[cfg(feature = "cpu-effects")]
+mod cpu_effects;
 pub mod dash_path_effect;
 pub mod discrete_path_effect;
 pub mod gradient;
 #[deprecated(since = "0.94.0", note = "Use the skia_safe::gradient module")]
 pub mod gradient_shader;
 pub mod high_contrast_filter;
+pub mod image_filter_graph;
 pub mod image_filters;
 pub mod luma_color_filter;
 pub mod overdraw_color_filter;
@@ -24,5 +27,8 @@ pub mod trim_path_effect;
 pub use _1d_path_effect::*;
 pub use _2d_path_effect::*;
 pub use color_matrix::ColorMatrix;
+#[cfg(feature = "cpu-effects")]
+pub use cpu_effects::*;
 pub use high_contrast_filter::{HighContrastConfig, high_contrast_config};
+pub use image_filter_graph::ImageFilterGraph;
 pub use runtime_effect::RuntimeEffect;
@@ -0,0 +1,366 @@
+//! Image filters, shaders, and color filters implemented in Rust.
+//!
+//! These effects run on the CPU only. They can be used with raster surfaces, pictures that are
+//! played back on raster surfaces, and anywhere else Skia renders with its CPU backend. GPU
+//! backends do not call back into Rust: they treat the shaders as empty and the color filters as
+//! no-ops, and the image filters produce no output.
+//!
+//! Rust effects can not be serialized.
+//!
+//! This module requires the `cpu-effects` feature. Skia has no public extension point for
+//! effects, so they are implemented on top of Skia's internal effect classes and raster pipeline.
+
+use std::mem;
+
+use skia_bindings::{
+    self as sb, RustColorFilter_Param, RustImageFilter_Param, RustShader_Param, SkColor4f,
+    SkIPoint, SkIRect, SkMatrix, SkPixmap, SkPoint, TraitObject,
+};
+
+use crate::{
+    Color4f, ColorFilter, IPoint, IRect, ImageFilter, Matrix, Pixmap, Point, Shader,
+    prelude::{safer, *},
+};
+
+/// An image filter implemented in Rust, see [`ImageFilter::from_cpu()`].
+///
+/// All bounds are in layer space, which is related to the local space of the filtered content by
+/// a scale and translation.
+pub trait CpuImageFilter: Send + Sync + 'static {
+    /// Renders the filter into `output`.
+    ///
+    /// `source` contains the output of the filter's input, or the filtered content if there is
+    /// no input, and may be empty. `source` and `output` are N32 premultiplied pixmaps in the
+    /// color space of the layer. `output` is transparent initially.
+    ///
+    /// Returns `false` if the filter failed, in which case its output is empty.
+    fn filter(&self, context: &CpuImageFilterContext, source: &Pixmap, output: &mut Pixmap)
+    -> bool;
+
+    /// Returns the area of the source that is needed to render `output_bounds`.
+    fn input_bounds(&self, matrix: &Matrix, output_bounds: &IRect) -> IRect {
+        let _ = matrix;
+        *output_bounds
+    }
+
+    /// Returns the area the filter may draw to if the source has content in `content_bounds`.
+    ///
+    /// `content_bounds` and the result are `None` if they are unbounded.
+    fn output_bounds(&self, matrix: &Matrix, content_bounds: Option<&IRect>) -> Option<IRect> {
+        let _ = matrix;
+        content_bounds.copied()
+    }
+}
+
+impl<F> CpuImageFilter for F
+where
+    F: Fn(&CpuImageFilterContext, &Pixmap, &mut Pixmap) -> bool + Send + Sync + 'static,
+{
+    fn filter(
+        &self,
+        context: &CpuImageFilterContext,
+        source: &Pixmap,
+        output: &mut Pixmap,
+    ) -> bool {
+        self(context, source, output)
+    }
+}
+
+/// Describes where the pixmaps passed to [`CpuImageFilter::filter()`] are located.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CpuImageFilterContext {
+    /// The scale and translation from local to layer space.
+    pub matrix: Matrix,
+    /// The position of the top left pixel of the source in layer space.
+    pub source_origin: IPoint,
+    /// The area covered by the output in layer space.
+    pub output_bounds: IRect,
+}
+
+/// A shader implemented in Rust, see [`Shader::from_cpu()`].
+pub trait CpuShader: Send + Sync + 'static {
+    /// Computes the colors at `points`, which are in the local space of the shader.
+    ///
+    /// The colors are unpremultiplied and in sRGB.
+    fn shade(&self, points: &[Point], colors: &mut [Color4f]);
+}
+
+impl<F> CpuShader for F
+where
+    F: Fn(&[Point], &mut [Color4f]) + Send + Sync + 'static,
+{
+    fn shade(&self, points: &[Point], colors: &mut [Color4f]) {
+        self(points, colors)
+    }
+}
+
+/// A color filter implemented in Rust, see [`ColorFilter::from_cpu()`].
+pub trait CpuColorFilter: Send + Sync + 'static {
+    /// Filters unpremultiplied sRGB `colors` in place.
+    fn filter_colors(&self, colors: &mut [Color4f]);
+}
+
+impl<F> CpuColorFilter for F
+where
+    F: Fn(&mut [Color4f]) + Send + Sync + 'static,
+{
+    fn filter_colors(&self, colors: &mut [Color4f]) {
+        self(colors)
+    }
+}
+
+impl ImageFilter {
+    /// Creates an image filter that calls `filter` to render the output of `input`, or the
+    /// filtered content if `input` is `None`.
+    pub fn from_cpu(
+        filter: impl CpuImageFilter,
+        input: impl Into<Option<ImageFilter>>,
+    ) -> ImageFilter {
+        let filter: Box<dyn CpuImageFilter> = Box::new(filter);
+        let param = RustImageFilter_Param {
+            trait_: unsafe { mem::transmute::<Box<dyn CpuImageFilter>, TraitObject>(filter) },
+            drop: Some(drop_image_filter),
+            filter: Some(filter_image),
+            inputBounds: Some(input_bounds),
+            outputBounds: Some(output_bounds),
+        };
+
+        return ImageFilter::from_ptr(unsafe {
+            sb::C_RustImageFilter_new(input.into().into_ptr_or_null(), &param)
+        })
+        .unwrap();
+
+        extern "C" fn drop_image_filter(filter: TraitObject) {
+            mem::drop(unsafe { mem::transmute::<TraitObject, Box<dyn CpuImageFilter>>(filter) });
+        }
+
+        extern "C" fn filter_image(
+            filter: TraitObject,
+            layer_matrix: *const SkMatrix,
+            source: *const SkPixmap,
+            source_origin: *const SkIPoint,
+            output: *mut SkPixmap,
+            output_origin: *const SkIPoint,
+        ) -> bool {
+            let filter = unsafe { image_filter(filter) };
+            let output = Pixmap::from_native_ref_mut(unsafe { &mut *output });
+            let output_origin = IPoint::from_native_c(unsafe { *output_origin });
+            let context = CpuImageFilterContext {
+                matrix: *Matrix::from_native_ref(unsafe { &*layer_matrix }),
+                source_origin: IPoint::from_native_c(unsafe { *source_origin }),
+                output_bounds: IRect::from_xywh(
+                    output_origin.x,
+                    output_origin.y,
+                    output.width(),
+                    output.height(),
+                ),
+            };
+            filter.filter(
+                &context,
+                Pixmap::from_native_ref(unsafe { &*source }),
+                output,
+            )
+        }
+
+        extern "C" fn input_bounds(
+            filter: TraitObject,
+            layer_matrix: *const SkMatrix,
+            desired_output: *const SkIRect,
+            input: *mut SkIRect,
+        ) {
+            let filter = unsafe { image_filter(filter) };
+            let bounds = filter.input_bounds(
+                Matrix::from_native_ref(unsafe { &*layer_matrix }),
+                IRect::from_native_ref(unsafe { &*desired_output }),
+            );
+            unsafe { *input = bounds.into_native() }
+        }
+
+        extern "C" fn output_bounds(
+            filter: TraitObject,
+            layer_matrix: *const SkMatrix,
+            content_bounds: *const SkIRect,
+            output: *mut SkIRect,
+        ) -> bool {
+            let filter = unsafe { image_filter(filter) };
+            let content_bounds = unsafe { content_bounds.as_ref() }.map(IRect::from_native_ref);
+            match filter.output_bounds(
+                Matrix::from_native_ref(unsafe { &*layer_matrix }),
+                content_bounds,
+            ) {
+                Some(bounds) => {
+                    unsafe { *output = bounds.into_native() };
+                    true
+                }
+                None => false,
+            }
+        }
+
+        unsafe fn image_filter<'a>(filter: TraitObject) -> &'a dyn CpuImageFilter {
+            unsafe { mem::transmute::<TraitObject, &dyn CpuImageFilter>(filter) }
+        }
+    }
+}
+
+impl Shader {
+    /// Creates a shader that calls `shader` to compute the colors of the pixels it covers.
+    pub fn from_cpu(shader: impl CpuShader) -> Shader {
+        let shader: Box<dyn CpuShader> = Box::new(shader);
+        let param = RustShader_Param {
+            trait_: unsafe { mem::transmute::<Box<dyn CpuShader>, TraitObject>(shader) },
+            drop: Some(drop_shader),
+            shade: Some(shade),
+        };
+
+        return Shader::from_ptr(unsafe { sb::C_RustShader_new(&param) }).unwrap();
+
+        extern "C" fn drop_shader(shader: TraitObject) {
+            mem::drop(unsafe { mem::transmute::<TraitObject, Box<dyn CpuShader>>(shader) });
+        }
+
+        extern "C" fn shade(
+            shader: TraitObject,
+            points: *const SkPoint,
+            colors: *mut SkColor4f,
+            count: usize,
+        ) {
+            let shader = unsafe { mem::transmute::<TraitObject, &dyn CpuShader>(shader) };
+            let points = unsafe { safer::from_raw_parts(Point::from_native_ptr(points), count) };
+            let colors =
+                unsafe { safer::from_raw_parts_mut(Color4f::from_native_ptr_mut(colors), count) };
+            shader.shade(points, colors)
+        }
+    }
+}
+
+impl ColorFilter {
+    /// Creates a color filter that calls `filter` to filter the colors of the pixels it is
+    /// applied to.
+    pub fn from_cpu(filter: impl CpuColorFilter) -> ColorFilter {
+        let filter: Box<dyn CpuColorFilter> = Box::new(filter);
+        let param = RustColorFilter_Param {
+            trait_: unsafe { mem::transmute::<Box<dyn CpuColorFilter>, TraitObject>(filter) },
+            drop: Some(drop_color_filter),
+            filterColors: Some(filter_colors),
+        };
+
+        return ColorFilter::from_ptr(unsafe { sb::C_RustColorFilter_new(&param) }).unwrap();
+
+        extern "C" fn drop_color_filter(filter: TraitObject) {
+            mem::drop(unsafe { mem::transmute::<TraitObject, Box<dyn CpuColorFilter>>(filter) });
+        }
+
+        extern "C" fn filter_colors(filter: TraitObject, colors: *mut SkColor4f, count: usize) {
+            let filter = unsafe { mem::transmute::<TraitObject, &dyn CpuColorFilter>(filter) };
+            let colors =
+                unsafe { safer::from_raw_parts_mut(Color4f::from_native_ptr_mut(colors), count) };
+            filter.filter_colors(colors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Paint, Rect, surfaces};
+
+    #[test]
+    fn color_filter_inverts_colors() {
+        let mut surface = surfaces::raster_n32_premul((4, 4)).unwrap();
+        let mut paint = Paint::default();
+        paint
+            .set_color(Color::RED)
+            .set_color_filter(ColorFilter::from_cpu(|colors: &mut [Color4f]| {
+                for color in colors {
+                    color.r = 1.0 - color.r;
+                    color.g = 1.0 - color.g;
+                    color.b = 1.0 - color.b;
+                }
+            }));
+        surface.canvas().draw_paint(&paint);
+        assert_eq!(
+            surface.peek_pixels().unwrap().get_color((1, 1)),
+            Color::CYAN
+        );
+    }
+
+    #[test]
+    fn shader_receives_local_coordinates() {
+        let mut surface = surfaces::raster_n32_premul((10, 1)).unwrap();
+        let mut paint = Paint::default();
+        paint.set_shader(Shader::from_cpu(
+            |points: &[Point], colors: &mut [Color4f]| {
+                for (point, color) in points.iter().zip(colors) {
+                    *color = if point.x < 5.0 {
+                        Color4f::from(Color::RED)
+                    } else {
+                        Color4f::from(Color::BLUE)
+                    };
+                }
+            },
+        ));
+        surface.canvas().draw_paint(&paint);
+        let pixmap = surface.peek_pixels().unwrap();
+        assert_eq!(pixmap.get_color((2, 0)), Color::RED);
+        assert_eq!(pixmap.get_color((7, 0)), Color::BLUE);
+    }
+
+    /// Moves its source to the right.
+    struct Offset(i32);
+
+    impl Offset {
+        fn dx(&self, matrix: &Matrix) -> i32 {
+            (self.0 as f32 * matrix.scale_x()).round() as i32
+        }
+    }
+
+    impl CpuImageFilter for Offset {
+        fn filter(
+            &self,
+            context: &CpuImageFilterContext,
+            source: &Pixmap,
+            output: &mut Pixmap,
+        ) -> bool {
+            let dx = self.dx(&context.matrix);
+            let (source_bytes, source_row_bytes) = (source.bytes(), source.row_bytes());
+            let output_row_bytes = output.row_bytes();
+            let output_bytes = output.bytes_mut().unwrap();
+            for y in 0..context.output_bounds.height() {
+                for x in 0..context.output_bounds.width() {
+                    let sx = context.output_bounds.left + x - dx - context.source_origin.x;
+                    let sy = context.output_bounds.top + y - context.source_origin.y;
+                    if sx < 0 || sy < 0 || sx >= source.width() || sy >= source.height() {
+                        continue;
+                    }
+                    let s = sy as usize * source_row_bytes + sx as usize * 4;
+                    let o = y as usize * output_row_bytes + x as usize * 4;
+                    output_bytes[o..o + 4].copy_from_slice(&source_bytes.unwrap()[s..s + 4]);
+                }
+            }
+            true
+        }
+
+        fn input_bounds(&self, matrix: &Matrix, output_bounds: &IRect) -> IRect {
+            output_bounds.with_offset((-self.dx(matrix), 0))
+        }
+
+        fn output_bounds(&self, matrix: &Matrix, content_bounds: Option<&IRect>) -> Option<IRect> {
+            content_bounds.map(|bounds| bounds.with_offset((self.dx(matrix), 0)))
+        }
+    }
+
+    #[test]
+    fn image_filter_maps_bounds() {
+        let mut surface = surfaces::raster_n32_premul((20, 10)).unwrap();
+        let mut paint = Paint::default();
+        paint
+            .set_color(Color::RED)
+            .set_image_filter(ImageFilter::from_cpu(Offset(5), None));
+        surface
+            .canvas()
+            .draw_rect(Rect::new(0.0, 0.0, 10.0, 10.0), &paint);
+        let pixmap = surface.peek_pixels().unwrap();
+        assert_eq!(pixmap.get_color((2, 5)), Color::TRANSPARENT);
+        assert_eq!(pixmap.get_color((12, 5)), Color::RED);
+    }
+}
@@ -0,0 +1,639 @@
+//! Describes an image filter as a graph of named nodes.
+//!
+//! Every node applies one of the filters of [`crate::image_filters`] to the outputs of the nodes
+//! it names as its inputs. An input that is `None` refers to the content the filter is applied
+//! to. With the `serde` feature, graphs can be (de)serialized, for example from JSON:
+//!
+//! ```json
+//! {
+//!   "nodes": [
+//!     { "name": "shadow", "type": "drop_shadow_only", "dx": 4, "dy": 4, "sigma_x": 2,
+//!       "sigma_y": 2, "color": { "r": 0, "g": 0, "b": 0, "a": 0.5 } },
+//!     { "name": "result", "type": "merge", "inputs": ["shadow", null] }
+//!   ],
+//!   "output": "result"
+//! }
+//! ```
+
+use std::{collections::HashMap, error, fmt};
+
+use crate::{
+    BlendMode, Color4f, ImageFilter, Rect, TileMode, color_filters,
+    image_filters::{self, CropRect},
+    scalar,
+};
+
+/// A graph of named image filter nodes that builds to an [`ImageFilter`].
+#[derive(Clone, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImageFilterGraph {
+    pub nodes: Vec<Node>,
+    /// The name of the node that produces the result. The last node if `None`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub output: Option<String>,
+}
+
+/// A named node of an [`ImageFilterGraph`].
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node {
+    pub name: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub op: Op,
+    /// Limits the output of the node.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub crop_rect: Option<Rect>,
+}
+
+/// The filter a [`Node`] applies.
+///
+/// Inputs name other nodes of the graph. `None` refers to the content the filter is applied to.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum Op {
+    Blur {
+        sigma_x: scalar,
+        sigma_y: scalar,
+        tile_mode: Option<TileMode>,
+        input: Option<String>,
+    },
+    Offset {
+        dx: scalar,
+        dy: scalar,
+        input: Option<String>,
+    },
+    DropShadow {
+        dx: scalar,
+        dy: scalar,
+        sigma_x: scalar,
+        sigma_y: scalar,
+        color: Color4f,
+        input: Option<String>,
+    },
+    DropShadowOnly {
+        dx: scalar,
+        dy: scalar,
+        sigma_x: scalar,
+        sigma_y: scalar,
+        color: Color4f,
+        input: Option<String>,
+    },
+    /// Applies a row major 4x5 color matrix.
+    ColorMatrix {
+        matrix: [scalar; 20],
+        input: Option<String>,
+    },
+    Dilate {
+        radius_x: scalar,
+        radius_y: scalar,
+        input: Option<String>,
+    },
+    Erode {
+        radius_x: scalar,
+        radius_y: scalar,
+        input: Option<String>,
+    },
+    Crop {
+        rect: Rect,
+        tile_mode: Option<TileMode>,
+        input: Option<String>,
+    },
+    /// Draws the inputs on top of each other, the first one at the bottom.
+    Merge { inputs: Vec<Option<String>> },
+    /// Applies `outer` to the output of `inner`.
+    Compose {
+        outer: String,
+        inner: Option<String>,
+    },
+    Blend {
+        mode: BlendMode,
+        background: Option<String>,
+        foreground: Option<String>,
+    },
+    DistantLitDiffuse {
+        direction: [scalar; 3],
+        color: Color4f,
+        surface_scale: scalar,
+        kd: scalar,
+        input: Option<String>,
+    },
+    PointLitDiffuse {
+        location: [scalar; 3],
+        color: Color4f,
+        surface_scale: scalar,
+        kd: scalar,
+        input: Option<String>,
+    },
+    DistantLitSpecular {
+        direction: [scalar; 3],
+        color: Color4f,
+        surface_scale: scalar,
+        ks: scalar,
+        shininess: scalar,
+        input: Option<String>,
+    },
+    PointLitSpecular {
+        location: [scalar; 3],
+        color: Color4f,
+        surface_scale: scalar,
+        ks: scalar,
+        shininess: scalar,
+        input: Option<String>,
+    },
+}
+
+impl Op {
+    /// The nodes this op reads from. `None` refers to the content the filter is applied to.
+    pub fn inputs(&self) -> Vec<Option<&str>> {
+        match self {
+            Op::Blur { input, .. }
+            | Op::Offset { input, .. }
+            | Op::DropShadow { input, .. }
+            | Op::DropShadowOnly { input, .. }
+            | Op::ColorMatrix { input, .. }
+            | Op::Dilate { input, .. }
+            | Op::Erode { input, .. }
+            | Op::Crop { input, .. }
+            | Op::DistantLitDiffuse { input, .. }
+            | Op::PointLitDiffuse { input, .. }
+            | Op::DistantLitSpecular { input, .. }
+            | Op::PointLitSpecular { input, .. } => vec![input.as_deref()],
+            Op::Merge { inputs } => inputs.iter().map(Option::as_deref).collect(),
+            Op::Compose { outer, inner } => vec![Some(outer.as_str()), inner.as_deref()],
+            Op::Blend {
+                background,
+                foreground,
+                ..
+            } => vec![background.as_deref(), foreground.as_deref()],
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum GraphError {
+    /// The graph has no nodes.
+    Empty,
+    /// More than one node has this name.
+    DuplicateNode(String),
+    /// `node` names an `input` that is not in the graph.
+    MissingInput { node: String, input: String },
+    /// The output names a node that is not in the graph.
+    MissingOutput(String),
+    /// The nodes form a cycle, the first node is repeated at the end.
+    Cycle(Vec<String>),
+    /// Skia could not create the filter of the node, for example because of invalid parameters.
+    InvalidNode(String),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::Empty => write!(f, "the image filter graph has no nodes"),
+            GraphError::DuplicateNode(name) => write!(f, "node '{name}' is defined more than once"),
+            GraphError::MissingInput { node, input } => {
+                write!(f, "node '{node}' reads from the missing node '{input}'")
+            }
+            GraphError::MissingOutput(name) => write!(f, "the output node '{name}' is missing"),
+            GraphError::Cycle(names) => write!(f, "the nodes form a cycle: {}", names.join(" -> ")),
+            GraphError::InvalidNode(name) => write!(f, "the filter of node '{name}' is invalid"),
+        }
+    }
+}
+
+impl error::Error for GraphError {}
+
+impl ImageFilterGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node without a crop rect.
+    pub fn add(&mut self, name: impl Into<String>, op: Op) -> &mut Self {
+        self.add_node(Node {
+            name: name.into(),
+            op,
+            crop_rect: None,
+        })
+    }
+
+    pub fn add_node(&mut self, node: Node) -> &mut Self {
+        self.nodes.push(node);
+        self
+    }
+
+    pub fn set_output(&mut self, name: impl Into<String>) -> &mut Self {
+        self.output = Some(name.into());
+        self
+    }
+
+    /// Checks that the node names are unique, that all inputs and the output exist, and that the
+    /// graph has no cycles.
+    pub fn validate(&self) -> Result<(), GraphError> {
+        self.index().map(|_| ())
+    }
+
+    /// Validates the graph and creates the image filter of its output node.
+    pub fn build(&self) -> Result<ImageFilter, GraphError> {
+        let (nodes, output) = self.index()?;
+        let mut built = HashMap::new();
+        visit_inputs_first(&nodes, output, &mut HashMap::new(), |node| {
+            let filter = build_node(node, &built)?;
+            built.insert(node.name.as_str(), filter);
+            Ok(())
+        })?;
+        Ok(built.remove(output.name.as_str()).unwrap())
+    }
+
+    /// Returns the nodes by name and the output node.
+    fn index(&self) -> Result<(HashMap<&str, &Node>, &Node), GraphError> {
+        let mut nodes = HashMap::new();
+        for node in &self.nodes {
+            if nodes.insert(node.name.as_str(), node).is_some() {
+                return Err(GraphError::DuplicateNode(node.name.clone()));
+            }
+        }
+
+        for node in &self.nodes {
+            if let Some(input) = node
+                .op
+                .inputs()
+                .into_iter()
+                .flatten()
+                .find(|input| !nodes.contains_key(input))
+            {
+                return Err(GraphError::MissingInput {
+                    node: node.name.clone(),
+                    input: input.to_owned(),
+                });
+            }
+        }
+
+        let mut visited = HashMap::new();
+        for node in &self.nodes {
+            visit_inputs_first(&nodes, node, &mut visited, |_| Ok(()))?;
+        }
+
+        let output = match &self.output {
+            Some(name) => *nodes
+                .get(name.as_str())
+                .ok_or_else(|| GraphError::MissingOutput(name.clone()))?,
+            None => self.nodes.last().ok_or(GraphError::Empty)?,
+        };
+
+        Ok((nodes, output))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+/// Depth-first search that calls `visit` for every node reachable from `start` that was not
+/// visited before, after it was called for all of the node's inputs. Reports the first cycle
+/// reachable from `start`.
+///
+/// Graphs may be deep, so the search keeps its path on the heap instead of recursing.
+fn visit_inputs_first<'a>(
+    nodes: &HashMap<&str, &'a Node>,
+    start: &'a Node,
+    visited: &mut HashMap<&'a str, Visit>,
+    mut visit: impl FnMut(&'a Node) -> Result<(), GraphError>,
+) -> Result<(), GraphError> {
+    if visited.contains_key(start.name.as_str()) {
+        return Ok(());
+    }
+
+    // The nodes in progress and their inputs that remain to be visited.
+    let mut path: Vec<(&'a Node, std::vec::IntoIter<&'a str>)> = Vec::new();
+    let pending_inputs = |node: &'a Node| {
+        let inputs: Vec<&'a str> = node.op.inputs().into_iter().flatten().collect();
+        inputs.into_iter()
+    };
+    visited.insert(start.name.as_str(), Visit::InProgress);
+    path.push((start, pending_inputs(start)));
+
+    while let Some((node, inputs)) = path.last_mut() {
+        let node = *node;
+        let Some(input) = inputs.next() else {
+            path.pop();
+            visited.insert(node.name.as_str(), Visit::Done);
+            visit(node)?;
+            continue;
+        };
+        let input = nodes[input];
+        match visited.get(input.name.as_str()) {
+            Some(Visit::Done) => {}
+            Some(Visit::InProgress) => {
+                let start = path.iter().position(|(n, _)| n.name == input.name).unwrap();
+                let mut cycle: Vec<String> =
+                    path[start..].iter().map(|(n, _)| n.name.clone()).collect();
+                cycle.push(input.name.clone());
+                return Err(GraphError::Cycle(cycle));
+            }
+            None => {
+                visited.insert(input.name.as_str(), Visit::InProgress);
+                path.push((input, pending_inputs(input)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Creates the filter of `node`. The filters of its inputs must already be `built`.
+fn build_node(node: &Node, built: &HashMap<&str, ImageFilter>) -> Result<ImageFilter, GraphError> {
+    let crop_rect = CropRect::from(node.crop_rect.map(CropRect::from));
+
+    match &node.op {
+        Op::Blur {
+            sigma_x,
+            sigma_y,
+            tile_mode,
+            input: i,
+        } => image_filters::blur(
+            (*sigma_x, *sigma_y),
+            *tile_mode,
+            build_input(built, i),
+            crop_rect,
+        ),
+        Op::Offset { dx, dy, input: i } => {
+            image_filters::offset((*dx, *dy), build_input(built, i), crop_rect)
+        }
+        Op::DropShadow {
+            dx,
+            dy,
+            sigma_x,
+            sigma_y,
+            color,
+            input: i,
+        } => image_filters::drop_shadow(
+            (*dx, *dy),
+            (*sigma_x, *sigma_y),
+            *color,
+            None,
+            build_input(built, i),
+            crop_rect,
+        ),
+        Op::DropShadowOnly {
+            dx,
+            dy,
+            sigma_x,
+            sigma_y,
+            color,
+            input: i,
+        } => image_filters::drop_shadow_only(
+            (*dx, *dy),
+            (*sigma_x, *sigma_y),
+            *color,
+            None,
+            build_input(built, i),
+            crop_rect,
+        ),
+        Op::ColorMatrix { matrix, input: i } => image_filters::color_filter(
+            color_filters::matrix_row_major(matrix, None),
+            build_input(built, i),
+            crop_rect,
+        ),
+        Op::Dilate {
+            radius_x,
+            radius_y,
+            input: i,
+        } => image_filters::dilate((*radius_x, *radius_y), build_input(built, i), crop_rect),
+        Op::Erode {
+            radius_x,
+            radius_y,
+            input: i,
+        } => image_filters::erode((*radius_x, *radius_y), build_input(built, i), crop_rect),
+        Op::Crop {
+            rect,
+            tile_mode,
+            input: i,
+        } => image_filters::crop(rect, *tile_mode, build_input(built, i)).and_then(|filter| {
+            match node.crop_rect {
+                Some(crop_rect) => image_filters::crop(crop_rect, None, filter),
+                None => Some(filter),
+            }
+        }),
+        Op::Merge { inputs } => {
+            let inputs: Vec<_> = inputs.iter().map(|i| build_input(built, i)).collect();
+            image_filters::merge(inputs, crop_rect)
+        }
+        Op::Compose { outer, inner } => {
+            let outer = built[outer.as_str()].clone();
+            let composed = match build_input(built, inner) {
+                Some(inner) => image_filters::compose(outer, inner),
+                None => Some(outer),
+            };
+            match node.crop_rect {
+                Some(crop_rect) => {
+                    composed.and_then(|filter| image_filters::crop(crop_rect, None, filter))
+                }
+                None => composed,
+            }
+        }
+        Op::Blend {
+            mode,
+            background,
+            foreground,
+        } => {
+            let background = build_input(built, background);
+            let foreground = build_input(built, foreground);
+            image_filters::blend(*mode, background, foreground, crop_rect)
+        }
+        Op::DistantLitDiffuse {
+            direction: [x, y, z],
+            color,
+            surface_scale,
+            kd,
+            input: i,
+        } => image_filters::distant_lit_diffuse(
+            (*x, *y, *z),
+            color.to_color(),
+            *surface_scale,
+            *kd,
+            build_input(built, i),
+            crop_rect,
+        ),
+        Op::PointLitDiffuse {
+            location: [x, y, z],
+            color,
+            surface_scale,
+            kd,
+            input: i,
+        } => image_filters::point_lit_diffuse(
+            (*x, *y, *z),
+            color.to_color(),
+            *surface_scale,
+            *kd,
+            build_input(built, i),
+            crop_rect,
+        ),
+        Op::DistantLitSpecular {
+            direction: [x, y, z],
+            color,
+            surface_scale,
+            ks,
+            shininess,
+            input: i,
+        } => image_filters::distant_lit_specular(
+            (*x, *y, *z),
+            color.to_color(),
+            *surface_scale,
+            *ks,
+            *shininess,
+            build_input(built, i),
+            crop_rect,
+        ),
+        Op::PointLitSpecular {
+            location: [x, y, z],
+            color,
+            surface_scale,
+            ks,
+            shininess,
+            input: i,
+        } => image_filters::point_lit_specular(
+            (*x, *y, *z),
+            color.to_color(),
+            *surface_scale,
+            *ks,
+            *shininess,
+            build_input(built, i),
+            crop_rect,
+        ),
+    }
+    .ok_or_else(|| GraphError::InvalidNode(node.name.clone()))
+}
+
+/// Returns the filter of the node `name` refers to, or `None` for the content the filter is
+/// applied to.
+fn build_input(built: &HashMap<&str, ImageFilter>, name: &Option<String>) -> Option<ImageFilter> {
+    name.as_deref().map(|name| built[name].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Paint, surfaces};
+
+    fn blur(input: Option<&str>) -> Op {
+        Op::Blur {
+            sigma_x: 2.0,
+            sigma_y: 2.0,
+            tile_mode: None,
+            input: input.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn builds_the_output_node() {
+        let mut graph = ImageFilterGraph::new();
+        graph
+            .add(
+                "shifted",
+                Op::Offset {
+                    dx: 10.0,
+                    dy: 0.0,
+                    input: None,
+                },
+            )
+            .add(
+                "both",
+                Op::Merge {
+                    inputs: vec![None, Some("shifted".into())],
+                },
+            )
+            .add("unused", blur(Some("shifted")))
+            .set_output("both");
+        let filter = graph.build().unwrap();
+
+        let mut surface = surfaces::raster_n32_premul((30, 10)).unwrap();
+        let mut paint = Paint::default();
+        paint.set_color(Color::RED).set_image_filter(filter);
+        surface
+            .canvas()
+            .draw_rect(Rect::new(0.0, 0.0, 10.0, 10.0), &paint);
+        let pixmap = surface.peek_pixels().unwrap();
+        assert_eq!(pixmap.get_color((5, 5)), Color::RED);
+        assert_eq!(pixmap.get_color((15, 5)), Color::RED);
+        assert_eq!(pixmap.get_color((25, 5)), Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn reports_invalid_graphs() {
+        assert_eq!(ImageFilterGraph::new().validate(), Err(GraphError::Empty));
+
+        let mut graph = ImageFilterGraph::new();
+        graph.add("a", blur(None)).add("a", blur(None));
+        assert_eq!(graph.validate(), Err(GraphError::DuplicateNode("a".into())));
+
+        let mut graph = ImageFilterGraph::new();
+        graph.add("a", blur(Some("b")));
+        assert_eq!(
+            graph.validate(),
+            Err(GraphError::MissingInput {
+                node: "a".into(),
+                input: "b".into()
+            })
+        );
+
+        let mut graph = ImageFilterGraph::new();
+        graph
+            .add("a", blur(Some("c")))
+            .add("b", blur(Some("a")))
+            .add("c", blur(Some("b")));
+        assert_eq!(
+            graph.validate(),
+            Err(GraphError::Cycle(vec![
+                "a".into(),
+                "c".into(),
+                "b".into(),
+                "a".into()
+            ]))
+        );
+
+        let mut graph = ImageFilterGraph::new();
+        graph.add("a", blur(None)).set_output("b");
+        assert_eq!(graph.validate(), Err(GraphError::MissingOutput("b".into())));
+    }
+
+    #[test]
+    fn validates_deep_graphs() {
+        let mut graph = ImageFilterGraph::new();
+        graph.add("0", blur(None));
+        for i in 1..100_000 {
+            graph.add(i.to_string(), blur(Some(&(i - 1).to_string())));
+        }
+        assert_eq!(graph.validate(), Ok(()));
+
+        graph.nodes[0].op = blur(Some("99999"));
+        assert!(
+            matches!(graph.validate(), Err(GraphError::Cycle(cycle)) if cycle.len() == 100_001)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserializes_json() {
+        let json = r#"{
+            "nodes": [
+                { "name": "shadow", "type": "drop_shadow_only", "dx": 4, "dy": 4,
+                  "sigma_x": 2, "sigma_y": 2, "color": { "r": 0, "g": 0, "b": 0, "a": 0.5 } },
+                { "name": "result", "type": "merge", "inputs": ["shadow", null] }
+            ],
+            "output": "result"
+        }"#;
+        let graph: ImageFilterGraph = serde_json::from_str(json).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.nodes[1].op.inputs(), [Some("shadow"), None]);
+        assert!(graph.build().is_ok());
+
+        let round_tripped: ImageFilterGraph =
+            serde_json::from_str(&serde_json::to_string(&graph).unwrap()).unwrap();
+        assert_eq!(round_tripped, graph);
+    }
+}
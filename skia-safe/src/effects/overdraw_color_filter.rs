@@ -1,8 +1,19 @@
-use crate::{Color, ColorFilter, prelude::*};
+use crate::{BlendMode, Color, ColorFilter, Image, Paint, prelude::*, surfaces};
 use skia_bindings as sb;
 
 pub const NUM_COLORS: usize = 6;
 
+/// The colors for no draws, one draw, and 1, 2, 3, and 4 or more overdraws: transparent,
+/// transparent, blue, green, pink, and red.
+pub const DEFAULT_COLORS: [Color; NUM_COLORS] = [
+    Color::new(0x00000000),
+    Color::new(0x00000000),
+    Color::new(0x7f0000ff),
+    Color::new(0x7f00ff00),
+    Color::new(0x7fff7fbf),
+    Color::new(0x7fff0000),
+];
+
 impl ColorFilter {
     pub fn overdraw(colors: &[Color; NUM_COLORS]) -> ColorFilter {
         new(colors)
@@ -15,3 +26,15 @@ pub fn new(colors: &[Color; NUM_COLORS]) -> ColorFilter {
     })
     .unwrap()
 }
+
+/// Converts the draws per pixel counted by an [`crate::OverdrawCanvas`] into an N32 image by
+/// mapping each count to one of the `colors`.
+pub fn heatmap(counts: &Image, colors: &[Color; NUM_COLORS]) -> Option<Image> {
+    let mut surface = surfaces::raster_n32_premul(counts.dimensions())?;
+    let mut paint = Paint::default();
+    paint
+        .set_color_filter(new(colors))
+        .set_blend_mode(BlendMode::Src);
+    surface.canvas().draw_image(counts, (0, 0), Some(&paint));
+    Some(surface.image_snapshot())
+}
@@ -1,5 +1,5 @@
 use crate::{
-    Blender, ColorFilter, Data, Matrix, Shader,
+    Blender, ColorFilter, Data, IPoint, Matrix, Shader,
     interop::{self, AsStr},
     prelude::*,
 };
@@ -10,6 +10,13 @@ use skia_bindings::{
 };
 use std::{fmt, marker::PhantomData, ops::DerefMut, ptr};
 
+mod debug_trace;
+mod diagnostics;
+mod uniforms;
+pub use debug_trace::*;
+pub use diagnostics::*;
+pub use uniforms::*;
+
 pub type Uniform = Handle<SkRuntimeEffect_Uniform>;
 unsafe_send_sync!(Uniform);
 
@@ -188,6 +195,33 @@ impl RuntimeEffect {
         .ok_or_else(|| error.to_string())
     }
 
+    /// Like [`Self::make_for_color_filter()`], but returns the compiler errors as [`Diagnostic`]s.
+    pub fn make_for_color_filter_with_diagnostics(
+        sksl: impl AsRef<str>,
+        options: Option<&Options<'_>>,
+    ) -> Result<RuntimeEffect, Vec<Diagnostic>> {
+        let sksl = sksl.as_ref();
+        Self::make_for_color_filter(sksl, options).map_err(|error| Diagnostic::parse(&error, sksl))
+    }
+
+    /// Like [`Self::make_for_shader()`], but returns the compiler errors as [`Diagnostic`]s.
+    pub fn make_for_shader_with_diagnostics(
+        sksl: impl AsRef<str>,
+        options: Option<&Options<'_>>,
+    ) -> Result<RuntimeEffect, Vec<Diagnostic>> {
+        let sksl = sksl.as_ref();
+        Self::make_for_shader(sksl, options).map_err(|error| Diagnostic::parse(&error, sksl))
+    }
+
+    /// Like [`Self::make_for_blender()`], but returns the compiler errors as [`Diagnostic`]s.
+    pub fn make_for_blender_with_diagnostics(
+        sksl: impl AsRef<str>,
+        options: Option<&Options<'_>>,
+    ) -> Result<RuntimeEffect, Vec<Diagnostic>> {
+        let sksl = sksl.as_ref();
+        Self::make_for_blender(sksl, options).map_err(|error| Diagnostic::parse(&error, sksl))
+    }
+
     fn construct_native_options(options: &Options<'_>) -> sb::SkRuntimeEffect_Options {
         construct(|opt| unsafe {
             sb::C_SkRuntimeEffect_Options_Construct(
@@ -270,7 +304,26 @@ impl RuntimeEffect {
         })
     }
 
-    // TODO: wrap MakeTraced
+    /// Returns a shader that records the execution of the pixel at `trace_coord` in a
+    /// [`DebugTrace`] when drawn with the raster backend.
+    ///
+    /// If `shader` is not a runtime shader, it is returned unchanged and without a trace.
+    pub fn make_traced(shader: impl Into<Shader>, trace_coord: impl Into<IPoint>) -> TracedShader {
+        let trace_coord: IPoint = trace_coord.into();
+        let mut debug_trace = ptr::null_mut();
+        let shader = Shader::from_ptr(unsafe {
+            sb::C_SkRuntimeEffect_MakeTraced(
+                shader.into().into_ptr(),
+                trace_coord.native(),
+                &mut debug_trace,
+            )
+        })
+        .unwrap();
+        TracedShader {
+            shader,
+            debug_trace: DebugTrace::from_ptr(debug_trace),
+        }
+    }
 
     pub fn source(&self) -> &str {
         let mut len = 0;
@@ -381,7 +434,7 @@ impl ChildPtr {
     //
     // Skia will copy the pointers and increase the reference counters if it uses the actual
     // objects.
-    pub(self) fn native(&self) -> Borrows<sb::SkRuntimeEffect_ChildPtr> {
+    pub(crate) fn native(&self) -> Borrows<sb::SkRuntimeEffect_ChildPtr> {
         let flattenable: *mut SkFlattenable = match self {
             // casting to &T &mut T is UB, so we don't use the base() indirection and directly cast
             // to a pointer.
@@ -400,24 +453,137 @@ impl ChildPtr {
     }
 }
 
-// TODO: wrap SkRuntimeEffectBuilder, SkRuntimeColorFilterBuilder,
-// SkRuntimeBlendBuilder
-
-pub type RuntimeShaderBuilder = Handle<sb::SkRuntimeShaderBuilder>;
-unsafe_send_sync!(RuntimeShaderBuilder);
-
-#[derive(Debug)]
-pub enum ShaderBuilderError {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuilderError {
     UniformSizeNotSupported,
+    ChildNotFound,
+    ChildTypeMismatch,
 }
-impl fmt::Display for ShaderBuilderError {
+
+#[deprecated(since = "0.102.0", note = "Use BuilderError")]
+pub type ShaderBuilderError = BuilderError;
+
+impl fmt::Display for BuilderError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ShaderBuilderError::UniformSizeNotSupported => write!(f, "Unsupported uniform size"),
+            BuilderError::UniformSizeNotSupported => write!(f, "Unsupported uniform size"),
+            BuilderError::ChildNotFound => write!(f, "Child not found"),
+            BuilderError::ChildTypeMismatch => write!(f, "Child type does not match"),
         }
     }
 }
-impl std::error::Error for ShaderBuilderError {}
+impl std::error::Error for BuilderError {}
+
+/// Implements the uniform and child setters of `SkRuntimeEffectBuilder`, the base class of all
+/// runtime effect builders.
+macro_rules! runtime_effect_builder {
+    ($builder:ty) => {
+        impl $builder {
+            fn native_builder_mut(&mut self) -> &mut sb::SkRuntimeEffectBuilder {
+                &mut self.native_mut()._base
+            }
+
+            /// Set float uniform values by name.
+            ///
+            /// Supported types are `float`, `float2`, `float3`, `float4`, `float2x2`, `float3x3`, `float4x4`.
+            ///
+            /// The data array must have the correct length for the corresponding uniform type:
+            /// - `float`: `[f32; 1]`
+            /// - `float2`: `[f32; 2]`
+            /// - `float3`: `[f32; 3]`
+            /// - `float4`: `[f32; 4]`
+            /// - `float2x2`: `[f32; 4]`
+            /// - `float3x3`: `[f32; 9]`
+            /// - `float4x4`: `[f32; 16]`
+            ///
+            pub fn set_uniform_float(
+                &mut self,
+                name: impl AsRef<str>,
+                data: &[f32],
+            ) -> Result<(), BuilderError> {
+                let name = name.as_ref();
+                let result = unsafe {
+                    sb::C_SkRuntimeEffectBuilder_setUniformFloat(
+                        self.native_builder_mut(),
+                        name.as_bytes().as_ptr() as _,
+                        name.len(),
+                        data.as_ptr() as _,
+                        data.len(),
+                    )
+                };
+                match result {
+                    ShaderBuilderUniformResult::Ok => Ok(()),
+                    ShaderBuilderUniformResult::Error => Err(BuilderError::UniformSizeNotSupported),
+                }
+            }
+
+            /// Set int uniform values by name.
+            ///
+            /// Supported types are `int`, `int2`, `int3`, `int4`.
+            ///
+            /// The data array must have the correct length for the corresponding uniform type:
+            /// - `int`: `[i32; 1]`
+            /// - `int2`: `[i32; 2]`
+            /// - `int3`: `[i32; 3]`
+            /// - `int4`: `[i32; 4]`
+            ///
+            pub fn set_uniform_int(
+                &mut self,
+                name: impl AsRef<str>,
+                data: &[i32],
+            ) -> Result<(), BuilderError> {
+                let name = name.as_ref();
+                let result = unsafe {
+                    sb::C_SkRuntimeEffectBuilder_setUniformInt(
+                        self.native_builder_mut(),
+                        name.as_bytes().as_ptr() as _,
+                        name.len(),
+                        data.as_ptr() as _,
+                        data.len(),
+                    )
+                };
+                match result {
+                    ShaderBuilderUniformResult::Ok => Ok(()),
+                    ShaderBuilderUniformResult::Error => Err(BuilderError::UniformSizeNotSupported),
+                }
+            }
+
+            /// Set a child by name. The child's type must match its SkSL declaration.
+            ///
+            /// `None` resets the child to `null`, which Skia evaluates as a transparent shader,
+            /// a pass-through color filter, or a `SrcOver` blender.
+            pub fn set_child(
+                &mut self,
+                name: impl AsRef<str>,
+                child: impl Into<Option<ChildPtr>>,
+            ) -> Result<(), BuilderError> {
+                let name = name.as_ref();
+                let child = child.into();
+                let native_child = child.as_ref().map(|c| c.native());
+                let result = unsafe {
+                    sb::C_SkRuntimeEffectBuilder_setChild(
+                        self.native_builder_mut(),
+                        name.as_bytes().as_ptr() as _,
+                        name.len(),
+                        native_child
+                            .as_ref()
+                            .map(|c| &**c as *const _)
+                            .unwrap_or(ptr::null()),
+                    )
+                };
+                match result {
+                    sb::BuilderChildResult::Ok => Ok(()),
+                    sb::BuilderChildResult::NotFound => Err(BuilderError::ChildNotFound),
+                    sb::BuilderChildResult::TypeMismatch => Err(BuilderError::ChildTypeMismatch),
+                }
+            }
+        }
+    };
+}
+
+pub type RuntimeShaderBuilder = Handle<sb::SkRuntimeShaderBuilder>;
+unsafe_send_sync!(RuntimeShaderBuilder);
+require_base_type!(sb::SkRuntimeShaderBuilder, sb::SkRuntimeEffectBuilder);
 
 impl NativeDrop for sb::SkRuntimeShaderBuilder {
     fn drop(&mut self) {
@@ -443,75 +609,70 @@ impl RuntimeShaderBuilder {
             Shader::from_ptr(shader)
         }
     }
-    /// Set float uniform values by name.
-    ///
-    /// Supported types are `float`, `float2`, `float3`, `float4`, `float2x2`, `float3x3`, `float4x4`.
-    ///
-    /// The data array must have the correct length for the corresponding uniform type:
-    /// - `float`: `[f32; 1]`
-    /// - `float2`: `[f32; 2]`
-    /// - `float3`: `[f32; 3]`
-    /// - `float4`: `[f32; 4]`
-    /// - `float2x2`: `[f32; 4]`
-    /// - `float3x3`: `[f32; 9]`
-    /// - `float4x4`: `[f32; 16]`
-    ///
-    pub fn set_uniform_float(
-        &mut self,
-        name: impl AsRef<str>,
-        data: &[f32],
-    ) -> Result<(), ShaderBuilderError> {
-        let name = name.as_ref();
-        let result = unsafe {
-            sb::C_SkRuntimeShaderBuilder_setUniformFloat(
-                self.native_mut() as _,
-                name.as_bytes().as_ptr() as _,
-                name.len(),
-                data.as_ptr() as _,
-                data.len(),
-            )
-        };
-        match result {
-            ShaderBuilderUniformResult::Ok => Ok(()),
-            ShaderBuilderUniformResult::Error => Err(ShaderBuilderError::UniformSizeNotSupported),
+}
+
+runtime_effect_builder!(RuntimeShaderBuilder);
+
+pub type RuntimeColorFilterBuilder = Handle<sb::SkRuntimeColorFilterBuilder>;
+unsafe_send_sync!(RuntimeColorFilterBuilder);
+require_base_type!(sb::SkRuntimeColorFilterBuilder, sb::SkRuntimeEffectBuilder);
+
+impl NativeDrop for sb::SkRuntimeColorFilterBuilder {
+    fn drop(&mut self) {
+        unsafe {
+            sb::C_SkRuntimeColorFilterBuilder_destruct(self);
         }
     }
-    /// Set int uniform values by name.
-    ///
-    /// Supported types are `int`, `int2`, `int3`, `int4`.
-    ///
-    /// The data array must have the correct length for the corresponding uniform type:
-    /// - `int`: `[i32; 1]`
-    /// - `int2`: `[i32; 2]`
-    /// - `int3`: `[i32; 3]`
-    /// - `int4`: `[i32; 4]`
-    ///
-    ///
-    pub fn set_uniform_int(
-        &mut self,
-        name: impl AsRef<str>,
-        data: &[i32],
-    ) -> Result<(), ShaderBuilderError> {
-        let name = name.as_ref();
-        let result = unsafe {
-            sb::C_SkRuntimeShaderBuilder_setUniformInt(
-                self.native_mut() as _,
-                name.as_bytes().as_ptr() as _,
-                name.len(),
-                data.as_ptr() as _,
-                data.len(),
-            )
-        };
-        match result {
-            ShaderBuilderUniformResult::Ok => Ok(()),
-            ShaderBuilderUniformResult::Error => Err(ShaderBuilderError::UniformSizeNotSupported),
+}
+
+impl RuntimeColorFilterBuilder {
+    pub fn new(effect: RuntimeEffect) -> Self {
+        Self::construct(|builder| unsafe {
+            let effect: *mut SkRuntimeEffect = effect.into_ptr() as _;
+            sb::C_SkRuntimeColorFilterBuilder_Construct(builder, effect)
+        })
+    }
+
+    pub fn make_color_filter(&self) -> Option<ColorFilter> {
+        ColorFilter::from_ptr(unsafe {
+            sb::C_SkRuntimeColorFilterBuilder_makeColorFilter(self.native())
+        })
+    }
+}
+
+runtime_effect_builder!(RuntimeColorFilterBuilder);
+
+pub type RuntimeBlendBuilder = Handle<sb::SkRuntimeBlendBuilder>;
+unsafe_send_sync!(RuntimeBlendBuilder);
+require_base_type!(sb::SkRuntimeBlendBuilder, sb::SkRuntimeEffectBuilder);
+
+impl NativeDrop for sb::SkRuntimeBlendBuilder {
+    fn drop(&mut self) {
+        unsafe {
+            sb::C_SkRuntimeBlendBuilder_destruct(self);
         }
     }
 }
 
+impl RuntimeBlendBuilder {
+    pub fn new(effect: RuntimeEffect) -> Self {
+        Self::construct(|builder| unsafe {
+            let effect: *mut SkRuntimeEffect = effect.into_ptr() as _;
+            sb::C_SkRuntimeBlendBuilder_Construct(builder, effect)
+        })
+    }
+
+    pub fn make_blender(&self) -> Option<Blender> {
+        Blender::from_ptr(unsafe { sb::C_SkRuntimeBlendBuilder_makeBlender(self.native()) })
+    }
+}
+
+runtime_effect_builder!(RuntimeBlendBuilder);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Color;
 
     // <https://github.com/rust-skia/rust-skia/discussions/1133>
     #[allow(unused)]
@@ -520,4 +681,70 @@ mod tests {
         RuntimeEffect::make_for_shader("", None);
         RuntimeEffect::make_for_blender("", None);
     }
+
+    #[test]
+    fn color_filter_builder_sets_uniforms_and_children() {
+        let effect = RuntimeEffect::make_for_color_filter(
+            "uniform half4 tint; uniform colorFilter inner; \
+             half4 main(half4 c) { return inner.eval(tint); }",
+            None,
+        )
+        .unwrap();
+        let mut builder = RuntimeColorFilterBuilder::new(effect);
+        builder
+            .set_uniform_float("tint", &[1.0, 0.0, 0.0, 1.0])
+            .unwrap();
+        assert_eq!(
+            builder.set_child("inner", ChildPtr::Shader(Shader::color(Color::BLUE))),
+            Err(BuilderError::ChildTypeMismatch)
+        );
+        assert_eq!(
+            builder.set_child("outer", None),
+            Err(BuilderError::ChildNotFound)
+        );
+        builder.set_child("inner", None).unwrap();
+
+        let filter = builder.make_color_filter().unwrap();
+        assert_eq!(filter.filter_color(Color::WHITE), Color::RED);
+    }
+
+    #[test]
+    fn traced_shader_records_execution() {
+        let effect = RuntimeEffect::make_for_shader(
+            "half4 main(float2 p) { half r = half(p.x) / 4; return half4(r, 0, 0, 1); }",
+            None,
+        )
+        .unwrap();
+        let shader = effect.make_shader(Data::new_empty(), &[], None).unwrap();
+        let traced = RuntimeEffect::make_traced(shader, (1, 1));
+        let debug_trace = traced.debug_trace.unwrap();
+
+        let mut surface = crate::surfaces::raster_n32_premul((4, 4)).unwrap();
+        let mut paint = crate::Paint::default();
+        paint.set_shader(traced.shader);
+        surface.canvas().draw_paint(&paint);
+
+        let json = debug_trace.to_json();
+        assert!(json.starts_with('{'));
+        assert!(json.contains("\"trace\""));
+        assert!(!debug_trace.dump().is_empty());
+    }
+
+    #[test]
+    fn blend_builder_makes_blender() {
+        let effect = RuntimeEffect::make_for_blender(
+            "uniform blender inner; \
+             half4 main(half4 src, half4 dst) { return inner.eval(src, dst); }",
+            None,
+        )
+        .unwrap();
+        let mut builder = RuntimeBlendBuilder::new(effect);
+        builder
+            .set_child(
+                "inner",
+                ChildPtr::Blender(Blender::mode(crate::BlendMode::Src)),
+            )
+            .unwrap();
+        assert!(builder.make_blender().is_some());
+    }
 }
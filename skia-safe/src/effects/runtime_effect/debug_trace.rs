@@ -0,0 +1,54 @@
+use std::{fmt, io};
+
+use crate::{
+    Shader,
+    interop::{DynamicMemoryWStream, RustWStream},
+    prelude::*,
+};
+use skia_bindings::{self as sb, SkRefCntBase, SkSL_DebugTrace};
+
+/// The execution trace of a single pixel, recorded by a shader returned from
+/// [`super::RuntimeEffect::make_traced()`].
+///
+/// The trace is filled in when the shader is drawn with the raster backend.
+pub type DebugTrace = RCHandle<SkSL_DebugTrace>;
+
+impl NativeRefCountedBase for SkSL_DebugTrace {
+    type Base = SkRefCntBase;
+}
+
+impl fmt::Debug for DebugTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebugTrace").finish()
+    }
+}
+
+impl DebugTrace {
+    /// Writes the trace as JSON, the format read by Skia's SkSL debugger.
+    pub fn write_json(&self, writer: &mut impl io::Write) {
+        let mut stream = RustWStream::new(writer);
+        unsafe { sb::C_SkSL_DebugTrace_writeTrace(self.native(), stream.stream_mut()) }
+    }
+
+    /// Returns the trace as JSON.
+    pub fn to_json(&self) -> String {
+        let mut stream = DynamicMemoryWStream::new();
+        unsafe { sb::C_SkSL_DebugTrace_writeTrace(self.native(), stream.native_mut().base_mut()) }
+        String::from_utf8_lossy(stream.detach_as_data().as_bytes()).into_owned()
+    }
+
+    /// Returns a human-readable listing of the executed lines and the values of the variables.
+    pub fn dump(&self) -> String {
+        let mut stream = DynamicMemoryWStream::new();
+        unsafe { sb::C_SkSL_DebugTrace_dump(self.native(), stream.native_mut().base_mut()) }
+        String::from_utf8_lossy(stream.detach_as_data().as_bytes()).into_owned()
+    }
+}
+
+/// The result of [`super::RuntimeEffect::make_traced()`].
+#[derive(Clone, Debug)]
+pub struct TracedShader {
+    pub shader: Shader,
+    /// `None` if the shader is not a runtime shader.
+    pub debug_trace: Option<DebugTrace>,
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+/// A single diagnostic of the SkSL compiler, as returned by
+/// [`super::RuntimeEffect::make_for_shader_with_diagnostics()`] and its siblings.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The 1-based line, if the diagnostic refers to a location in the source.
+    pub line: Option<usize>,
+    /// The 1-based column in characters, if the compiler reported the exact location.
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{line}:{column}: ")?,
+            (Some(line), None) => write!(f, "{line}: ")?,
+            _ => {}
+        }
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+impl Diagnostic {
+    /// Parses the error text of the SkSL compiler, which is the only form Skia reports errors of
+    /// runtime effects in.
+    ///
+    /// `sksl` is the source the effect was compiled from. It is used to map the compiler's caret
+    /// markers, which count tabs as four spaces, to character columns.
+    pub(crate) fn parse(error: &str, sksl: &str) -> Vec<Diagnostic> {
+        let source_lines: Vec<&str> = sksl.lines().collect();
+        let lines: Vec<&str> = error.lines().collect();
+        let mut diagnostics = Vec::new();
+
+        let mut i = 0;
+        while i < lines.len() {
+            let text = lines[i];
+            i += 1;
+
+            let (severity, rest) = if let Some(rest) = text.strip_prefix("error: ") {
+                (Severity::Error, rest)
+            } else if let Some(rest) = text.strip_prefix("warning: ") {
+                (Severity::Warning, rest)
+            } else {
+                if !text.trim().is_empty() && !is_summary(text) {
+                    diagnostics.push(Diagnostic {
+                        severity: Severity::Error,
+                        line: None,
+                        column: None,
+                        message: text.to_owned(),
+                    });
+                }
+                continue;
+            };
+
+            let (line, message) = match rest.split_once(": ") {
+                Some((line, message)) if line.parse::<usize>().is_ok() => {
+                    (line.parse::<usize>().ok(), message)
+                }
+                _ => (None, rest),
+            };
+
+            // The compiler echoes the source line and marks the location with carets below it.
+            let mut column = None;
+            if let (Some(line), Some(echo), Some(carets)) = (line, lines.get(i), lines.get(i + 1)) {
+                if is_caret_line(carets) {
+                    let source_line = line
+                        .checked_sub(1)
+                        .and_then(|l| source_lines.get(l))
+                        .copied();
+                    column = caret_column(echo, carets, source_line);
+                    i += 2;
+                }
+            }
+
+            diagnostics.push(Diagnostic {
+                severity,
+                line,
+                column,
+                message: message.to_owned(),
+            });
+        }
+
+        diagnostics
+    }
+}
+
+const TAB_WIDTH: usize = 4;
+const ELLIPSIS: &str = "...";
+
+fn is_summary(text: &str) -> bool {
+    match text.split_once(' ') {
+        Some((count, kind)) => {
+            count.parse::<usize>().is_ok()
+                && matches!(kind, "error" | "errors" | "warning" | "warnings")
+        }
+        None => false,
+    }
+}
+
+fn is_caret_line(text: &str) -> bool {
+    text.contains('^') && text.chars().all(|c| c == ' ' || c == '^')
+}
+
+/// Resolves the 1-based character column of the first caret.
+fn caret_column(echo: &str, carets: &str, source_line: Option<&str>) -> Option<usize> {
+    let caret = carets.find('^')?;
+    let Some(source_line) = source_line else {
+        return Some(caret + 1);
+    };
+
+    let expanded: String = source_line
+        .chars()
+        .flat_map(|c| {
+            let (c, n) = if c == '\t' { (' ', TAB_WIDTH) } else { (c, 1) };
+            std::iter::repeat_n(c, n)
+        })
+        .collect();
+
+    // Long lines are shortened to the text surrounding the location and prefixed with an ellipsis.
+    let expanded_column = match echo.strip_prefix(ELLIPSIS) {
+        Some(snippet) => {
+            let start = expanded.find(snippet.trim_end())?;
+            start + caret.checked_sub(ELLIPSIS.len())?
+        }
+        None => caret,
+    };
+
+    let mut width = 0;
+    for (column, c) in source_line.chars().enumerate() {
+        width += if c == '\t' { TAB_WIDTH } else { 1 };
+        if width > expanded_column {
+            return Some(column + 1);
+        }
+    }
+    Some(source_line.chars().count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RuntimeEffect;
+
+    #[test]
+    fn parses_located_and_unlocated_errors() {
+        let sksl = "half4 main(float2 p) {\n\treturn half4(q);\n}";
+        let error = "error: 2: unknown identifier 'q'\n    return half4(q);\n                 ^\n\
+                     error: missing 'main' function\n2 errors\n";
+
+        let diagnostics = Diagnostic::parse(error, sksl);
+        assert_eq!(
+            diagnostics,
+            [
+                Diagnostic {
+                    severity: Severity::Error,
+                    line: Some(2),
+                    column: Some(15),
+                    message: "unknown identifier 'q'".into(),
+                },
+                Diagnostic {
+                    severity: Severity::Error,
+                    line: None,
+                    column: None,
+                    message: "missing 'main' function".into(),
+                }
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "2:15: error: unknown identifier 'q'"
+        );
+    }
+
+    #[test]
+    fn parses_compiler_output() {
+        let sksl = "half4 main(float2 p) {\n    return half4(q);\n}";
+        let diagnostics = RuntimeEffect::make_for_shader_with_diagnostics(sksl, None).unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert!(diagnostics[0].message.contains("'q'"));
+    }
+}
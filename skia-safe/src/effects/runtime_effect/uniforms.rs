@@ -0,0 +1,468 @@
+use std::{fmt, marker::PhantomData};
+
+use super::uniform;
+use crate::{Color4f, Data, IPoint, M44, Matrix, Point, RuntimeEffect, V2, V3, V4};
+
+#[cfg(feature = "uniforms-derive")]
+pub use skia_uniforms_macros::Uniforms;
+
+/// A struct whose fields are bound to the uniforms of a [`RuntimeEffect`].
+///
+/// Usually implemented with `#[derive(Uniforms)]` (feature `uniforms-derive`), which binds every
+/// field to the uniform of the same name, or to the name given with `#[uniform(name = "...")]`.
+pub trait Uniforms {
+    /// The uniforms the fields bind to, in field order.
+    const FIELDS: &'static [UniformField];
+
+    /// Writes all fields, in the order of [`Self::FIELDS`].
+    fn write_fields(&self, writer: &mut UniformWriter<'_>);
+
+    /// Validates the binding against `effect` and returns the uniform data.
+    ///
+    /// To create the data for the same effect repeatedly, use [`UniformBinding`].
+    fn to_uniform_data(&self, effect: &RuntimeEffect) -> Result<Data, UniformsError>
+    where
+        Self: Sized,
+    {
+        UniformBinding::new(effect).map(|binding| binding.make_data(self))
+    }
+}
+
+/// The declaration of one field of a [`Uniforms`] struct.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct UniformField {
+    pub name: &'static str,
+    pub ty: uniform::Type,
+    pub array_count: Option<usize>,
+}
+
+impl UniformField {
+    pub const fn of<T: UniformValue + ?Sized>(name: &'static str) -> Self {
+        Self {
+            name,
+            ty: T::TYPE,
+            array_count: T::ARRAY_COUNT,
+        }
+    }
+}
+
+/// A value that can be bound to one uniform.
+///
+/// Implemented for all [`UniformElement`] types and for arrays of them, which bind to SkSL
+/// arrays of the same length.
+pub trait UniformValue {
+    const TYPE: uniform::Type;
+    const ARRAY_COUNT: Option<usize> = None;
+
+    /// Writes the value into `out`, which is exactly the size of the uniform.
+    fn write(&self, out: &mut [u8]);
+}
+
+/// A single, non-array value of an SkSL uniform type.
+///
+/// `float2`, `float3` and `float4` uniforms bind to [`V2`] / [`Point`], [`V3`] and [`V4`] /
+/// [`Color4f`], `float3x3` binds to [`Matrix`], `float4x4` to [`M44`], and `int2` to [`IPoint`].
+/// Half precision uniforms use the same layout as their full precision counterparts.
+pub trait UniformElement {
+    const TYPE: uniform::Type;
+
+    /// Writes the value into `out`, which is exactly the size of [`Self::TYPE`].
+    fn write(&self, out: &mut [u8]);
+}
+
+impl<T: UniformElement> UniformValue for T {
+    const TYPE: uniform::Type = T::TYPE;
+
+    fn write(&self, out: &mut [u8]) {
+        UniformElement::write(self, out)
+    }
+}
+
+impl<T: UniformElement, const N: usize> UniformValue for [T; N] {
+    const TYPE: uniform::Type = T::TYPE;
+    const ARRAY_COUNT: Option<usize> = Some(N);
+
+    fn write(&self, out: &mut [u8]) {
+        let stride = out.len() / N.max(1);
+        for (element, out) in self.iter().zip(out.chunks_exact_mut(stride)) {
+            UniformElement::write(element, out)
+        }
+    }
+}
+
+fn write_f32s(out: &mut [u8], values: &[f32]) {
+    for (v, out) in values.iter().zip(out.chunks_exact_mut(4)) {
+        out.copy_from_slice(&v.to_ne_bytes());
+    }
+}
+
+fn write_i32s(out: &mut [u8], values: &[i32]) {
+    for (v, out) in values.iter().zip(out.chunks_exact_mut(4)) {
+        out.copy_from_slice(&v.to_ne_bytes());
+    }
+}
+
+impl UniformElement for f32 {
+    const TYPE: uniform::Type = uniform::Type::Float;
+
+    fn write(&self, out: &mut [u8]) {
+        write_f32s(out, &[*self])
+    }
+}
+
+impl UniformElement for V2 {
+    const TYPE: uniform::Type = uniform::Type::Float2;
+
+    fn write(&self, out: &mut [u8]) {
+        write_f32s(out, &[self.x, self.y])
+    }
+}
+
+impl UniformElement for Point {
+    const TYPE: uniform::Type = uniform::Type::Float2;
+
+    fn write(&self, out: &mut [u8]) {
+        write_f32s(out, &[self.x, self.y])
+    }
+}
+
+impl UniformElement for V3 {
+    const TYPE: uniform::Type = uniform::Type::Float3;
+
+    fn write(&self, out: &mut [u8]) {
+        write_f32s(out, &[self.x, self.y, self.z])
+    }
+}
+
+impl UniformElement for V4 {
+    const TYPE: uniform::Type = uniform::Type::Float4;
+
+    fn write(&self, out: &mut [u8]) {
+        write_f32s(out, &[self.x, self.y, self.z, self.w])
+    }
+}
+
+impl UniformElement for Color4f {
+    const TYPE: uniform::Type = uniform::Type::Float4;
+
+    fn write(&self, out: &mut [u8]) {
+        write_f32s(out, self.as_array())
+    }
+}
+
+impl UniformElement for Matrix {
+    const TYPE: uniform::Type = uniform::Type::Float3x3;
+
+    fn write(&self, out: &mut [u8]) {
+        // SkSL matrices are column-major.
+        let m = self;
+        write_f32s(out, &[m[0], m[3], m[6], m[1], m[4], m[7], m[2], m[5], m[8]])
+    }
+}
+
+impl UniformElement for M44 {
+    const TYPE: uniform::Type = uniform::Type::Float4x4;
+
+    fn write(&self, out: &mut [u8]) {
+        let mut values = [0.0; 16];
+        self.get_col_major(&mut values);
+        write_f32s(out, &values)
+    }
+}
+
+impl UniformElement for i32 {
+    const TYPE: uniform::Type = uniform::Type::Int;
+
+    fn write(&self, out: &mut [u8]) {
+        write_i32s(out, &[*self])
+    }
+}
+
+impl UniformElement for IPoint {
+    const TYPE: uniform::Type = uniform::Type::Int2;
+
+    fn write(&self, out: &mut [u8]) {
+        write_i32s(out, &[self.x, self.y])
+    }
+}
+
+/// Writes the fields of a [`Uniforms`] struct to the locations the effect expects them.
+pub struct UniformWriter<'a> {
+    data: &'a mut [u8],
+    slots: std::slice::Iter<'a, Slot>,
+}
+
+impl fmt::Debug for UniformWriter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UniformWriter")
+            .field("size", &self.data.len())
+            .finish()
+    }
+}
+
+impl UniformWriter<'_> {
+    /// Writes the value of the next field.
+    pub fn write<T: UniformValue + ?Sized>(&mut self, value: &T) {
+        let slot = self
+            .slots
+            .next()
+            .expect("more fields written than declared in Uniforms::FIELDS");
+        value.write(&mut self.data[slot.offset..slot.offset + slot.size]);
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Slot {
+    offset: usize,
+    size: usize,
+}
+
+/// A [`Uniforms`] struct validated against the uniforms of a [`RuntimeEffect`].
+///
+/// Every uniform of the effect must be bound by exactly one field of a matching type and array
+/// length.
+pub struct UniformBinding<U> {
+    effect: RuntimeEffect,
+    slots: Vec<Slot>,
+    _uniforms: PhantomData<fn(&U)>,
+}
+
+impl<U> fmt::Debug for UniformBinding<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UniformBinding")
+            .field("effect", &self.effect)
+            .finish()
+    }
+}
+
+impl<U: Uniforms> UniformBinding<U> {
+    pub fn new(effect: &RuntimeEffect) -> Result<Self, UniformsError> {
+        let mut slots = Vec::with_capacity(U::FIELDS.len());
+        for field in U::FIELDS {
+            let uniform = effect
+                .find_uniform(field.name)
+                .ok_or(UniformsError::UnknownUniform { name: field.name })?;
+            if uniform.ty() != field.ty {
+                return Err(UniformsError::TypeMismatch {
+                    name: field.name,
+                    expected: uniform.ty(),
+                    found: field.ty,
+                });
+            }
+            let count = uniform.is_array().then(|| uniform.count() as usize);
+            if count != field.array_count {
+                return Err(UniformsError::ArrayCountMismatch {
+                    name: field.name,
+                    expected: count,
+                    found: field.array_count,
+                });
+            }
+            slots.push(Slot {
+                offset: uniform.offset(),
+                size: uniform.size_in_bytes(),
+            });
+        }
+
+        if let Some(unbound) = effect
+            .uniforms()
+            .iter()
+            .find(|u| !U::FIELDS.iter().any(|f| f.name == u.name()))
+        {
+            return Err(UniformsError::UnboundUniform {
+                name: unbound.name().to_owned(),
+            });
+        }
+
+        Ok(Self {
+            effect: effect.clone(),
+            slots,
+            _uniforms: PhantomData,
+        })
+    }
+
+    pub fn effect(&self) -> &RuntimeEffect {
+        &self.effect
+    }
+
+    /// Returns the uniform data for `uniforms` in the layout of the effect.
+    pub fn make_data(&self, uniforms: &U) -> Data {
+        let mut data = vec![0u8; self.effect.uniform_size()];
+        let mut writer = UniformWriter {
+            data: &mut data,
+            slots: self.slots.iter(),
+        };
+        uniforms.write_fields(&mut writer);
+        debug_assert!(
+            writer.slots.next().is_none(),
+            "fewer fields written than declared in Uniforms::FIELDS"
+        );
+        Data::new_copy(&data)
+    }
+}
+
+/// The reasons why a [`Uniforms`] struct does not match the uniforms of an effect.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum UniformsError {
+    /// A field binds to a uniform the effect does not declare.
+    UnknownUniform { name: &'static str },
+    /// A uniform of the effect is not bound by any field.
+    UnboundUniform { name: String },
+    TypeMismatch {
+        name: &'static str,
+        expected: uniform::Type,
+        found: uniform::Type,
+    },
+    /// A field's array length differs from the uniform's. `None` denotes a non-array.
+    ArrayCountMismatch {
+        name: &'static str,
+        expected: Option<usize>,
+        found: Option<usize>,
+    },
+}
+
+impl fmt::Display for UniformsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UniformsError::UnknownUniform { name } => {
+                write!(f, "The effect has no uniform named '{name}'")
+            }
+            UniformsError::UnboundUniform { name } => {
+                write!(f, "Uniform '{name}' is not bound to a field")
+            }
+            UniformsError::TypeMismatch {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Uniform '{name}' is of type {expected:?}, but the field is of type {found:?}"
+            ),
+            UniformsError::ArrayCountMismatch {
+                name,
+                expected,
+                found,
+            } => {
+                let count = |c: &Option<usize>| match c {
+                    Some(n) => format!("an array of {n}"),
+                    None => "not an array".into(),
+                };
+                write!(
+                    f,
+                    "Uniform '{name}' is {}, but the field is {}",
+                    count(expected),
+                    count(found)
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for UniformsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKSL: &str = r#"
+        uniform float2 offset;
+        uniform half4 color;
+        uniform float weights[3];
+        uniform int mode;
+        half4 main(float2 p) { return color; }
+    "#;
+
+    struct Params {
+        offset: Point,
+        color: Color4f,
+        weights: [f32; 3],
+        mode: i32,
+    }
+
+    impl Uniforms for Params {
+        const FIELDS: &'static [UniformField] = &[
+            UniformField::of::<Point>("offset"),
+            UniformField::of::<Color4f>("color"),
+            UniformField::of::<[f32; 3]>("weights"),
+            UniformField::of::<i32>("mode"),
+        ];
+
+        fn write_fields(&self, writer: &mut UniformWriter<'_>) {
+            writer.write(&self.offset);
+            writer.write(&self.color);
+            writer.write(&self.weights);
+            writer.write(&self.mode);
+        }
+    }
+
+    #[test]
+    fn writes_fields_at_effect_offsets() {
+        let effect = RuntimeEffect::make_for_shader(SKSL, None).unwrap();
+        let params = Params {
+            offset: Point::new(1.0, 2.0),
+            color: Color4f::new(0.25, 0.5, 0.75, 1.0),
+            weights: [3.0, 4.0, 5.0],
+            mode: 7,
+        };
+        let data = params.to_uniform_data(&effect).unwrap();
+        assert_eq!(data.len(), effect.uniform_size());
+
+        let read_f32 =
+            |offset: usize| f32::from_ne_bytes(data[offset..offset + 4].try_into().unwrap());
+        let offset_of = |name: &str| effect.find_uniform(name).unwrap().offset();
+        assert_eq!(read_f32(offset_of("offset") + 4), 2.0);
+        assert_eq!(read_f32(offset_of("color") + 8), 0.75);
+        assert_eq!(read_f32(offset_of("weights") + 8), 5.0);
+        let mode = offset_of("mode");
+        assert_eq!(
+            i32::from_ne_bytes(data[mode..mode + 4].try_into().unwrap()),
+            7
+        );
+    }
+
+    #[test]
+    fn rejects_mismatching_layouts() {
+        struct Mismatch;
+        impl Uniforms for Mismatch {
+            const FIELDS: &'static [UniformField] = &[
+                UniformField::of::<Point>("offset"),
+                UniformField::of::<V3>("color"),
+            ];
+
+            fn write_fields(&self, _writer: &mut UniformWriter<'_>) {}
+        }
+
+        let effect = RuntimeEffect::make_for_shader(SKSL, None).unwrap();
+        assert_eq!(
+            UniformBinding::<Mismatch>::new(&effect).unwrap_err(),
+            UniformsError::TypeMismatch {
+                name: "color",
+                expected: uniform::Type::Float4,
+                found: uniform::Type::Float3
+            }
+        );
+    }
+
+    #[cfg(feature = "uniforms-derive")]
+    #[test]
+    fn derived_uniforms_bind_by_name() {
+        #[derive(Uniforms)]
+        #[uniforms(crate = "crate")]
+        struct Derived {
+            #[uniform(name = "offset")]
+            translate: Point,
+            color: Color4f,
+            weights: [f32; 2],
+            mode: i32,
+        }
+
+        let effect = RuntimeEffect::make_for_shader(SKSL, None).unwrap();
+        assert_eq!(
+            UniformBinding::<Derived>::new(&effect).unwrap_err(),
+            UniformsError::ArrayCountMismatch {
+                name: "weights",
+                expected: Some(3),
+                found: Some(2)
+            }
+        );
+    }
+}
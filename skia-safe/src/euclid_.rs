@@ -0,0 +1,132 @@
+//! Conversions to and from the types of the [`euclid`] crate.
+//!
+//! The units of the `euclid` types are not checked.
+
+use euclid::{Box2D, Point2D, Size2D, Transform2D, Transform3D, Vector2D};
+
+use crate::{M44, Matrix, Point, Rect, Size, scalar};
+
+impl<U> From<Point2D<scalar, U>> for Point {
+    fn from(p: Point2D<scalar, U>) -> Self {
+        Point::new(p.x, p.y)
+    }
+}
+
+impl<U> From<Point> for Point2D<scalar, U> {
+    fn from(p: Point) -> Self {
+        Point2D::new(p.x, p.y)
+    }
+}
+
+impl<U> From<Vector2D<scalar, U>> for Point {
+    fn from(v: Vector2D<scalar, U>) -> Self {
+        Point::new(v.x, v.y)
+    }
+}
+
+impl<U> From<Point> for Vector2D<scalar, U> {
+    fn from(v: Point) -> Self {
+        Vector2D::new(v.x, v.y)
+    }
+}
+
+impl<U> From<Size2D<scalar, U>> for Size {
+    fn from(s: Size2D<scalar, U>) -> Self {
+        Size::new(s.width, s.height)
+    }
+}
+
+impl<U> From<Size> for Size2D<scalar, U> {
+    fn from(s: Size) -> Self {
+        Size2D::new(s.width, s.height)
+    }
+}
+
+impl<U> From<Box2D<scalar, U>> for Rect {
+    fn from(b: Box2D<scalar, U>) -> Self {
+        Rect::new(b.min.x, b.min.y, b.max.x, b.max.y)
+    }
+}
+
+impl<U> From<Rect> for Box2D<scalar, U> {
+    fn from(r: Rect) -> Self {
+        Box2D::new(Point2D::new(r.left, r.top), Point2D::new(r.right, r.bottom))
+    }
+}
+
+impl<U> From<euclid::Rect<scalar, U>> for Rect {
+    fn from(r: euclid::Rect<scalar, U>) -> Self {
+        r.to_box2d().into()
+    }
+}
+
+impl<U> From<Rect> for euclid::Rect<scalar, U> {
+    fn from(r: Rect) -> Self {
+        Box2D::from(r).to_rect()
+    }
+}
+
+impl<Src, Dst> From<Transform2D<scalar, Src, Dst>> for Matrix {
+    fn from(t: Transform2D<scalar, Src, Dst>) -> Self {
+        // euclid transforms row vectors, so its matrices are the transposed ones of Skia.
+        Matrix::new_all(t.m11, t.m21, t.m31, t.m12, t.m22, t.m32, 0.0, 0.0, 1.0)
+    }
+}
+
+/// Fails and returns the matrix if it has perspective.
+impl<Src, Dst> TryFrom<Matrix> for Transform2D<scalar, Src, Dst> {
+    type Error = Matrix;
+
+    fn try_from(m: Matrix) -> Result<Self, Self::Error> {
+        let [sx, ky, kx, sy, tx, ty] = m.to_affine().ok_or(m)?;
+        Ok(Transform2D::new(sx, ky, kx, sy, tx, ty))
+    }
+}
+
+impl<Src, Dst> From<Transform3D<scalar, Src, Dst>> for M44 {
+    fn from(t: Transform3D<scalar, Src, Dst>) -> Self {
+        // The row-major values of a euclid transform are the column-major values of `M44`.
+        M44::col_major(&t.to_array())
+    }
+}
+
+impl<Src, Dst> From<M44> for Transform3D<scalar, Src, Dst> {
+    fn from(m: M44) -> Self {
+        let mut values = [0.0; 16];
+        m.get_col_major(&mut values);
+        Transform3D::from_array(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use euclid::{Angle, default};
+
+    use crate::{M44, Matrix, Point, Rect};
+
+    #[test]
+    fn round_trips() {
+        let p: default::Point2D<f32> = Point::new(1.0, 2.0).into();
+        assert_eq!(p, default::Point2D::new(1.0, 2.0));
+        assert_eq!(Point::from(p), Point::new(1.0, 2.0));
+
+        let rect = Rect::new(1.0, 2.0, 4.0, 6.0);
+        let r: default::Rect<f32> = rect.into();
+        assert_eq!(r, default::Rect::new((1.0, 2.0).into(), (3.0, 4.0).into()));
+        assert_eq!(Rect::from(r), rect);
+        assert_eq!(Rect::from(default::Box2D::from(rect)), rect);
+
+        let transform = default::Transform2D::rotation(Angle::degrees(90.0))
+            .then_translate(default::Vector2D::new(10.0, 0.0));
+        let matrix = Matrix::from(transform);
+        let mapped = matrix.map_xy(1.0, 0.0);
+        let expected = transform.transform_point(default::Point2D::new(1.0, 0.0));
+        assert!((mapped.x - expected.x).abs() < 1e-5 && (mapped.y - expected.y).abs() < 1e-5);
+        assert_eq!(default::Transform2D::try_from(matrix), Ok(transform));
+
+        let m44 = M44::translate(1.0, 2.0, 3.0);
+        let t: default::Transform3D<f32> = m44.clone().into();
+        assert_eq!(t, default::Transform3D::translation(1.0, 2.0, 3.0));
+        assert_eq!(M44::from(t), m44);
+    }
+}
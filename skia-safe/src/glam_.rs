@@ -0,0 +1,112 @@
+//! Conversions to and from the types of the [`glam`] crate.
+
+use crate::{M44, Matrix, Point, Point3};
+
+impl From<glam::Vec2> for Point {
+    fn from(v: glam::Vec2) -> Self {
+        Point::new(v.x, v.y)
+    }
+}
+
+impl From<Point> for glam::Vec2 {
+    fn from(p: Point) -> Self {
+        glam::Vec2::new(p.x, p.y)
+    }
+}
+
+impl From<glam::Vec3> for Point3 {
+    fn from(v: glam::Vec3) -> Self {
+        Point3::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Point3> for glam::Vec3 {
+    fn from(p: Point3) -> Self {
+        glam::Vec3::new(p.x, p.y, p.z)
+    }
+}
+
+impl From<glam::Mat3> for Matrix {
+    fn from(m: glam::Mat3) -> Self {
+        // The row-major values of `Matrix` are the column-major values of the transposed matrix.
+        let mut matrix = Matrix::default();
+        matrix.set_9(&m.transpose().to_cols_array());
+        matrix
+    }
+}
+
+impl From<Matrix> for glam::Mat3 {
+    fn from(m: Matrix) -> Self {
+        let mut values = [0.0; 9];
+        m.get_9(&mut values);
+        glam::Mat3::from_cols_array(&values).transpose()
+    }
+}
+
+impl From<glam::Affine2> for Matrix {
+    fn from(a: glam::Affine2) -> Self {
+        Matrix::from_affine(&a.to_cols_array())
+    }
+}
+
+/// Fails and returns the matrix if it has perspective.
+impl TryFrom<Matrix> for glam::Affine2 {
+    type Error = Matrix;
+
+    fn try_from(m: Matrix) -> Result<Self, Self::Error> {
+        m.to_affine()
+            .map(|affine| glam::Affine2::from_cols_array(&affine))
+            .ok_or(m)
+    }
+}
+
+impl From<glam::Mat4> for M44 {
+    fn from(m: glam::Mat4) -> Self {
+        M44::col_major(&m.to_cols_array())
+    }
+}
+
+impl From<M44> for glam::Mat4 {
+    fn from(m: M44) -> Self {
+        let mut values = [0.0; 16];
+        m.get_col_major(&mut values);
+        glam::Mat4::from_cols_array(&values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{M44, Matrix, Point};
+
+    #[test]
+    fn round_trips() {
+        let v: glam::Vec2 = Point::new(1.0, 2.0).into();
+        assert_eq!(v, glam::Vec2::new(1.0, 2.0));
+        assert_eq!(Point::from(v), Point::new(1.0, 2.0));
+
+        let matrix = Matrix::translate((10.0, 20.0));
+        let mat3: glam::Mat3 = matrix.into();
+        assert_eq!(mat3, glam::Mat3::from_translation(glam::vec2(10.0, 20.0)));
+        assert_eq!(Matrix::from(mat3), matrix);
+
+        let affine = glam::Affine2::from_scale_angle_translation(
+            glam::vec2(2.0, 3.0),
+            0.0,
+            glam::vec2(4.0, 5.0),
+        );
+        let matrix = Matrix::from(affine);
+        assert_eq!(matrix.map_xy(1.0, 1.0), Point::new(6.0, 8.0));
+        assert_eq!(glam::Affine2::try_from(matrix), Ok(affine));
+        let mut perspective = Matrix::default();
+        perspective.set_persp_x(0.5);
+        assert!(glam::Affine2::try_from(perspective).is_err());
+
+        let m44 = M44::translate(1.0, 2.0, 3.0);
+        let mat4: glam::Mat4 = m44.clone().into();
+        assert_eq!(
+            mat4,
+            glam::Mat4::from_translation(glam::vec3(1.0, 2.0, 3.0))
+        );
+        assert_eq!(M44::from(mat4), m44);
+    }
+}
@@ -0,0 +1,186 @@
+//! Conversions to and from the types of the [`kurbo`] crate.
+//!
+//! `kurbo` uses `f64` coordinates, which are converted to `f32` when converting to Skia types.
+
+use kurbo::{Affine, BezPath, PathEl};
+
+use crate::{Matrix, Path, PathBuilder, PathVerb, Point, Rect, Size, scalar};
+
+impl From<kurbo::Point> for Point {
+    fn from(p: kurbo::Point) -> Self {
+        Point::new(p.x as scalar, p.y as scalar)
+    }
+}
+
+impl From<Point> for kurbo::Point {
+    fn from(p: Point) -> Self {
+        kurbo::Point::new(p.x.into(), p.y.into())
+    }
+}
+
+impl From<kurbo::Vec2> for Point {
+    fn from(v: kurbo::Vec2) -> Self {
+        Point::new(v.x as scalar, v.y as scalar)
+    }
+}
+
+impl From<Point> for kurbo::Vec2 {
+    fn from(v: Point) -> Self {
+        kurbo::Vec2::new(v.x.into(), v.y.into())
+    }
+}
+
+impl From<kurbo::Size> for Size {
+    fn from(s: kurbo::Size) -> Self {
+        Size::new(s.width as scalar, s.height as scalar)
+    }
+}
+
+impl From<Size> for kurbo::Size {
+    fn from(s: Size) -> Self {
+        kurbo::Size::new(s.width.into(), s.height.into())
+    }
+}
+
+impl From<kurbo::Rect> for Rect {
+    fn from(r: kurbo::Rect) -> Self {
+        Rect::new(
+            r.x0 as scalar,
+            r.y0 as scalar,
+            r.x1 as scalar,
+            r.y1 as scalar,
+        )
+    }
+}
+
+impl From<Rect> for kurbo::Rect {
+    fn from(r: Rect) -> Self {
+        kurbo::Rect::new(r.left.into(), r.top.into(), r.right.into(), r.bottom.into())
+    }
+}
+
+impl From<Affine> for Matrix {
+    fn from(a: Affine) -> Self {
+        Matrix::from_affine(&a.as_coeffs().map(|c| c as scalar))
+    }
+}
+
+/// Fails and returns the matrix if it has perspective.
+impl TryFrom<Matrix> for Affine {
+    type Error = Matrix;
+
+    fn try_from(m: Matrix) -> Result<Self, Self::Error> {
+        let affine = m.to_affine().ok_or(m)?;
+        Ok(Affine::new(affine.map(f64::from)))
+    }
+}
+
+impl From<&BezPath> for Path {
+    fn from(bez_path: &BezPath) -> Self {
+        let mut builder = PathBuilder::new();
+        for el in bez_path.elements() {
+            match *el {
+                PathEl::MoveTo(p) => builder.move_to(p),
+                PathEl::LineTo(p) => builder.line_to(p),
+                PathEl::QuadTo(p1, p2) => builder.quad_to(p1, p2),
+                PathEl::CurveTo(p1, p2, p3) => builder.cubic_to(p1, p2, p3),
+                PathEl::ClosePath => builder.close(),
+            };
+        }
+        builder.detach()
+    }
+}
+
+impl From<BezPath> for Path {
+    fn from(bez_path: BezPath) -> Self {
+        Path::from(&bez_path)
+    }
+}
+
+/// Conics are approximated by `2^CONIC_QUADS_POW2` quads.
+const CONIC_QUADS_POW2: usize = 3;
+
+/// The fill type of the path is not preserved.
+impl From<&Path> for BezPath {
+    fn from(path: &Path) -> Self {
+        let mut bez_path = BezPath::new();
+        for rec in path.iter() {
+            let points = rec.points();
+            let p = |i: usize| kurbo::Point::from(points[i]);
+            match rec.verb() {
+                PathVerb::Move => bez_path.move_to(p(0)),
+                PathVerb::Line => bez_path.line_to(p(1)),
+                PathVerb::Quad => bez_path.quad_to(p(1), p(2)),
+                PathVerb::Conic => {
+                    let mut quads = [Point::default(); 1 + 2 * (1 << CONIC_QUADS_POW2)];
+                    let count = Path::convert_conic_to_quads(
+                        points[0],
+                        points[1],
+                        points[2],
+                        rec.conic_weight(),
+                        &mut quads,
+                        CONIC_QUADS_POW2,
+                    )
+                    .unwrap_or_default();
+                    for quad in quads[1..].chunks_exact(2).take(count) {
+                        bez_path.quad_to(quad[0], quad[1]);
+                    }
+                }
+                PathVerb::Cubic => bez_path.curve_to(p(1), p(2), p(3)),
+                PathVerb::Close => bez_path.close_path(),
+            }
+        }
+        bez_path
+    }
+}
+
+impl From<Path> for BezPath {
+    fn from(path: Path) -> Self {
+        BezPath::from(&path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kurbo::{Affine, BezPath, Shape};
+
+    use crate::{Matrix, Path, Point, Rect};
+
+    #[test]
+    fn geometry_round_trips() {
+        let p: kurbo::Point = Point::new(1.0, 2.0).into();
+        assert_eq!(p, kurbo::Point::new(1.0, 2.0));
+        assert_eq!(Point::from(p), Point::new(1.0, 2.0));
+
+        let rect = Rect::new(1.0, 2.0, 4.0, 6.0);
+        assert_eq!(Rect::from(kurbo::Rect::from(rect)), rect);
+
+        let affine = Affine::translate((10.0, 20.0)) * Affine::scale(2.0);
+        let matrix = Matrix::from(affine);
+        assert_eq!(matrix.map_xy(1.0, 1.0), Point::new(12.0, 22.0));
+        assert_eq!(Affine::try_from(matrix), Ok(affine));
+    }
+
+    #[test]
+    fn path_round_trips() {
+        let bez_path = kurbo::Rect::new(0.0, 0.0, 10.0, 20.0).to_path(0.1);
+        let path = Path::from(&bez_path);
+        assert_eq!(*path.bounds(), Rect::new(0.0, 0.0, 10.0, 20.0));
+        assert_eq!(BezPath::from(&path), bez_path);
+    }
+
+    #[test]
+    fn conics_are_converted_to_quads() {
+        let path = Path::circle((0.0, 0.0), 10.0, None);
+        let bez_path = BezPath::from(&path);
+        assert!(
+            bez_path
+                .elements()
+                .iter()
+                .all(|el| !matches!(el, kurbo::PathEl::CurveTo(..)))
+        );
+        let bounds = bez_path.bounding_box();
+        assert!((bounds.width() - 20.0).abs() < 0.01);
+        assert!((bez_path.area().abs() - std::f64::consts::PI * 100.0).abs() < 0.1);
+    }
+}
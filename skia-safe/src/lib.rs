@@ -15,19 +15,29 @@ pub use codec::Result as CodecResult;
 pub use codec::{Codec, EncodedImageFormat, EncodedOrigin, codecs};
 
 mod core;
-#[cfg(feature = "pdf")]
 mod docs;
 mod effects;
 mod encode_;
+#[cfg(feature = "euclid")]
+mod euclid_;
+#[cfg(feature = "glam")]
+mod glam_;
 #[cfg(feature = "gpu")]
 pub mod gpu;
 #[cfg(feature = "graphite")]
 pub mod graphite;
 mod interop;
+#[cfg(feature = "kurbo")]
+mod kurbo_;
+#[cfg(feature = "mint")]
+mod mint_;
 mod modules;
 mod pathops;
 mod prelude;
 pub(crate) mod private;
+pub mod render;
+#[cfg(feature = "serde")]
+mod serde_;
 pub mod skottie;
 pub mod svg;
 pub mod wrapper;
@@ -42,7 +52,6 @@ pub use crate::prelude::{Borrows, ConditionallySend, Handle, RCHandle, RefHandle
 
 // All Sk* types are accessible via skia_safe::
 pub use crate::core::*;
-#[cfg(feature = "pdf")]
 pub use docs::*;
 pub use effects::*;
 pub use encode_::*;
@@ -0,0 +1,130 @@
+//! Conversions to and from the types of the [`mint`] crate.
+
+use crate::{M44, Matrix, Point, Point3, scalar};
+
+impl From<mint::Point2<scalar>> for Point {
+    fn from(p: mint::Point2<scalar>) -> Self {
+        Point::new(p.x, p.y)
+    }
+}
+
+impl From<Point> for mint::Point2<scalar> {
+    fn from(p: Point) -> Self {
+        mint::Point2 { x: p.x, y: p.y }
+    }
+}
+
+impl From<mint::Vector2<scalar>> for Point {
+    fn from(v: mint::Vector2<scalar>) -> Self {
+        Point::new(v.x, v.y)
+    }
+}
+
+impl From<Point> for mint::Vector2<scalar> {
+    fn from(v: Point) -> Self {
+        mint::Vector2 { x: v.x, y: v.y }
+    }
+}
+
+impl From<mint::Point3<scalar>> for Point3 {
+    fn from(p: mint::Point3<scalar>) -> Self {
+        Point3::new(p.x, p.y, p.z)
+    }
+}
+
+impl From<Point3> for mint::Point3<scalar> {
+    fn from(p: Point3) -> Self {
+        mint::Point3 {
+            x: p.x,
+            y: p.y,
+            z: p.z,
+        }
+    }
+}
+
+impl From<mint::RowMatrix3<scalar>> for Matrix {
+    fn from(m: mint::RowMatrix3<scalar>) -> Self {
+        let rows: [[scalar; 3]; 3] = m.into();
+        let mut matrix = Matrix::default();
+        matrix.set_9(rows.as_flattened().try_into().unwrap());
+        matrix
+    }
+}
+
+impl From<Matrix> for mint::RowMatrix3<scalar> {
+    fn from(m: Matrix) -> Self {
+        let mut values = [0.0; 9];
+        m.get_9(&mut values);
+        values.into()
+    }
+}
+
+impl From<mint::ColumnMatrix3<scalar>> for Matrix {
+    fn from(m: mint::ColumnMatrix3<scalar>) -> Self {
+        mint::RowMatrix3::from(m).into()
+    }
+}
+
+impl From<Matrix> for mint::ColumnMatrix3<scalar> {
+    fn from(m: Matrix) -> Self {
+        mint::RowMatrix3::from(m).into()
+    }
+}
+
+impl From<mint::RowMatrix4<scalar>> for M44 {
+    fn from(m: mint::RowMatrix4<scalar>) -> Self {
+        let rows: [[scalar; 4]; 4] = m.into();
+        M44::row_major(rows.as_flattened().try_into().unwrap())
+    }
+}
+
+impl From<M44> for mint::RowMatrix4<scalar> {
+    fn from(m: M44) -> Self {
+        let mut values = [0.0; 16];
+        m.get_row_major(&mut values);
+        values.into()
+    }
+}
+
+impl From<mint::ColumnMatrix4<scalar>> for M44 {
+    fn from(m: mint::ColumnMatrix4<scalar>) -> Self {
+        let columns: [[scalar; 4]; 4] = m.into();
+        M44::col_major(columns.as_flattened().try_into().unwrap())
+    }
+}
+
+impl From<M44> for mint::ColumnMatrix4<scalar> {
+    fn from(m: M44) -> Self {
+        let mut values = [0.0; 16];
+        m.get_col_major(&mut values);
+        values.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{M44, Matrix, Point};
+
+    #[test]
+    fn round_trips() {
+        let p: mint::Point2<f32> = Point::new(1.0, 2.0).into();
+        assert_eq!((p.x, p.y), (1.0, 2.0));
+        assert_eq!(Point::from(p), Point::new(1.0, 2.0));
+
+        let matrix = Matrix::translate((10.0, 20.0));
+        let rows: mint::RowMatrix3<f32> = matrix.into();
+        assert_eq!(rows.x.z, 10.0);
+        assert_eq!(Matrix::from(rows), matrix);
+        let columns: mint::ColumnMatrix3<f32> = matrix.into();
+        assert_eq!(columns.z.y, 20.0);
+        assert_eq!(Matrix::from(columns), matrix);
+
+        let m44 = M44::translate(1.0, 2.0, 3.0);
+        let rows: mint::RowMatrix4<f32> = m44.clone().into();
+        assert_eq!(rows.z.w, 3.0);
+        assert_eq!(M44::from(rows), m44);
+        let columns: mint::ColumnMatrix4<f32> = m44.clone().into();
+        assert_eq!(columns.w.x, 1.0);
+        assert_eq!(M44::from(columns), m44);
+    }
+}
@@ -1,4 +1,10 @@
-use std::{borrow::Cow, ffi::CStr, mem, os::raw, ptr};
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString},
+    mem,
+    os::raw,
+    ptr,
+};
 
 use helpers::ResourceKind;
 use skia_bindings::{
@@ -68,6 +74,66 @@ impl NativeRefCountedBase for RustResourceProvider {
     type Base = SkRefCntBase;
 }
 
+impl NativeResourceProvider {
+    /// Loads a resource through the [`ResourceProvider`] this provider was created from.
+    pub fn load(&self, resource_path: &str, resource_name: &str) -> Option<Data> {
+        let resource_path = CString::new(resource_path).ok()?;
+        let resource_name = CString::new(resource_name).ok()?;
+        Data::from_ptr(unsafe {
+            sb::C_RustResourceProvider_load(
+                self.native(),
+                resource_path.as_ptr(),
+                resource_name.as_ptr(),
+            )
+        })
+    }
+
+    /// The font manager of the [`ResourceProvider`] this provider was created from.
+    #[cfg(feature = "svg")]
+    pub(crate) fn font_mgr(&self) -> FontMgr {
+        FontMgr::from_ptr(unsafe { sb::C_RustResourceProvider_fontMgr(self.native()) }).unwrap()
+    }
+}
+
+/// Skia's `CachingResourceProvider`, which forwards to a [`NativeResourceProvider`] and keeps the
+/// image assets it loaded.
+#[cfg(feature = "svg")]
+pub(crate) type CachingResourceProvider = RCHandle<sb::skresources_ResourceProvider>;
+
+#[cfg(feature = "svg")]
+impl NativeRefCountedBase for sb::skresources_ResourceProvider {
+    type Base = SkRefCntBase;
+}
+
+#[cfg(feature = "svg")]
+impl CachingResourceProvider {
+    pub(crate) fn new(provider: &NativeResourceProvider) -> Self {
+        CachingResourceProvider::from_ptr(unsafe {
+            sb::C_CachingResourceProvider_Make(provider.clone().into_ptr())
+        })
+        .unwrap()
+    }
+
+    pub(crate) fn load_image_asset(
+        &self,
+        resource_path: &str,
+        resource_name: &str,
+        resource_id: &str,
+    ) -> Option<ImageAsset> {
+        let resource_path = CString::new(resource_path).ok()?;
+        let resource_name = CString::new(resource_name).ok()?;
+        let resource_id = CString::new(resource_id).ok()?;
+        ImageAsset::from_ptr(unsafe {
+            sb::C_ResourceProvider_loadImageAsset(
+                self.native(),
+                resource_path.as_ptr(),
+                resource_name.as_ptr(),
+                resource_id.as_ptr(),
+            )
+        })
+    }
+}
+
 impl<T: ResourceProvider + 'static> From<T> for NativeResourceProvider {
     fn from(value: T) -> Self {
         let b: Box<dyn ResourceProvider> = Box::new(value);
@@ -199,6 +265,92 @@ impl From<FontMgr> for NativeResourceProvider {
     }
 }
 
+/// Specifies which resources a [`RestrictedResourceProvider`] passes on to the provider it wraps.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ResourcePolicy {
+    /// Only inline `data:` URLs are loaded.
+    DataUrlsOnly,
+    /// `data:` URLs and local files are loaded, but nothing is fetched from a remote location,
+    /// like an `http://` or `https://` URL.
+    NoRemote,
+    /// All resources are loaded.
+    #[default]
+    All,
+}
+
+impl ResourcePolicy {
+    /// Returns `true` if the resource at `resource_path` / `resource_name` may be loaded.
+    pub fn allows(self, resource_path: &str, resource_name: &str) -> bool {
+        let location = helpers::classify_location(resource_path, resource_name);
+        match self {
+            ResourcePolicy::DataUrlsOnly => location == helpers::Location::Data,
+            ResourcePolicy::NoRemote => location != helpers::Location::Remote,
+            ResourcePolicy::All => true,
+        }
+    }
+}
+
+/// A resource provider that wraps another one and refuses to load the resources that are not
+/// allowed by a [`ResourcePolicy`].
+///
+/// Typefaces that can not be loaded are replaced by the default typeface of the font manager.
+#[derive(Debug)]
+pub struct RestrictedResourceProvider<P> {
+    provider: P,
+    policy: ResourcePolicy,
+}
+
+impl<P: ResourceProvider> RestrictedResourceProvider<P> {
+    pub fn new(provider: P, policy: ResourcePolicy) -> Self {
+        Self { provider, policy }
+    }
+
+    pub fn policy(&self) -> ResourcePolicy {
+        self.policy
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    pub fn into_provider(self) -> P {
+        self.provider
+    }
+}
+
+impl<P: ResourceProvider> ResourceProvider for RestrictedResourceProvider<P> {
+    fn load(&self, resource_path: &str, resource_name: &str) -> Option<Data> {
+        if !self.policy.allows(resource_path, resource_name) {
+            return None;
+        }
+        self.provider.load(resource_path, resource_name)
+    }
+
+    fn load_image_asset(
+        &self,
+        resource_path: &str,
+        resource_name: &str,
+        resource_id: &str,
+    ) -> Option<ImageAsset> {
+        if !self.policy.allows(resource_path, resource_name) {
+            return None;
+        }
+        self.provider
+            .load_image_asset(resource_path, resource_name, resource_id)
+    }
+
+    fn load_typeface(&self, name: &str, url: &str) -> Option<Typeface> {
+        if url.is_empty() || self.policy.allows(url, name) {
+            return self.provider.load_typeface(name, url);
+        }
+        helpers::load_typeface(self, &self.font_mgr(), name, url)
+    }
+
+    fn font_mgr(&self) -> FontMgr {
+        self.provider.font_mgr()
+    }
+}
+
 #[cfg(feature = "ureq")]
 #[derive(Debug)]
 /// A resource provider that uses ureq for downloading resources.
@@ -286,6 +438,47 @@ pub mod helpers {
         })
     }
 
+    /// Where a resource is loaded from.
+    #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+    pub enum Location {
+        /// An inline `data:` URL.
+        Data,
+        /// A local file.
+        Local,
+        /// A URL with a scheme other than `file:`, or a network path like `//host/name`.
+        Remote,
+    }
+
+    /// Figure out where the resource at `resource_path` / `resource_name` is loaded from.
+    pub fn classify_location(resource_path: &str, resource_name: &str) -> Location {
+        if resource_path.is_empty() && resource_name.starts_with("data:") {
+            return Location::Data;
+        }
+        let location = if resource_path.is_empty() {
+            resource_name
+        } else {
+            resource_path
+        };
+        if location.starts_with("//") || location.starts_with("\\\\") {
+            return Location::Remote;
+        }
+        match url_scheme(location) {
+            // A single letter is a drive of a Windows path.
+            Some(scheme) if scheme.len() > 1 && !scheme.eq_ignore_ascii_case("file") => {
+                Location::Remote
+            }
+            _ => Location::Local,
+        }
+    }
+
+    fn url_scheme(url: &str) -> Option<&str> {
+        let (scheme, _) = url.split_once(':')?;
+        let mut chars = scheme.chars();
+        let starts_with_letter = chars.next()?.is_ascii_alphabetic();
+        (starts_with_letter && chars.all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)))
+            .then_some(scheme)
+    }
+
     /// Try to parse base64 data from an data: URL. Returns empty [`Data`] if data can not be parsed.
     fn load_base64(data: &str) -> Data {
         let data: Vec<_> = data.split(',').collect();
@@ -360,4 +553,19 @@ pub mod helpers {
         // invalid characters in the input
         assert_eq!(0, decode_base64("$GVsbG8h").len());
     }
+
+    #[test]
+    fn classifying_locations() {
+        assert_eq!(classify_location("", "data:,x"), Location::Data);
+        assert_eq!(
+            classify_location("", "https://x.org/a.png"),
+            Location::Remote
+        );
+        assert_eq!(classify_location("http://x.org", "a.png"), Location::Remote);
+        assert_eq!(classify_location("", "//x.org/a.png"), Location::Remote);
+        assert_eq!(classify_location("", "file:///a.png"), Location::Local);
+        assert_eq!(classify_location("", "C:\\a.png"), Location::Local);
+        assert_eq!(classify_location("assets", "a.png"), Location::Local);
+        assert_eq!(classify_location("", "C:/a.png"), Location::Local);
+    }
 }
@@ -21,6 +21,8 @@ use skia_bindings::{self as sb, SkNVRefCnt};
 
 use super::resources::NativeResourceProvider;
 
+pub mod export;
+
 /// A Lottie animation that can be rendered to a canvas.
 ///
 /// Animations are reference-counted and can be cloned cheaply.
@@ -0,0 +1,508 @@
+//! Render a range of frames of an [`Animation`] into raster frames or image sequences.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use skia_safe::{EncodedImageFormat, skottie::{Animation, export}};
+//!
+//! let json = r#"{"v":"5.5.7","fr":30,"ip":0,"op":60,"w":200,"h":200,"layers":[]}"#;
+//! let animation = Animation::from_str(json).unwrap();
+//! let options = export::Options::new((100, 100)).with_fps(10.0);
+//!
+//! for frame in export::frames(&animation, &options).unwrap() {
+//!     let mut frame = frame.unwrap();
+//!     let pixmap = frame.pixmap();
+//!     // feed `pixmap` into a video encoder ...
+//! }
+//!
+//! export::write_sequence(&animation, &options, EncodedImageFormat::PNG, None, "frames").unwrap();
+//!
+//! // Render with three threads, each one needs its own instance.
+//! let animations = (0..3).map(|_| Animation::from_str(json).unwrap()).collect();
+//! for frame in export::frames_parallel(animations, &options).unwrap() {
+//!     let frame = frame.unwrap();
+//!     // frames arrive in order ...
+//! }
+//! ```
+
+use std::{
+    fmt, fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    AlphaType, Color, ColorType, EncodedImageFormat, ISize, ImageInfo, Pixmap, Rect, Surface,
+    prelude::*, surfaces,
+};
+
+use super::Animation;
+
+/// Parameters that control which frames are rendered and how.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// The pixel size of the rendered frames. The animation is scaled to fit.
+    pub size: ISize,
+    /// Frames per second of the output. `None` uses the animation's frame rate.
+    pub fps: Option<f32>,
+    /// The time range in seconds to render. `None` renders the full animation.
+    pub time_range: Option<Range<f64>>,
+    /// The color every frame is cleared to before the animation is rendered.
+    pub background: Color,
+}
+
+impl Options {
+    pub fn new(size: impl Into<ISize>) -> Self {
+        Self {
+            size: size.into(),
+            fps: None,
+            time_range: None,
+            background: Color::TRANSPARENT,
+        }
+    }
+
+    pub fn with_fps(mut self, fps: f32) -> Self {
+        self.fps = Some(fps);
+        self
+    }
+
+    pub fn with_time_range(mut self, time_range: Range<f64>) -> Self {
+        self.time_range = Some(time_range);
+        self
+    }
+
+    pub fn with_background(mut self, background: impl Into<Color>) -> Self {
+        self.background = background.into();
+        self
+    }
+
+    /// The frame rate and time range resolved against `animation`.
+    fn resolve(&self, animation: &Animation) -> (f64, Range<f64>) {
+        let fps = self.fps.unwrap_or_else(|| animation.fps()) as f64;
+        let range = self
+            .time_range
+            .clone()
+            .unwrap_or(0.0..animation.duration() as f64);
+        (fps, range)
+    }
+
+    /// The number of frames that are rendered for `animation`.
+    pub fn frame_count(&self, animation: &Animation) -> usize {
+        let (fps, range) = self.resolve(animation);
+        if fps <= 0.0 || range.end <= range.start {
+            return 0;
+        }
+        // Frames are sampled while their time is before the end of the range. The epsilon keeps
+        // rounding errors, like `(0.4 - 0.1) * 10.0 = 3.0000000000000004`, from adding a frame.
+        ((range.end - range.start) * fps - 1e-9).ceil() as usize
+    }
+
+    /// The time in seconds at which the frame with the index `index` is sampled.
+    pub fn frame_time(&self, animation: &Animation, index: usize) -> f64 {
+        let (fps, range) = self.resolve(animation);
+        range.start + index as f64 / fps
+    }
+
+    fn image_info(&self) -> ImageInfo {
+        ImageInfo::new(self.size, ColorType::RGBA8888, AlphaType::Unpremul, None)
+    }
+}
+
+/// A rendered frame with unpremultiplied RGBA pixels.
+#[derive(Clone)]
+pub struct Frame {
+    index: usize,
+    time: f64,
+    info: ImageInfo,
+    pixels: Vec<u8>,
+}
+
+impl fmt::Debug for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Frame")
+            .field("index", &self.index)
+            .field("time", &self.time)
+            .field("info", &self.info)
+            .finish()
+    }
+}
+
+impl Frame {
+    /// The index of the frame in the rendered sequence.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The animation time in seconds the frame was rendered at.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn image_info(&self) -> &ImageInfo {
+        &self.info
+    }
+
+    pub fn row_bytes(&self) -> usize {
+        self.info.min_row_bytes()
+    }
+
+    /// The raw pixels, tightly packed rows of RGBA8888 values.
+    pub fn bytes(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    pub fn pixmap(&mut self) -> Pixmap<'_> {
+        let row_bytes = self.row_bytes();
+        Pixmap::new(&self.info, &mut self.pixels, row_bytes).unwrap()
+    }
+
+    pub fn encode(
+        &mut self,
+        format: EncodedImageFormat,
+        quality: impl Into<Option<u32>>,
+    ) -> Option<Vec<u8>> {
+        self.pixmap().encode(format, quality)
+    }
+}
+
+/// Renders single frames of an animation into a reusable raster surface.
+struct Renderer {
+    surface: Surface,
+    info: ImageInfo,
+    dst: Rect,
+    background: Color,
+}
+
+impl Renderer {
+    fn new(options: &Options) -> Option<Self> {
+        let surface = surfaces::raster_n32_premul(options.size)?;
+        Some(Self {
+            surface,
+            info: options.image_info(),
+            dst: Rect::from_isize(options.size),
+            background: options.background,
+        })
+    }
+
+    fn render(&mut self, animation: &Animation, index: usize, time: f64) -> io::Result<Frame> {
+        animation.seek_frame_time(time);
+        let canvas = self.surface.canvas();
+        canvas.clear(self.background);
+        animation.render(canvas, self.dst);
+
+        let mut pixels = vec![0u8; self.info.compute_min_byte_size()];
+        let row_bytes = self.info.min_row_bytes();
+        if !self
+            .surface
+            .read_pixels(&self.info, &mut pixels, row_bytes, (0, 0))
+        {
+            return Err(io::Error::other(format!(
+                "Failed to read the pixels of frame {index}"
+            )));
+        }
+
+        Ok(Frame {
+            index,
+            time,
+            info: self.info.clone(),
+            pixels,
+        })
+    }
+}
+
+/// An iterator over the rendered frames of an animation.
+pub struct Frames<'a> {
+    animation: &'a Animation,
+    options: Options,
+    renderer: Renderer,
+    indices: Range<usize>,
+}
+
+impl fmt::Debug for Frames<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Frames")
+            .field("animation", self.animation)
+            .field("options", &self.options)
+            .field("indices", &self.indices)
+            .finish()
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = io::Result<Frame>;
+
+    fn next(&mut self) -> Option<io::Result<Frame>> {
+        let index = self.indices.next()?;
+        let time = self.options.frame_time(self.animation, index);
+        Some(self.renderer.render(self.animation, index, time))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Frames<'_> {}
+
+/// Returns an iterator that renders the frames described by `options` one after another. Every
+/// item is an error if the pixels of its frame could not be read back from the raster surface.
+///
+/// Returns `None` if a raster surface of the requested size can not be created.
+pub fn frames<'a>(animation: &'a Animation, options: &Options) -> Option<Frames<'a>> {
+    Some(Frames {
+        animation,
+        options: options.clone(),
+        renderer: Renderer::new(options)?,
+        indices: 0..options.frame_count(animation),
+    })
+}
+
+/// An iterator over the frames of an animation that are rendered in parallel, see
+/// [`frames_parallel()`].
+///
+/// The frames are returned in order. Every thread renders at most one frame ahead. Dropping the
+/// iterator stops the threads.
+pub struct ParallelFrames {
+    receivers: Vec<Receiver<io::Result<Frame>>>,
+    threads: Vec<JoinHandle<()>>,
+    indices: Range<usize>,
+}
+
+impl fmt::Debug for ParallelFrames {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParallelFrames")
+            .field("threads", &self.threads.len())
+            .field("indices", &self.indices)
+            .finish()
+    }
+}
+
+impl Iterator for ParallelFrames {
+    type Item = io::Result<Frame>;
+
+    fn next(&mut self) -> Option<io::Result<Frame>> {
+        let index = self.indices.next()?;
+        // The thread that renders the frame sends it, unless it panicked.
+        Some(
+            self.receivers[index % self.receivers.len()]
+                .recv()
+                .unwrap_or_else(|_| {
+                    Err(io::Error::other(format!("Failed to render frame {index}")))
+                }),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ParallelFrames {}
+
+impl Drop for ParallelFrames {
+    fn drop(&mut self) {
+        // Threads that are waiting to send a frame stop as soon as their receiver is gone.
+        self.receivers.clear();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Returns an iterator that renders the frames described by `options` in parallel, using one
+/// thread per animation instance. Like [`frames()`], the frames are returned in order.
+///
+/// Because seeking mutates the state of an [`Animation`], every thread needs its own instance.
+/// All `animations` are expected to be loaded from the same source. The frame rate and range
+/// are resolved against the first animation.
+///
+/// Returns an error if `animations` is empty, if one of them is shared, for example because it
+/// is a clone of another one, or if a raster surface of the requested size can not be created.
+pub fn frames_parallel(
+    animations: Vec<Animation>,
+    options: &Options,
+) -> io::Result<ParallelFrames> {
+    let first = animations
+        .first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No animations to render"))?;
+    if !animations
+        .iter()
+        .all(|animation| animation.native().unique())
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Every animation must be a separately loaded instance that is not shared",
+        ));
+    }
+    Renderer::new(options).ok_or_else(|| surface_error(options))?;
+
+    let frame_count = options.frame_count(first);
+    let workers = animations.len().min(frame_count.max(1));
+    let (receivers, threads) = animations
+        .into_iter()
+        .take(workers)
+        .enumerate()
+        .map(|(worker, animation)| {
+            let (sender, receiver) = mpsc::sync_channel(1);
+            let options = options.clone();
+            let thread = thread::spawn(move || {
+                let mut renderer = Renderer::new(&options);
+                for index in (worker..frame_count).step_by(workers) {
+                    let time = options.frame_time(&animation, index);
+                    let frame = match &mut renderer {
+                        Some(renderer) => renderer.render(&animation, index, time),
+                        None => Err(surface_error(&options)),
+                    };
+                    if sender.send(frame).is_err() {
+                        break;
+                    }
+                }
+            });
+            (receiver, thread)
+        })
+        .unzip();
+
+    Ok(ParallelFrames {
+        receivers,
+        threads,
+        indices: 0..frame_count,
+    })
+}
+
+/// Renders the frames described by `options` and writes them as numbered image files
+/// (`00000.png`, `00001.png`, ...) into `dir`, which is created if it does not exist.
+///
+/// Returns the number of frames written.
+pub fn write_sequence(
+    animation: &Animation,
+    options: &Options,
+    format: EncodedImageFormat,
+    quality: impl Into<Option<u32>>,
+    dir: impl AsRef<Path>,
+) -> io::Result<usize> {
+    let dir = dir.as_ref();
+    let extension = match format {
+        EncodedImageFormat::JPEG => "jpg",
+        EncodedImageFormat::PNG => "png",
+        EncodedImageFormat::WEBP => "webp",
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("Unsupported image sequence format: {format:?}"),
+            ));
+        }
+    };
+    write_sequence_with(animation, options, format, quality, |index| {
+        dir.join(format!("{index:05}.{extension}"))
+    })
+}
+
+/// Renders the frames described by `options`, encodes them, and writes each one to the path
+/// returned by `path_for_frame`. Missing parent directories are created.
+///
+/// Returns the number of frames written.
+pub fn write_sequence_with(
+    animation: &Animation,
+    options: &Options,
+    format: EncodedImageFormat,
+    quality: impl Into<Option<u32>>,
+    mut path_for_frame: impl FnMut(usize) -> PathBuf,
+) -> io::Result<usize> {
+    let quality = quality.into();
+    let frames = frames(animation, options).ok_or_else(|| surface_error(options))?;
+
+    let mut written = 0;
+    for frame in frames {
+        let mut frame = frame?;
+        let encoded = frame.encode(format, quality).ok_or_else(|| {
+            io::Error::other(format!(
+                "Failed to encode frame {} as {format:?}",
+                frame.index()
+            ))
+        })?;
+        let path = path_for_frame(frame.index());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, encoded)?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+fn surface_error(options: &Options) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!(
+            "Failed to create a raster surface of size {:?}",
+            options.size
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{"v":"5.5.7","fr":30,"ip":0,"op":60,"w":200,"h":200,"layers":[]}"#;
+
+    #[test]
+    fn frame_count_and_times() {
+        let anim = Animation::from_str(JSON).unwrap();
+        let options = Options::new((20, 20));
+        assert_eq!(options.frame_count(&anim), 60);
+
+        let options = options.with_fps(10.0).with_time_range(0.5..1.0);
+        assert_eq!(options.frame_count(&anim), 5);
+        assert!((options.frame_time(&anim, 2) - 0.7).abs() < 1e-9);
+
+        let options = options.with_time_range(0.1..0.4);
+        assert_eq!(options.frame_count(&anim), 3);
+    }
+
+    #[test]
+    fn iterate_frames() {
+        let anim = Animation::from_str(JSON).unwrap();
+        let options = Options::new((20, 10))
+            .with_fps(5.0)
+            .with_background(Color::RED);
+        let frames: Vec<_> = frames(&anim, &options)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(frames.len(), 10);
+        let mut frame = frames[3].clone();
+        assert_eq!(frame.index(), 3);
+        assert_eq!(frame.bytes().len(), 20 * 10 * 4);
+        assert_eq!(frame.pixmap().get_color((5, 5)), Color::RED);
+    }
+
+    #[test]
+    fn parallel_rendering_keeps_order() {
+        let animations = (0..3).map(|_| Animation::from_str(JSON).unwrap()).collect();
+        let options = Options::new((8, 8)).with_fps(4.0);
+        let frames: Vec<_> = frames_parallel(animations, &options)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(frames.len(), 8);
+        assert!(frames.iter().enumerate().all(|(i, f)| f.index() == i));
+    }
+
+    #[test]
+    fn parallel_rendering_rejects_shared_animations() {
+        let err = frames_parallel(Vec::new(), &Options::new((8, 8))).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let animation = Animation::from_str(JSON).unwrap();
+        let err =
+            frames_parallel(vec![animation.clone(), animation], &Options::new((8, 8))).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -1,6 +1,9 @@
+mod animation;
 mod clip_path;
 mod container;
+mod css;
 mod defs;
+mod diagnostics;
 mod dom;
 pub mod fe;
 mod filter;
@@ -18,11 +21,15 @@ mod text;
 mod transformable_node;
 mod types;
 mod r#use;
+mod writer;
+mod xml;
 
 pub use self::{
     clip_path::ClipPath,
     container::Container,
+    css::Stylesheet,
     defs::Defs,
+    diagnostics::{Warning, WarningKind},
     dom::*,
     filter::Filter,
     g::G,
@@ -38,6 +45,7 @@ pub use self::{
     transformable_node::TransformableNode,
     types::*,
     r#use::Use,
+    xml::{TextPosition, XmlError},
 };
 
 #[macro_export]
@@ -101,6 +109,466 @@ mod tests {
         assert_eq!(1, font_mgr.native()._ref_cnt());
     }
 
+    #[test]
+    fn parse_error_reports_kind_and_position() {
+        use crate::svg::{LoadError, ParseErrorKind};
+
+        let svg = "<svg xmlns=\"http://www.w3.org/2000/svg\">\n  <rect width=\"10\">\n</svg>";
+        assert_eq!(Dom::from_str(svg, FontMgr::new()).unwrap_err(), LoadError);
+
+        let error = Dom::from_bytes_with_warnings(svg.as_bytes(), FontMgr::new()).unwrap_err();
+        let ParseErrorKind::Xml(xml_error) = error.kind() else {
+            panic!("not an XML error: {error}");
+        };
+        assert_eq!(xml_error.message(), "mismatched tag");
+        let position = error.position().unwrap();
+        assert_eq!((position.line, position.column), (3, 3));
+
+        let error = Dom::read_with_warnings(&b"<html/>"[..], FontMgr::new()).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::RootNotSvg { name } if name == "html"));
+
+        let svg = br#"<!DOCTYPE svg [<!ENTITY a "b">]><svg>&a;</svg>"#;
+        let error = Dom::from_bytes_with_warnings(svg, FontMgr::new()).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::EntityDeclaration));
+    }
+
+    #[test]
+    fn read_reports_io_errors() {
+        use crate::svg::ParseErrorKind;
+
+        struct Failing;
+        impl std::io::Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "peer went away",
+                ))
+            }
+        }
+        let error = Dom::read_with_warnings(Failing, FontMgr::new()).unwrap_err();
+        let ParseErrorKind::Io(io_error) = error.kind() else {
+            panic!("not an I/O error: {error}");
+        };
+        assert_eq!(io_error.kind(), std::io::ErrorKind::ConnectionReset);
+        assert!(error.to_string().contains("peer went away"));
+        assert_eq!(std::io::Error::from(error).to_string(), "peer went away");
+    }
+
+    #[test]
+    fn load_with_warnings() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">
+            <blink/>
+            <rect width="10" height="10" fill="red" wiggle="yes"/>
+            </svg>"#;
+        let (_dom, warnings) =
+            Dom::from_bytes_with_warnings(svg.as_bytes(), FontMgr::new()).unwrap();
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn build_and_edit_dom() {
+        use crate::svg::{G, Rect};
+
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+            <g id="content"><circle id="dot" cx="50" cy="50" r="10"/></g>
+            </svg>"##;
+        let dom = Dom::from_str(svg, FontMgr::new()).unwrap();
+
+        let mut dot = dom.find_node_by_id("dot").unwrap();
+        assert!(dot.parse_and_set_attribute("fill", "#0f0"));
+        assert!(!dot.parse_and_set_attribute("fill", "not-a-color"));
+
+        let mut rect = Rect::default();
+        rect.set_width(Length::new(20.0, LengthUnit::Number));
+        rect.set_height(Length::new(20.0, LengthUnit::Number));
+        assert!(rect.parse_and_set_attribute("fill", "blue"));
+        let mut group = G::default();
+        group.append_child(rect.into_node());
+        dom.root().append_child(group.into_node());
+        assert!(dom.find_node_by_id("content").is_some());
+
+        let mut surface = surfaces::raster_n32_premul((100, 100)).unwrap();
+        dom.render(surface.canvas());
+        save_to_tmp(&mut surface, "edited");
+    }
+
+    #[test]
+    fn ids_follow_the_tree() {
+        use crate::svg::{Rect, TypedNode};
+
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="30" height="10">
+            <defs><rect id="shape" width="10" height="10" fill="red"/></defs>
+            <use xlink:href="#shape"/>
+            <rect id="right" x="20" width="10" height="10" fill="blue"/>
+            </svg>"##;
+        let mut dom = Dom::from_str(svg, FontMgr::new()).unwrap();
+        let color_at = |x, render: &dyn Fn(&crate::Canvas)| {
+            let mut surface = surfaces::raster_n32_premul((30, 10)).unwrap();
+            render(surface.canvas());
+            surface.peek_pixels().unwrap().get_color((x, 5))
+        };
+        assert_eq!(color_at(5, &|canvas| dom.render(canvas)), crate::Color::RED);
+
+        // Create a node.
+        let mut middle = Rect::default();
+        middle.set_x(Length::new(10.0, LengthUnit::Number));
+        middle.set_width(Length::new(10.0, LengthUnit::Number));
+        middle.set_height(Length::new(10.0, LengthUnit::Number));
+        assert!(middle.parse_and_set_attribute("fill", "lime"));
+        let middle = middle.into_node();
+        assert!(dom.set_node_id(&middle, Some("middle")));
+        assert!(dom.find_node_by_id("middle").is_none());
+        dom.root().append_child(middle.clone());
+        assert!(dom.find_node_by_id("middle").unwrap().ptr_eq(&middle));
+        assert_eq!(
+            color_at(15, &|canvas| dom.render(canvas)),
+            crate::Color::GREEN
+        );
+        // Skia can only resolve the ids of the parsed document.
+        assert_eq!(
+            color_at(15, &|canvas| assert!(!dom.render_node(canvas, "middle"))),
+            crate::Color::TRANSPARENT
+        );
+
+        // Remove a node.
+        let shape = dom.find_node_by_id("shape").unwrap();
+        let TypedNode::Defs(mut defs) = dom.root().children()[0].clone().typed() else {
+            panic!("expected <defs>");
+        };
+        assert!(defs.remove_child_node(&shape));
+        assert!(dom.find_node_by_id("shape").is_none());
+        assert_eq!(
+            dom.node_ids()
+                .iter()
+                .map(|(id, _)| id.as_str())
+                .collect::<Vec<_>>(),
+            ["middle", "right"]
+        );
+        assert_eq!(
+            color_at(5, &|canvas| dom.render(canvas)),
+            crate::Color::TRANSPARENT
+        );
+        assert_eq!(
+            color_at(5, &|canvas| assert!(!dom.render_node(canvas, "shape"))),
+            crate::Color::TRANSPARENT
+        );
+
+        // Change an id.
+        let right = dom.find_node_by_id("right").unwrap();
+        assert!(dom.set_node_id(&right, Some("shape")));
+        assert!(dom.find_node_by_id("right").is_none());
+        assert!(dom.find_node_by_id("shape").unwrap().ptr_eq(&right));
+        assert_eq!(dom.hit_test((25.0, 5.0)).as_deref(), Some("shape"));
+        assert_eq!(
+            color_at(25, &|canvas| dom.render(canvas)),
+            crate::Color::BLUE
+        );
+        assert_eq!(
+            color_at(25, &|canvas| assert!(dom.render_node(canvas, "shape"))),
+            crate::Color::BLUE
+        );
+        assert_eq!(
+            color_at(15, &|canvas| assert!(dom.render_node(canvas, "shape"))),
+            crate::Color::TRANSPARENT
+        );
+        assert!(!dom.render_node(
+            surfaces::raster_n32_premul((30, 10)).unwrap().canvas(),
+            "right"
+        ));
+    }
+
+    #[test]
+    fn node_geometry_and_hit_testing() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+            <g id="group" transform="translate(10 10)">
+                <rect id="back" width="50" height="50" fill="red"/>
+                <circle cx="25" cy="25" r="10" stroke="black" stroke-width="4"/>
+            </g>
+            <line id="line" x1="80" y1="0" x2="80" y2="100" stroke="blue" stroke-width="10"/>
+            </svg>"##;
+        let dom = Dom::from_str(svg, FontMgr::new()).unwrap();
+
+        let back = dom.find_node_by_id("back").unwrap();
+        assert_eq!(
+            dom.node_bounds(&back),
+            Some(crate::Rect::new(10.0, 10.0, 60.0, 60.0))
+        );
+        let path = dom.node_path(&back).unwrap();
+        assert!(path.contains((20.0, 20.0)));
+        assert!(!path.contains((5.0, 5.0)));
+
+        let group = dom.find_node_by_id("group").unwrap();
+        assert_eq!(
+            dom.node_bounds(&group),
+            Some(crate::Rect::new(10.0, 10.0, 60.0, 60.0))
+        );
+        let line = dom.find_node_by_id("line").unwrap();
+        assert_eq!(
+            dom.node_bounds(&line),
+            Some(crate::Rect::new(75.0, 0.0, 85.0, 100.0))
+        );
+
+        // The circle has no id, the id of its parent is returned.
+        assert_eq!(dom.hit_test((35.0, 35.0)).as_deref(), Some("group"));
+        assert_eq!(dom.hit_test((15.0, 15.0)).as_deref(), Some("back"));
+        assert_eq!(dom.hit_test((78.0, 50.0)).as_deref(), Some("line"));
+        assert!(dom.hit_test((95.0, 95.0)).is_none());
+        assert_eq!(
+            dom.node_at((35.0, 35.0)).unwrap().tag(),
+            crate::svg::NodeTag::Circle
+        );
+
+        let mut surface = surfaces::raster_n32_premul((100, 100)).unwrap();
+        assert!(dom.render_node(surface.canvas(), "back"));
+        assert!(!dom.render_node(surface.canvas(), "missing"));
+        save_to_tmp(&mut surface, "node");
+    }
+
+    #[test]
+    fn filter_to_image_filter() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+            <filter id="shift" filterUnits="userSpaceOnUse" x="0" y="0" width="100" height="100">
+                <feOffset in="SourceGraphic" dx="20" dy="0"/>
+            </filter>
+            </svg>"##;
+        let dom = Dom::from_str(svg, FontMgr::new()).unwrap();
+        let crate::svg::TypedNode::Filter(filter) = dom.find_node_by_id("shift").unwrap().typed()
+        else {
+            panic!("not a filter");
+        };
+        let bounds = crate::Rect::new(10.0, 10.0, 30.0, 30.0);
+        let image_filter = dom.filter_image_filter(&filter, bounds).unwrap();
+
+        let mut surface = surfaces::raster_n32_premul((100, 100)).unwrap();
+        let mut paint = crate::Paint::default();
+        paint.set_color(crate::Color::RED);
+        paint.set_image_filter(image_filter);
+        surface.canvas().draw_rect(bounds, &paint);
+
+        let pixmap = surface.peek_pixels().unwrap();
+        assert_eq!(pixmap.get_color((15, 20)), crate::Color::TRANSPARENT);
+        assert_eq!(pixmap.get_color((45, 20)), crate::Color::RED);
+    }
+
+    #[test]
+    fn stylesheets_are_applied() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+            <style>.icon rect { fill: red } #right { fill: lime }</style>
+            <g class="icon"><rect width="10" height="10" fill="black"/><rect id="right" x="10" width="10" height="10"/></g>
+            </svg>"##;
+        let color_at = |dom: &Dom, x| {
+            let mut surface = surfaces::raster_n32_premul((20, 10)).unwrap();
+            dom.render(surface.canvas());
+            surface.peek_pixels().unwrap().get_color((x, 5))
+        };
+
+        let dom = Dom::from_str(svg, FontMgr::new()).unwrap();
+        assert_eq!(color_at(&dom, 5), crate::Color::RED);
+        assert_eq!(color_at(&dom, 15), crate::Color::GREEN);
+
+        let theme = super::Stylesheet::parse("rect { fill: blue !important }");
+        let dom = Dom::from_bytes_with_stylesheet(svg.as_bytes(), &theme, FontMgr::new()).unwrap();
+        assert_eq!(color_at(&dom, 5), crate::Color::BLUE);
+        assert_eq!(color_at(&dom, 15), crate::Color::BLUE);
+    }
+
+    #[test]
+    fn animations() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+            <rect width="10" height="10" fill="red">
+                <set attributeName="fill" to="lime" begin="1s" dur="1s"/>
+            </rect>
+            <rect id="moving" width="10" height="10">
+                <animateTransform attributeName="transform" type="translate" from="0 0" to="10 0"
+                    dur="2s" fill="freeze"/>
+            </rect>
+            </svg>"##;
+        let mut dom = Dom::from_str(svg, FontMgr::new()).unwrap();
+        assert_eq!(dom.duration(), 2.0);
+        assert!(dom.node_ids().iter().all(|(id, _)| id == "moving"));
+
+        let moving = dom.find_node_by_id("moving").unwrap();
+        let color_at = |dom: &Dom, x| {
+            let mut surface = surfaces::raster_n32_premul((20, 10)).unwrap();
+            dom.render(surface.canvas());
+            surface.peek_pixels().unwrap().get_color((x, 5))
+        };
+
+        assert_eq!(color_at(&dom, 2), crate::Color::BLACK);
+        dom.seek_time(1.5);
+        assert_eq!(
+            dom.node_bounds(&moving),
+            Some(crate::Rect::new(7.5, 0.0, 17.5, 10.0))
+        );
+        assert_eq!(color_at(&dom, 2), crate::Color::GREEN);
+        dom.seek(1.0);
+        assert_eq!(color_at(&dom, 2), crate::Color::RED);
+        assert_eq!(color_at(&dom, 15), crate::Color::BLACK);
+        dom.seek(0.0);
+        assert_eq!(color_at(&dom, 15), crate::Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn nested_svg_images() {
+        use crate::{
+            Data, Typeface,
+            resources::{
+                LocalResourceProvider, ResourcePolicy, ResourceProvider, RestrictedResourceProvider,
+            },
+            svg::TypedNode,
+        };
+
+        struct Files(LocalResourceProvider);
+
+        impl ResourceProvider for Files {
+            fn load(&self, resource_path: &str, resource_name: &str) -> Option<Data> {
+                if resource_name == "left.svg" {
+                    return Some(Data::new_copy(
+                        br#"<svg xmlns="http://www.w3.org/2000/svg" width="1" height="1"><rect width="1" height="1" fill="lime"/></svg>"#,
+                    ));
+                }
+                self.0.load(resource_path, resource_name)
+            }
+
+            fn load_typeface(&self, name: &str, url: &str) -> Option<Typeface> {
+                self.0.load_typeface(name, url)
+            }
+
+            fn font_mgr(&self) -> FontMgr {
+                self.0.font_mgr()
+            }
+        }
+
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="20" height="10">
+            <image id="left" width="10" height="10" xlink:href="left.svg"/>
+            <image x="10" width="10" height="10" xlink:href="data:image/svg+xml;base64,PHN2ZyB4bWxucz0naHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmcnIHZpZXdCb3g9JzAgMCAyIDInPjxyZWN0IHdpZHRoPScyJyBoZWlnaHQ9JzInIGZpbGw9JyMwMGYnLz48L3N2Zz4="/>
+            </svg>"##;
+        let color_at = |dom: &Dom, x| {
+            let mut surface = surfaces::raster_n32_premul((20, 10)).unwrap();
+            dom.render(surface.canvas());
+            surface.peek_pixels().unwrap().get_color((x, 5))
+        };
+
+        let files = || Files(LocalResourceProvider::new(FontMgr::new()));
+        let dom = Dom::from_str(svg, files()).unwrap();
+        let TypedNode::Image(left) = dom.find_node_by_id("left").unwrap().typed() else {
+            panic!("expected an image");
+        };
+        assert_eq!(left.href().data(), "left.svg");
+        assert_eq!(color_at(&dom, 5), crate::Color::GREEN);
+        assert_eq!(color_at(&dom, 15), crate::Color::BLUE);
+        assert_eq!(dom.hit_test((5.0, 5.0)).as_deref(), Some("left"));
+
+        let mut written = Vec::new();
+        dom.write(&mut written).unwrap();
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.contains(r#"xlink:href="left.svg""#));
+        assert_eq!(written.matches("<image").count(), 2);
+
+        let restricted = RestrictedResourceProvider::new(files(), ResourcePolicy::DataUrlsOnly);
+        let dom = Dom::from_str(svg, restricted).unwrap();
+        assert_eq!(color_at(&dom, 5), crate::Color::TRANSPARENT);
+        assert_eq!(color_at(&dom, 15), crate::Color::BLUE);
+    }
+
+    #[test]
+    fn write_and_read_back() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="100" height="100">
+            <defs>
+                <linearGradient id="grad" x2="1">
+                    <stop offset="0" stop-color="red"/>
+                    <stop offset="1" stop-color="blue"/>
+                </linearGradient>
+                <rect id="shape" width="10" height="10" fill="url(#grad)"/>
+            </defs>
+            <use xlink:href="#shape" x="20" transform="rotate(90)"/>
+            <text x="5" y="50">Fish &amp; Chips</text>
+            </svg>"##;
+        let dom = Dom::from_str(svg, FontMgr::new()).unwrap();
+        assert_eq!(
+            dom.node_ids()
+                .iter()
+                .map(|(id, _)| id.as_str())
+                .collect::<Vec<_>>(),
+            ["grad", "shape"]
+        );
+
+        let mut written = Vec::new();
+        dom.write(&mut written).unwrap();
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.contains(r#"<linearGradient id="grad""#));
+        assert!(written.contains(r#"fill="url(#grad)""#));
+        assert!(written.contains(r##"xlink:href="#shape""##));
+        assert!(written.contains("Fish &amp; Chips</text>"));
+
+        let read = Dom::from_str(&written, FontMgr::new()).unwrap();
+        assert!(read.find_node_by_id("grad").is_some());
+        assert!(read.find_node_by_id("shape").is_some());
+        let mut rewritten = Vec::new();
+        read.write(&mut rewritten).unwrap();
+        assert_eq!(written, String::from_utf8(rewritten).unwrap());
+    }
+
+    #[test]
+    fn write_round_trips_text_images_and_references() {
+        use crate::svg::{NodeTag, TextLiteral, TypedNode};
+
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="100" height="100">
+            <defs>
+                <radialGradient id="glow" cx="0.5" cy="0.5" r="0.5" gradientUnits="objectBoundingBox">
+                    <stop offset="0" stop-color="white"/>
+                    <stop offset="1" stop-color="black"/>
+                </radialGradient>
+                <path id="curve" d="M10 80 Q50 10 90 80"/>
+            </defs>
+            <image id="photo" x="10" y="10" width="20" height="20" xlink:href="photo.png"/>
+            <use xlink:href="#photo" x="40"/>
+            <text id="label" x="5" y="50" fill="url(#glow)">Total: <tspan font-weight="bold">42</tspan> items</text>
+            <text><textPath xlink:href="#curve">Along the curve</textPath></text>
+            </svg>"##;
+
+        let write = |dom: &Dom| {
+            let mut written = Vec::new();
+            dom.write(&mut written).unwrap();
+            String::from_utf8(written).unwrap()
+        };
+        let tags = |node: &crate::svg::Node| -> Vec<NodeTag> {
+            node.child_nodes().iter().map(|child| child.tag()).collect()
+        };
+
+        let written = write(&Dom::from_str(svg, FontMgr::new()).unwrap());
+        assert!(written.contains(r#"<image id="photo" x="10" y="10" width="20" height="20""#));
+        assert!(written.contains(r#"xlink:href="photo.png""#));
+        assert!(written.contains(r##"xlink:href="#photo""##));
+        assert!(written.contains(r#"<radialGradient id="glow""#));
+        assert!(written.contains(r#"fill="url(#glow)""#));
+        assert!(written.contains(r#"Total: <tspan font-weight="bold">42</tspan> items</text>"#));
+        assert!(written.contains(r##"<textPath xlink:href="#curve""##));
+
+        let read = Dom::from_str(&written, FontMgr::new()).unwrap();
+        let TypedNode::Image(image) = read.find_node_by_id("photo").unwrap().typed() else {
+            panic!("not an image");
+        };
+        assert_eq!(image.href().data(), "photo.png");
+        let label = read.find_node_by_id("label").unwrap();
+        assert_eq!(
+            tags(&label),
+            [NodeTag::TextLiteral, NodeTag::TSpan, NodeTag::TextLiteral]
+        );
+        let glow = read.find_node_by_id("glow").unwrap();
+        assert_eq!(tags(&glow), [NodeTag::Stop, NodeTag::Stop]);
+        assert_eq!(write(&read), written);
+
+        let TypedNode::Text(mut text) = label.typed() else {
+            panic!("not a text");
+        };
+        let mut literal = TextLiteral::default();
+        literal.set_text("!");
+        text.append_child(literal);
+        assert_eq!(text.children().len(), 4);
+        assert!(write(&read).contains("</tspan> items!</text>"));
+    }
+
     // Run this manually (needs network connectivity)
     #[cfg(feature = "ureq")]
     #[test]
@@ -0,0 +1,891 @@
+//! SMIL animations of SVG documents.
+//!
+//! Skia's SVG parser skips `<animate>`, `<set>` and `<animateTransform>` elements. They are
+//! collected from the elements recorded while the document is loaded, and evaluated by setting
+//! the animated attributes of their target nodes when the document is seeked.
+//!
+//! Supported are offset values for `begin` and `end`, `dur`, `repeatCount`, `repeatDur`,
+//! `fill`, `values`, `from`, `to`, `by`, `keyTimes`, `keySplines`, the `discrete`, `linear` and
+//! `spline` calc modes, and `additive="sum"`. The `paced` calc mode is interpolated linearly.
+//! Animations that begin with an event or a sync base never begin.
+
+use std::{collections::HashMap, ffi::CString, fmt};
+
+use super::{
+    Node,
+    css::Declarations,
+    xml::{Document, Element},
+};
+use crate::Color;
+use skia_bindings as sb;
+
+/// Returns `true` for the animation elements that are evaluated by [`Animations`].
+pub(crate) fn is_animation_element(name: &str) -> bool {
+    matches!(name, "animate" | "set" | "animateTransform")
+}
+
+/// The animations of a document, owned by its [`Dom`](super::Dom).
+#[derive(Debug, Clone)]
+pub(crate) struct Animations {
+    targets: Vec<Target>,
+    duration: f64,
+}
+
+/// An attribute of a node and the animations that change it, in document order.
+#[derive(Debug, Clone)]
+struct Target {
+    node: Node,
+    attribute: String,
+    base: Value,
+    animations: Vec<Animation>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Animation {
+    timing: Timing,
+    values: Vec<Value>,
+    calc_mode: CalcMode,
+    key_times: Option<Vec<f64>>,
+    key_splines: Option<Vec<[f64; 4]>>,
+    transform: Option<TransformType>,
+    additive: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Timing {
+    begin: f64,
+    /// The simple duration, `None` if indefinite.
+    simple: Option<f64>,
+    /// The active duration, which may be infinite.
+    active: f64,
+    freeze: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CalcMode {
+    Discrete,
+    Linear,
+    Spline,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum TransformType {
+    Translate,
+    Scale,
+    Rotate,
+    SkewX,
+    SkewY,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Color(Color),
+    /// Numbers and the text around them, for example `"10px"` or `"M 0 0 L 10 10"`. `text` has
+    /// one element more than `numbers`.
+    Numbers {
+        text: Vec<String>,
+        numbers: Vec<f64>,
+    },
+}
+
+impl Animations {
+    /// The time in seconds the last animation ends. Animations that repeat indefinitely end
+    /// after their first iteration.
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    /// Sets the animated attributes to their values at `time` seconds.
+    pub fn seek(&mut self, time: f64) {
+        for target in &mut self.targets {
+            let mut value = target.base.clone();
+            for animation in &target.animations {
+                let Some(progress) = animation.timing.progress(time) else {
+                    continue;
+                };
+                let animated = animation.value_at(progress);
+                value = match (animation.transform, animation.additive) {
+                    (Some(transform), true) => value.concat(&transform.apply(&animated)),
+                    (Some(transform), false) => transform.apply(&animated),
+                    (None, true) => value.add(&animated),
+                    (None, false) => animated,
+                };
+            }
+            target
+                .node
+                .parse_and_set_attribute(&target.attribute, value.to_string());
+        }
+    }
+}
+
+/// Collects the animations of `document` and resolves their targets to `nodes`. `styles` are the
+/// declarations the stylesheets set on the elements.
+///
+/// Returns `None` if the document has no animations.
+pub(crate) fn prepare(
+    document: &Document,
+    nodes: &[Option<Node>],
+    styles: &[Declarations],
+) -> Option<Animations> {
+    let elements = &document.elements;
+    let ids: HashMap<&str, usize> = elements
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| !is_animation_element(&e.name))
+        .filter_map(|(i, e)| e.attribute("id").map(|id| (id, i)))
+        .collect();
+    let base_of = |element: usize, attribute: &str| {
+        parse_value(&base_value(
+            &elements[element],
+            styles.get(element).map(Vec::as_slice).unwrap_or_default(),
+            attribute,
+        ))
+    };
+
+    // The animations grouped by target element and attribute, in document order.
+    let mut grouped: Vec<((usize, String), Vec<Animation>)> = Vec::new();
+    for element in elements.iter().filter(|e| is_animation_element(&e.name)) {
+        let attributes: HashMap<String, String> = element.attributes.iter().cloned().collect();
+        let target = match attributes
+            .get("href")
+            .or_else(|| attributes.get("xlink:href"))
+        {
+            Some(href) => href.strip_prefix('#').and_then(|id| ids.get(id).copied()),
+            None => element
+                .parent
+                .filter(|parent| !is_animation_element(&elements[*parent].name)),
+        };
+        let (Some(target), Some(attribute)) = (target, attributes.get("attributeName")) else {
+            continue;
+        };
+        let base = base_of(target, attribute);
+        let Some(animation) = Animation::parse(&element.name, &attributes, &base) else {
+            continue;
+        };
+        let key = (target, attribute.clone());
+        match grouped.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, animations)) => animations.push(animation),
+            None => grouped.push((key, vec![animation])),
+        }
+    }
+
+    let targets: Vec<Target> = grouped
+        .into_iter()
+        .filter_map(|((element, attribute), animations)| {
+            Some(Target {
+                node: nodes.get(element)?.clone()?,
+                base: base_of(element, &attribute),
+                attribute,
+                animations,
+            })
+        })
+        .collect();
+    if targets.is_empty() {
+        return None;
+    }
+
+    let duration = targets
+        .iter()
+        .flat_map(|t| &t.animations)
+        .map(|a| a.timing.end())
+        .fold(0.0, f64::max);
+    Some(Animations { targets, duration })
+}
+
+/// Returns the value of `attribute` of `element` before it is animated. The declarations of the
+/// stylesheets take precedence over the `style` attribute, which takes precedence over the
+/// attribute.
+fn base_value(element: &Element, styles: &[(String, String)], attribute: &str) -> String {
+    let styled = styles
+        .iter()
+        .rfind(|(name, _)| name == attribute)
+        .map(|(_, value)| value.clone());
+    let style = || {
+        element.attribute("style").and_then(|style| {
+            style
+                .split(';')
+                .filter_map(|d| d.split_once(':'))
+                .filter(|(name, _)| name.trim() == attribute)
+                .map(|(_, value)| value.trim().to_string())
+                .next_back()
+        })
+    };
+    if let Some(value) = styled
+        .or_else(style)
+        .or_else(|| element.attribute(attribute).map(Into::into))
+    {
+        return value;
+    }
+    if matches!(
+        attribute,
+        "transform" | "gradientTransform" | "patternTransform"
+    ) {
+        "matrix(1 0 0 1 0 0)".into()
+    } else {
+        // Restores the inherited value of presentation attributes.
+        "inherit".into()
+    }
+}
+
+impl Animation {
+    fn parse(element: &str, attributes: &HashMap<String, String>, base: &Value) -> Option<Self> {
+        let get = |name: &str| {
+            attributes
+                .get(name)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+
+        let transform = match element {
+            "animateTransform" => Some(match get("type").unwrap_or("translate") {
+                "translate" => TransformType::Translate,
+                "scale" => TransformType::Scale,
+                "rotate" => TransformType::Rotate,
+                "skewX" => TransformType::SkewX,
+                "skewY" => TransformType::SkewY,
+                _ => return None,
+            }),
+            _ => None,
+        };
+        let parse = |value: &str| match transform {
+            Some(_) => parse_parameters(value),
+            None => Some(parse_value(value)),
+        };
+        let optional = |name: &str| {
+            get(name)
+                .map(parse)
+                .map_or(Ok(None), |v| v.ok_or(()).map(Some))
+        };
+
+        let mut additive = get("additive") == Some("sum");
+        let values = if element == "set" {
+            vec![parse(get("to")?)?]
+        } else if let Some(values) = get("values") {
+            values
+                .split(';')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(parse)
+                .collect::<Option<Vec<_>>>()?
+        } else {
+            let (from, to, by) = (
+                optional("from").ok()?,
+                optional("to").ok()?,
+                optional("by").ok()?,
+            );
+            let underlying = |like: &Value| match transform {
+                Some(transform) => transform.identity(like),
+                None => base.clone(),
+            };
+            match (from, to, by) {
+                (Some(from), Some(to), _) => vec![from, to],
+                (Some(from), None, Some(by)) => {
+                    let to = from.add(&by);
+                    vec![from, to]
+                }
+                (None, Some(to), _) => vec![underlying(&to), to],
+                (None, None, Some(by)) => {
+                    // By animations are additive.
+                    additive = true;
+                    let from = match transform {
+                        Some(transform) => transform.identity(&by),
+                        None => zero(&by),
+                    };
+                    vec![from, by]
+                }
+                _ => return None,
+            }
+        };
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut calc_mode = match (element, get("calcMode")) {
+            ("set", _) | (_, Some("discrete")) => CalcMode::Discrete,
+            (_, Some("spline")) => CalcMode::Spline,
+            _ => CalcMode::Linear,
+        };
+        let key_times = get("keyTimes").and_then(parse_list).filter(|key_times| {
+            key_times.len() == values.len()
+                && key_times[0] == 0.0
+                && key_times.windows(2).all(|w| w[0] <= w[1])
+                && key_times.last().is_some_and(|last| {
+                    *last <= 1.0 && (calc_mode == CalcMode::Discrete || *last == 1.0)
+                })
+        });
+        let key_splines = get("keySplines")
+            .filter(|_| calc_mode == CalcMode::Spline)
+            .and_then(|splines| {
+                splines
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(|spline| {
+                        let spline: [f64; 4] = parse_numbers(spline)?.try_into().ok()?;
+                        spline
+                            .iter()
+                            .all(|v| (0.0..=1.0).contains(v))
+                            .then_some(spline)
+                    })
+                    .collect::<Option<Vec<_>>>()
+            })
+            .filter(|splines| splines.len() + 1 == values.len());
+        if calc_mode == CalcMode::Spline && key_splines.is_none() {
+            calc_mode = CalcMode::Linear;
+        }
+
+        Some(Self {
+            timing: Timing::parse(&get, get("fill") == Some("freeze"))?,
+            values,
+            calc_mode,
+            key_times,
+            key_splines,
+            transform,
+            additive,
+        })
+    }
+
+    /// Returns the value at `progress` through the simple duration.
+    fn value_at(&self, progress: f64) -> Value {
+        let n = self.values.len();
+        if n == 1 {
+            return self.values[0].clone();
+        }
+
+        if self.calc_mode == CalcMode::Discrete {
+            let i = match &self.key_times {
+                Some(key_times) => key_times.iter().rposition(|k| *k <= progress),
+                None => Some((progress * n as f64) as usize),
+            };
+            return self.values[i.unwrap_or(0).min(n - 1)].clone();
+        }
+
+        let key_time = |i: usize| match &self.key_times {
+            Some(key_times) => key_times[i],
+            None => i as f64 / (n - 1) as f64,
+        };
+        let i = (0..n - 1).rfind(|i| key_time(*i) <= progress).unwrap_or(0);
+        let (start, end) = (key_time(i), key_time(i + 1));
+        let mut t = if end > start {
+            ((progress - start) / (end - start)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        if let Some(key_splines) = &self.key_splines {
+            t = cubic_bezier(key_splines[i], t);
+        }
+        self.values[i].interpolate(&self.values[i + 1], t)
+    }
+}
+
+impl Timing {
+    fn parse<'a>(get: &impl Fn(&str) -> Option<&'a str>, freeze: bool) -> Option<Self> {
+        let offset = |value: &str| value.split(';').find_map(|v| parse_offset(v.trim()));
+        let repeat = |value: &str, parse: fn(&str) -> Option<f64>| match value {
+            "indefinite" => Some(f64::INFINITY),
+            value => parse(value).filter(|v| *v > 0.0),
+        };
+
+        let begin = match get("begin") {
+            Some(begin) => offset(begin)?,
+            None => 0.0,
+        };
+        let simple = get("dur").and_then(parse_clock).filter(|d| *d > 0.0);
+        let repeat_count = get("repeatCount").and_then(|r| repeat(r, |r| r.parse().ok()));
+        let repeat_dur = get("repeatDur").and_then(|r| repeat(r, parse_clock));
+
+        let mut active = match (simple, repeat_count, repeat_dur) {
+            (None, _, repeat_dur) => repeat_dur.unwrap_or(f64::INFINITY),
+            (Some(simple), None, None) => simple,
+            (Some(simple), repeat_count, repeat_dur) => repeat_count
+                .map_or(f64::INFINITY, |count| count * simple)
+                .min(repeat_dur.unwrap_or(f64::INFINITY)),
+        };
+        if let Some(end) = get("end").and_then(offset) {
+            active = active.min((end - begin).max(0.0));
+        }
+
+        Some(Self {
+            begin,
+            simple,
+            active,
+            freeze,
+        })
+    }
+
+    /// The time the animation ends, or if it repeats indefinitely, the time its first
+    /// iteration ends.
+    fn end(&self) -> f64 {
+        self.begin
+            + if self.active.is_finite() {
+                self.active
+            } else {
+                self.simple.unwrap_or(0.0)
+            }
+    }
+
+    /// Returns the progress through the simple duration at `time`, or `None` if the animation
+    /// has no effect at `time`.
+    fn progress(&self, time: f64) -> Option<f64> {
+        if time < self.begin {
+            return None;
+        }
+        let elapsed = time - self.begin;
+        if elapsed < self.active {
+            return Some(
+                self.simple
+                    .map_or(0.0, |simple| (elapsed % simple) / simple),
+            );
+        }
+        if !self.freeze {
+            return None;
+        }
+        let Some(simple) = self.simple else {
+            return Some(0.0);
+        };
+        let local = self.active % simple;
+        Some(if local == 0.0 && self.active > 0.0 {
+            1.0
+        } else {
+            local / simple
+        })
+    }
+}
+
+impl TransformType {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Translate => "translate",
+            Self::Scale => "scale",
+            Self::Rotate => "rotate",
+            Self::SkewX => "skewX",
+            Self::SkewY => "skewY",
+        }
+    }
+
+    /// Returns parameters with the same count as `like` that do not transform.
+    fn identity(self, like: &Value) -> Value {
+        let Value::Numbers { text, numbers } = like else {
+            return like.clone();
+        };
+        let numbers = numbers
+            .iter()
+            .enumerate()
+            .map(|(i, n)| match (self, i) {
+                (Self::Scale, _) => 1.0,
+                // The center of a rotation.
+                (Self::Rotate, 1 | 2) => *n,
+                _ => 0.0,
+            })
+            .collect();
+        Value::Numbers {
+            text: text.clone(),
+            numbers,
+        }
+    }
+
+    /// Wraps `parameters` into a transform function.
+    fn apply(self, parameters: &Value) -> Value {
+        match parameters {
+            Value::Numbers { text, numbers } => {
+                let mut text = text.clone();
+                text[0].insert_str(0, &format!("{}(", self.name()));
+                text.last_mut().unwrap().push(')');
+                Value::Numbers {
+                    text,
+                    numbers: numbers.clone(),
+                }
+            }
+            Value::Color(_) => parameters.clone(),
+        }
+    }
+}
+
+impl Value {
+    /// Interpolates between `self` and `to`. Values that can not be interpolated change
+    /// halfway.
+    fn interpolate(&self, to: &Value, t: f64) -> Value {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        match (self, to) {
+            (Value::Color(a), Value::Color(b)) => {
+                let channel = |a: u8, b: u8| lerp(a.into(), b.into()).round() as u8;
+                Value::Color(Color::from_argb(
+                    channel(a.a(), b.a()),
+                    channel(a.r(), b.r()),
+                    channel(a.g(), b.g()),
+                    channel(a.b(), b.b()),
+                ))
+            }
+            (
+                Value::Numbers { text, numbers: a },
+                Value::Numbers {
+                    text: to_text,
+                    numbers: b,
+                },
+            ) if text == to_text && a.len() == b.len() => Value::Numbers {
+                text: text.clone(),
+                numbers: a.iter().zip(b).map(|(a, b)| lerp(*a, *b)).collect(),
+            },
+            _ if t < 0.5 => self.clone(),
+            _ => to.clone(),
+        }
+    }
+
+    /// Adds `other` to `self`. If the values can not be added, `other` is returned.
+    fn add(&self, other: &Value) -> Value {
+        match (self, other) {
+            (Value::Color(a), Value::Color(b)) => Value::Color(Color::from_argb(
+                a.a().max(b.a()),
+                a.r().saturating_add(b.r()),
+                a.g().saturating_add(b.g()),
+                a.b().saturating_add(b.b()),
+            )),
+            (
+                Value::Numbers { text, numbers: a },
+                Value::Numbers {
+                    text: other_text,
+                    numbers: b,
+                },
+            ) if text == other_text && a.len() == b.len() => Value::Numbers {
+                text: text.clone(),
+                numbers: a.iter().zip(b).map(|(a, b)| a + b).collect(),
+            },
+            _ => other.clone(),
+        }
+    }
+
+    /// Appends `other` separated by a space, which combines transform lists.
+    fn concat(&self, other: &Value) -> Value {
+        match (self, other) {
+            (
+                Value::Numbers { text, numbers },
+                Value::Numbers {
+                    text: other_text,
+                    numbers: other_numbers,
+                },
+            ) => {
+                let mut text = text.clone();
+                let joined = format!("{} {}", text.pop().unwrap(), other_text[0]);
+                text.push(joined);
+                text.extend_from_slice(&other_text[1..]);
+                Value::Numbers {
+                    text,
+                    numbers: numbers.iter().chain(other_numbers).copied().collect(),
+                }
+            }
+            _ => other.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Color(color) if color.a() == 0xff => {
+                write!(f, "#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+            }
+            Value::Color(color) => {
+                let alpha = (f64::from(color.a()) / 255.0 * 1e3).round() / 1e3;
+                write!(
+                    f,
+                    "rgba({}, {}, {}, {alpha})",
+                    color.r(),
+                    color.g(),
+                    color.b()
+                )
+            }
+            Value::Numbers { text, numbers } => {
+                for (text, number) in text.iter().zip(numbers) {
+                    // Rounding avoids printing artifacts like 0.30000000000000004.
+                    write!(f, "{text}{}", (number * 1e6).round() / 1e6)?;
+                }
+                f.write_str(text.last().unwrap())
+            }
+        }
+    }
+}
+
+/// Returns the same value with all numbers set to 0.
+fn zero(like: &Value) -> Value {
+    match like {
+        Value::Color(_) => Value::Color(Color::from_argb(0, 0, 0, 0)),
+        Value::Numbers { text, numbers } => Value::Numbers {
+            text: text.clone(),
+            numbers: vec![0.0; numbers.len()],
+        },
+    }
+}
+
+fn parse_value(value: &str) -> Value {
+    if let Some(color) = parse_color(value) {
+        return Value::Color(color);
+    }
+
+    let mut text = vec![String::new()];
+    let mut numbers = Vec::new();
+    let mut rest = value;
+    while let Some(c) = rest.chars().next() {
+        // IRIs are not split, even if they contain digits.
+        if rest.starts_with("url(") {
+            let end = rest.find(')').map(|i| i + 1).unwrap_or(rest.len());
+            text.last_mut().unwrap().push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        match number_len(rest) {
+            Some(len) => {
+                numbers.push(rest[..len].parse().unwrap_or_default());
+                text.push(String::new());
+                rest = &rest[len..];
+            }
+            None => {
+                text.last_mut().unwrap().push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    Value::Numbers { text, numbers }
+}
+
+/// Parses the parameters of a transform function, for example `"10, 20"`.
+fn parse_parameters(value: &str) -> Option<Value> {
+    let numbers = parse_numbers(value)?;
+    if numbers.is_empty() {
+        return None;
+    }
+    let mut text = vec![String::new()];
+    text.extend((1..numbers.len()).map(|_| " ".to_string()));
+    text.push(String::new());
+    Some(Value::Numbers { text, numbers })
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let value = CString::new(value).ok()?;
+    let mut color = 0;
+    unsafe { sb::C_SkSVGAttributeParser_parseColor(value.as_ptr(), &mut color) }
+        .then(|| Color::from(color))
+}
+
+/// Returns the length of the number at the start of `s`.
+fn number_len(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let digits = |from: usize| {
+        bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+
+    let mut len = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let integer = digits(len);
+    len += integer;
+    let mut fraction = 0;
+    if bytes.get(len) == Some(&b'.') {
+        fraction = digits(len + 1);
+        if fraction > 0 {
+            len += 1 + fraction;
+        }
+    }
+    if integer == 0 && fraction == 0 {
+        return None;
+    }
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+        let exponent = digits(len + 1 + sign);
+        if exponent > 0 {
+            len += 1 + sign + exponent;
+        }
+    }
+    Some(len)
+}
+
+/// Parses numbers separated by whitespace or commas.
+fn parse_numbers(s: &str) -> Option<Vec<f64>> {
+    s.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|n| !n.is_empty())
+        .map(|n| n.parse().ok())
+        .collect()
+}
+
+/// Parses a `keyTimes` list.
+fn parse_list(s: &str) -> Option<Vec<f64>> {
+    s.split(';').map(|n| n.trim().parse().ok()).collect()
+}
+
+/// Parses an offset value like `"2s"` or `"-0.5s"`.
+fn parse_offset(value: &str) -> Option<f64> {
+    match value.strip_prefix('-') {
+        Some(value) => parse_clock(value.trim_start()).map(|v| -v),
+        None => parse_clock(value.strip_prefix('+').unwrap_or(value).trim_start()),
+    }
+}
+
+/// Parses a clock value like `"01:30"`, `"2.5s"`, `"500ms"` or `"3"` into seconds.
+fn parse_clock(value: &str) -> Option<f64> {
+    let number = |s: &str| -> Option<f64> {
+        if !s.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+            return None;
+        }
+        s.parse().ok()
+    };
+    if value.contains(':') {
+        return value
+            .split(':')
+            .try_fold((0.0, 0), |(seconds, parts), part| {
+                Some((seconds * 60.0 + number(part)?, parts + 1))
+            })
+            .filter(|(_, parts)| *parts <= 3)
+            .map(|(seconds, _)| seconds);
+    }
+    let (value, scale) = [("ms", 0.001), ("min", 60.0), ("h", 3600.0), ("s", 1.0)]
+        .into_iter()
+        .find_map(|(unit, scale)| value.strip_suffix(unit).map(|v| (v, scale)))
+        .unwrap_or((value, 1.0));
+    number(value).map(|v| v * scale)
+}
+
+/// Evaluates the easing curve with the control points `(x1, y1)` and `(x2, y2)` at `x`.
+fn cubic_bezier([x1, y1, x2, y2]: [f64; 4], x: f64) -> f64 {
+    let bezier = |t: f64, p1: f64, p2: f64| {
+        let u = 1.0 - t;
+        3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+    };
+    // x(t) is monotonic for control points in [0, 1].
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..32 {
+        let t = (low + high) / 2.0;
+        if bezier(t, x1, x2) < x {
+            low = t;
+        } else {
+            high = t;
+        }
+    }
+    bezier((low + high) / 2.0, y1, y2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation(attributes: &[(&str, &str)]) -> Option<Animation> {
+        let attributes = attributes
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Animation::parse("animate", &attributes, &parse_value("0"))
+    }
+
+    #[test]
+    fn clock_values() {
+        assert_eq!(parse_clock("2.5s"), Some(2.5));
+        assert_eq!(parse_clock("500ms"), Some(0.5));
+        assert_eq!(parse_clock("3"), Some(3.0));
+        assert_eq!(parse_clock("1min"), Some(60.0));
+        assert_eq!(parse_clock("01:30"), Some(90.0));
+        assert_eq!(parse_clock("1:00:05"), Some(3605.0));
+        assert_eq!(parse_clock("indefinite"), None);
+        assert_eq!(parse_offset("-1s"), Some(-1.0));
+        assert_eq!(parse_offset("+ 2s"), Some(2.0));
+    }
+
+    #[test]
+    fn timing() {
+        let a = animation(&[("values", "0;10"), ("begin", "1s"), ("dur", "2s")]).unwrap();
+        assert_eq!(a.timing.progress(0.5), None);
+        assert_eq!(a.timing.progress(2.0), Some(0.5));
+        assert_eq!(a.timing.progress(3.5), None);
+        assert_eq!(a.timing.end(), 3.0);
+
+        let a = animation(&[
+            ("values", "0;10"),
+            ("dur", "2s"),
+            ("repeatCount", "1.5"),
+            ("fill", "freeze"),
+        ])
+        .unwrap();
+        assert_eq!(a.timing.progress(2.5), Some(0.25));
+        assert_eq!(a.timing.progress(10.0), Some(0.5));
+
+        let a = animation(&[
+            ("values", "0;10"),
+            ("dur", "1s"),
+            ("repeatCount", "indefinite"),
+        ])
+        .unwrap();
+        assert_eq!(a.timing.progress(7.25), Some(0.25));
+        assert_eq!(a.timing.end(), 1.0);
+
+        assert!(animation(&[("values", "0;10"), ("begin", "click")]).is_none());
+    }
+
+    #[test]
+    fn values() {
+        let a = animation(&[("values", "0px;10px;30px"), ("dur", "1s")]).unwrap();
+        assert_eq!(a.value_at(0.25).to_string(), "5px");
+        assert_eq!(a.value_at(0.75).to_string(), "20px");
+
+        let a = animation(&[
+            ("values", "0;10;30"),
+            ("keyTimes", "0;0.8;1"),
+            ("calcMode", "discrete"),
+        ])
+        .unwrap();
+        assert_eq!(a.value_at(0.5).to_string(), "0");
+        assert_eq!(a.value_at(0.9).to_string(), "10");
+
+        let a = animation(&[
+            ("from", "0"),
+            ("to", "1"),
+            ("calcMode", "spline"),
+            ("keySplines", "0 0 1 1"),
+        ])
+        .unwrap();
+        assert!((a.value_at(0.3).to_string().parse::<f64>().unwrap() - 0.3).abs() < 1e-6);
+
+        let a = animation(&[("by", "5")]).unwrap();
+        assert!(a.additive);
+        assert_eq!(parse_value("2").add(&a.value_at(1.0)).to_string(), "7");
+
+        let path = parse_value("M0 -5 L 1.5 .5");
+        assert_eq!(
+            path.interpolate(&parse_value("M10 5 L 2.5 1.5"), 0.5)
+                .to_string(),
+            "M5 0 L 2 1"
+        );
+        let Value::Numbers { numbers, .. } = parse_value("M0-5 1.5.5e1") else {
+            panic!("expected numbers");
+        };
+        assert_eq!(numbers, [0.0, -5.0, 1.5, 5.0]);
+        assert_eq!(
+            parse_value("url(#a1)").interpolate(&parse_value("url(#b2)"), 0.4),
+            parse_value("url(#a1)")
+        );
+
+        let red = parse_value("red");
+        assert_eq!(red.to_string(), "#ff0000");
+        assert_eq!(
+            red.interpolate(&parse_value("#0000ff"), 0.5).to_string(),
+            "#800080"
+        );
+        assert_eq!(
+            zero(&red).interpolate(&red, 0.5).to_string(),
+            "rgba(128, 0, 0, 0.502)"
+        );
+    }
+
+    #[test]
+    fn transforms() {
+        let rotate = TransformType::Rotate;
+        let parameters = parse_parameters("90, 5 5").unwrap();
+        assert_eq!(rotate.apply(&parameters).to_string(), "rotate(90 5 5)");
+        assert_eq!(
+            rotate.identity(&parameters).to_string(),
+            parse_parameters("0 5 5").unwrap().to_string()
+        );
+        let base = parse_value("translate(10,20)");
+        assert_eq!(
+            base.concat(&rotate.apply(&parameters)).to_string(),
+            "translate(10,20) rotate(90 5 5)"
+        );
+    }
+}
@@ -22,6 +22,42 @@ impl Container {
         unsafe { sb::C_SkSVGContainer_appendChild(self.native_mut(), node.into().into_ptr()) }
     }
 
+    /// Inserts `node` at `index`, shifting all children after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > children().len()`.
+    pub fn insert_child(&mut self, index: usize, node: impl Into<Node>) {
+        assert!(index <= self.children_count());
+        unsafe {
+            sb::C_SkSVGContainer_insertChild(self.native_mut(), index, node.into().into_ptr())
+        }
+    }
+
+    /// Removes and returns the child at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= children().len()`.
+    pub fn remove_child(&mut self, index: usize) -> Node {
+        assert!(index < self.children_count());
+        Node::from_ptr(unsafe { sb::C_SkSVGContainer_removeChild(self.native_mut(), index) })
+            .unwrap()
+    }
+
+    /// Removes `node` from the direct children of this container.
+    ///
+    /// Returns `false` if `node` is not a child of this container.
+    pub fn remove_child_node(&mut self, node: &Node) -> bool {
+        match self.children().iter().position(|child| child.ptr_eq(node)) {
+            Some(index) => {
+                self.remove_child(index);
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn children(&self) -> &[Node] {
         unsafe {
             let sp_slice = safer::from_raw_parts(
@@ -43,3 +79,24 @@ impl Container {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::svg::{Circle, G, Rect};
+
+    #[test]
+    fn insert_and_remove_children() {
+        let mut g = G::default();
+        let rect = Rect::default().into_node();
+        let circle = Circle::default().into_node();
+        g.append_child(rect.clone());
+        g.insert_child(0, circle.clone());
+        assert!(g.children()[0].ptr_eq(&circle));
+        assert!(g.children()[1].ptr_eq(&rect));
+
+        assert!(g.remove_child(0).ptr_eq(&circle));
+        assert!(g.remove_child_node(&rect));
+        assert!(!g.remove_child_node(&rect));
+        assert!(g.children().is_empty());
+    }
+}
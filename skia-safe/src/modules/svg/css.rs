@@ -0,0 +1,551 @@
+//! A subset of CSS for styling SVG documents.
+//!
+//! Skia's SVG parser applies presentation attributes and `style` attributes, but ignores
+//! `<style>` elements and `class` attributes. After a document is loaded, the rules of its
+//! stylesheets are matched against its elements, and the matching declarations are set on the
+//! nodes of the elements.
+
+use std::str::FromStr;
+
+use super::{Node, xml::Document};
+
+/// A parsed CSS stylesheet.
+///
+/// Supported are type, class, id and universal selectors, compound selectors like `rect.a#b`,
+/// the descendant and child combinators, and selector lists. Rules that use other selectors and
+/// at-rules like `@media` are ignored. Declarations can be marked `!important`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stylesheet {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    selector: Selector,
+    declarations: Vec<Declaration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Declaration {
+    name: String,
+    value: String,
+    important: bool,
+}
+
+/// A complex selector. Each compound selector is stored with the combinator that relates it to
+/// the compound selector before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Selector {
+    compounds: Vec<(Combinator, Compound)>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Compound {
+    element: Option<String>,
+    ids: Vec<String>,
+    classes: Vec<String>,
+}
+
+/// The (id, class, type) specificity of a selector.
+type Specificity = (usize, usize, usize);
+
+/// An element and the attributes that selectors match against.
+#[derive(Debug)]
+struct Element<'a> {
+    name: &'a str,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+impl Stylesheet {
+    /// Parses a stylesheet. Rules and declarations that can not be parsed are skipped.
+    pub fn parse(css: &str) -> Self {
+        let css = strip_comments(css);
+        let mut rules = Vec::new();
+        let mut rest = css.as_str();
+
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            if rest.starts_with('@') {
+                rest = skip_at_rule(rest);
+                continue;
+            }
+            let Some(open) = rest.find('{') else {
+                break;
+            };
+            let prelude = &rest[..open];
+            let body_start = open + 1;
+            let body_end = rest[body_start..]
+                .find('}')
+                .map(|i| body_start + i)
+                .unwrap_or(rest.len());
+            let body = &rest[body_start..body_end];
+            rest = rest.get(body_end + 1..).unwrap_or_default();
+
+            // An invalid selector invalidates the whole rule.
+            let Some(selectors) = split_top_level(prelude, ',')
+                .into_iter()
+                .map(Selector::parse)
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let declarations = parse_declarations(body);
+            if declarations.is_empty() {
+                continue;
+            }
+            rules.extend(selectors.into_iter().map(|selector| Rule {
+                selector,
+                declarations: declarations.clone(),
+            }));
+        }
+
+        Self { rules }
+    }
+
+    /// Returns `true` if the stylesheet does not contain any rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl FromStr for Stylesheet {
+    type Err = std::convert::Infallible;
+
+    fn from_str(css: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(css))
+    }
+}
+
+impl Selector {
+    fn parse(selector: &str) -> Option<Self> {
+        let mut compounds = Vec::new();
+        let mut combinator = Combinator::Descendant;
+        let mut rest = selector.trim();
+        if rest.is_empty() {
+            return None;
+        }
+
+        while !rest.is_empty() {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '>')
+                .unwrap_or(rest.len());
+            compounds.push((combinator, Compound::parse(&rest[..end])?));
+            rest = rest[end..].trim_start();
+            combinator = match rest.strip_prefix('>') {
+                Some(after) => {
+                    rest = after.trim_start();
+                    if rest.is_empty() {
+                        return None;
+                    }
+                    Combinator::Child
+                }
+                None => Combinator::Descendant,
+            };
+        }
+
+        Some(Self { compounds })
+    }
+
+    fn specificity(&self) -> Specificity {
+        self.compounds
+            .iter()
+            .fold((0, 0, 0), |(ids, classes, types), (_, compound)| {
+                (
+                    ids + compound.ids.len(),
+                    classes + compound.classes.len(),
+                    types + compound.element.is_some() as usize,
+                )
+            })
+    }
+
+    /// Returns `true` if the selector matches the last element of `elements`, which holds the
+    /// element and all its ancestors.
+    fn matches(&self, elements: &[Element]) -> bool {
+        matches_compounds(&self.compounds, elements)
+    }
+}
+
+fn matches_compounds(compounds: &[(Combinator, Compound)], elements: &[Element]) -> bool {
+    let Some(((combinator, compound), outer)) = compounds.split_last() else {
+        return true;
+    };
+    let Some((subject, ancestors)) = elements.split_last() else {
+        return false;
+    };
+    if !compound.matches(subject) {
+        return false;
+    }
+    if outer.is_empty() {
+        return true;
+    }
+    match combinator {
+        Combinator::Child => matches_compounds(outer, ancestors),
+        Combinator::Descendant => {
+            (0..ancestors.len()).any(|i| matches_compounds(outer, &ancestors[..=i]))
+        }
+    }
+}
+
+impl Compound {
+    fn parse(compound: &str) -> Option<Self> {
+        let (element, mut rest) =
+            compound.split_at(compound.find(['.', '#']).unwrap_or(compound.len()));
+        let mut parsed = Self::default();
+        match element {
+            "" if rest.is_empty() => return None,
+            "" | "*" => {}
+            name if is_identifier(name) => parsed.element = Some(name.into()),
+            _ => return None,
+        }
+
+        while let Some(marker) = rest.chars().next() {
+            let end = rest[1..]
+                .find(['.', '#'])
+                .map(|i| i + 1)
+                .unwrap_or(rest.len());
+            let name = &rest[1..end];
+            if !is_identifier(name) {
+                return None;
+            }
+            match marker {
+                '.' => parsed.classes.push(name.into()),
+                _ => parsed.ids.push(name.into()),
+            }
+            rest = &rest[end..];
+        }
+
+        Some(parsed)
+    }
+
+    fn matches(&self, element: &Element) -> bool {
+        self.element
+            .as_ref()
+            .is_none_or(|name| name == element.name)
+            && self
+                .ids
+                .iter()
+                .all(|id| element.id.as_deref() == Some(id.as_str()))
+            && self
+                .classes
+                .iter()
+                .all(|class| element.classes.contains(class))
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii())
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// Skips an at-rule, which ends with either a `;` or a block that may contain nested blocks.
+fn skip_at_rule(css: &str) -> &str {
+    let mut depth = 0usize;
+    for (i, c) in css.char_indices() {
+        match c {
+            ';' if depth == 0 => return &css[i + 1..],
+            '{' => depth += 1,
+            '}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return &css[i + 1..];
+                }
+            }
+            _ => {}
+        }
+    }
+    ""
+}
+
+/// Splits `s` at `separator`, but not inside of parentheses or quoted strings.
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if q == c => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, c) if c == separator && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn parse_declarations(block: &str) -> Vec<Declaration> {
+    split_top_level(block, ';')
+        .into_iter()
+        .filter_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            let name = name.trim();
+            let value = value.trim();
+            let (value, important) = match value.rfind('!') {
+                Some(i) if value[i + 1..].trim().eq_ignore_ascii_case("important") => {
+                    (value[..i].trim_end(), true)
+                }
+                _ => (value, false),
+            };
+            if name.is_empty() || value.is_empty() {
+                return None;
+            }
+            Some(Declaration {
+                name: name.into(),
+                value: value.into(),
+                important,
+            })
+        })
+        .collect()
+}
+
+/// The declarations that were set on the node of an element, in the order they were set.
+pub(crate) type Declarations = Vec<(String, String)>;
+
+/// Applies the rules of the `<style>` elements of `document` and then the rules of `user` to the
+/// nodes of the matching elements.
+///
+/// Returns the declarations that were set, indexed like the elements, or nothing if there are no
+/// rules.
+pub(crate) fn apply(
+    document: &Document,
+    nodes: &[Option<Node>],
+    user: Option<&Stylesheet>,
+) -> Vec<Declarations> {
+    let mut css = String::new();
+    for element in &document.elements {
+        if element.name == "style"
+            && element
+                .attribute("type")
+                .is_none_or(|t| t.trim() == "text/css")
+        {
+            css.push_str(&element.text);
+            css.push('\n');
+        }
+    }
+
+    let stylesheet = Stylesheet::parse(&css);
+    let rules: Vec<_> = stylesheet
+        .rules
+        .iter()
+        .chain(user.into_iter().flat_map(|user| &user.rules))
+        .collect();
+    if rules.is_empty() {
+        return Vec::new();
+    }
+
+    // The current element and its ancestors, and their indices.
+    let mut elements: Vec<Element> = Vec::new();
+    let mut indices: Vec<usize> = Vec::new();
+    let mut applied = Vec::with_capacity(document.elements.len());
+
+    for (index, element) in document.elements.iter().enumerate() {
+        while indices.last().is_some_and(|i| Some(*i) != element.parent) {
+            indices.pop();
+            elements.pop();
+        }
+        indices.push(index);
+        elements.push(Element {
+            name: &element.name,
+            id: element.attribute("id").map(Into::into),
+            classes: element
+                .attribute("class")
+                .map(|classes| classes.split_whitespace().map(Into::into).collect())
+                .unwrap_or_default(),
+        });
+
+        let mut declarations = Declarations::new();
+        if let Some(mut node) = nodes[index].clone() {
+            for (name, value) in cascade(&rules, &elements, element.attribute("style")) {
+                node.parse_and_set_attribute(name, value);
+                declarations.push((name.into(), value.into()));
+            }
+        }
+        applied.push(declarations);
+    }
+
+    applied
+}
+
+/// Splits the value of a `style` attribute into its declarations, like Skia's parser does.
+pub(crate) fn style_declarations(style: &str) -> impl Iterator<Item = (&str, &str)> {
+    style
+        .split(';')
+        .filter_map(|d| d.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+}
+
+/// Returns the declarations of the rules that match the last element of `elements` in the order
+/// they are set, or nothing if no rule matches.
+///
+/// Skia has already set the presentation attributes and the declarations of the `style`
+/// attribute while loading the document. Later declarations override earlier ones, so the
+/// declarations are ordered by importance, specificity and source order, and the declarations of
+/// the `style` attribute are set again between the normal and the important declarations.
+fn cascade<'a>(
+    rules: &[&'a Rule],
+    elements: &[Element],
+    inline: Option<&'a str>,
+) -> Vec<(&'a str, &'a str)> {
+    let mut matched: Vec<(bool, Specificity, usize, &Declaration)> = Vec::new();
+    for (order, rule) in rules.iter().enumerate() {
+        if rule.selector.matches(elements) {
+            let specificity = rule.selector.specificity();
+            matched.extend(
+                rule.declarations
+                    .iter()
+                    .map(|d| (d.important, specificity, order, d)),
+            );
+        }
+    }
+    if matched.is_empty() {
+        return Vec::new();
+    }
+    matched.sort_by_key(|(important, specificity, order, _)| (*important, *specificity, *order));
+
+    let mut inline = inline.map(|style| style_declarations(style).collect::<Vec<_>>());
+    let mut declarations = Vec::new();
+    for (important, _, _, declaration) in matched {
+        if let Some(inline) = inline.take_if(|_| important) {
+            declarations.extend(inline);
+        }
+        declarations.push((declaration.name.as_str(), declaration.value.as_str()));
+    }
+    declarations.extend(inline.into_iter().flatten());
+    declarations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rules() {
+        let sheet = Stylesheet::parse(
+            "/* c */ @import url(a.css); @media print { rect { fill: red } }
+             rect.a, #b > g .c { fill: blue; stroke : url(#g) !important; ; }
+             rect:hover { fill: green }
+             a[href] , g { fill: green }",
+        );
+        assert_eq!(sheet.rules.len(), 2);
+        assert_eq!(sheet.rules[0].selector.specificity(), (0, 1, 1));
+        assert_eq!(sheet.rules[1].selector.specificity(), (1, 1, 1));
+        assert_eq!(
+            sheet.rules[1].declarations[1],
+            Declaration {
+                name: "stroke".into(),
+                value: "url(#g)".into(),
+                important: true
+            }
+        );
+        assert!(Stylesheet::parse("").is_empty());
+        assert!(Selector::parse("g >").is_none());
+        assert!(Selector::parse(".1a").is_none());
+    }
+
+    #[test]
+    fn selectors_match() {
+        let element = |name, id: Option<&str>, classes: &[&str]| Element {
+            name,
+            id: id.map(Into::into),
+            classes: classes.iter().map(|c| c.to_string()).collect(),
+        };
+        let elements = [
+            element("svg", None, &[]),
+            element("g", Some("outer"), &["layer"]),
+            element("g", None, &[]),
+            element("rect", Some("r"), &["a", "b"]),
+        ];
+        let matches = |selector: &str| Selector::parse(selector).unwrap().matches(&elements);
+
+        assert!(matches("rect"));
+        assert!(matches("*"));
+        assert!(matches(".a.b"));
+        assert!(matches("rect#r.b"));
+        assert!(matches("#outer rect"));
+        assert!(matches("svg .layer > g > .a"));
+        assert!(!matches("#outer > rect"));
+        assert!(!matches(".c"));
+        assert!(!matches("circle"));
+        assert!(!matches("rect g"));
+    }
+
+    #[test]
+    fn declarations_are_cascaded() {
+        let element = Element {
+            name: "rect",
+            id: Some("r".into()),
+            classes: vec!["a".into()],
+        };
+        let cascaded = |stylesheets: &[&Stylesheet], inline| {
+            let rules: Vec<_> = stylesheets.iter().flat_map(|s| &s.rules).collect();
+            cascade(&rules, std::slice::from_ref(&element), inline)
+                .into_iter()
+                .map(|(name, value)| format!("{name}:{value}"))
+                .collect::<Vec<_>>()
+        };
+
+        let document = Stylesheet::parse("rect { fill: red; stroke: black } .a { fill: blue }");
+        assert_eq!(
+            cascaded(&[&document], Some("stroke: white; ")),
+            ["fill:red", "stroke:black", "fill:blue", "stroke:white"]
+        );
+        assert!(cascaded(&[&document], None).len() == 3);
+
+        // Specificity wins over source order, user rules come after the document's rules, and
+        // important declarations override the `style` attribute.
+        let document = Stylesheet::parse("#r { fill: red } rect { fill: blue }");
+        let user = Stylesheet::parse("rect { stroke: gray !important } #r { fill: black }");
+        assert_eq!(
+            cascaded(&[&document, &user], Some("stroke:white")),
+            [
+                "fill:blue",
+                "fill:red",
+                "fill:black",
+                "stroke:white",
+                "stroke:gray"
+            ]
+        );
+
+        assert!(
+            cascaded(
+                &[&Stylesheet::parse("circle { fill: red }")],
+                Some("fill:red")
+            )
+            .is_empty()
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt;
+
+use super::{
+    Node,
+    animation::is_animation_element,
+    xml::{Document, TextPosition},
+};
+
+/// Something in an SVG document that was ignored while loading it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub position: TextPosition,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// An element that is not supported. The element and all its children were skipped.
+    UnsupportedElement { name: String },
+    /// An attribute or `style` declaration with an unknown name or a value that could not be
+    /// parsed.
+    UnsupportedAttribute {
+        element: String,
+        name: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            WarningKind::UnsupportedElement { name } => {
+                write!(
+                    f,
+                    "{}: unsupported element <{name}> was skipped",
+                    self.position
+                )
+            }
+            WarningKind::UnsupportedAttribute {
+                element,
+                name,
+                value,
+            } => write!(
+                f,
+                "{}: unsupported attribute {name}={value:?} on <{element}> was ignored",
+                self.position
+            ),
+        }
+    }
+}
+
+/// Attributes that are handled by the DOM builder itself, have no effect on rendering, or belong
+/// to another XML namespace are not reported.
+fn is_ignored_attribute(name: &str) -> bool {
+    if matches!(name, "id" | "class" | "xmlns" | "version" | "baseProfile") {
+        return true;
+    }
+    match name.split_once(':') {
+        Some((prefix, _)) => prefix != "xlink" && prefix != "xml",
+        None => false,
+    }
+}
+
+/// Collects the elements and attributes of a well-formed document that are skipped by Skia.
+pub(crate) fn warnings(document: &Document) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    // Whether an element or one of its ancestors was skipped, indexed like the elements.
+    let mut skipped = vec![false; document.elements.len()];
+
+    for (index, element) in document.elements.iter().enumerate() {
+        if element.parent.is_some_and(|parent| skipped[parent]) {
+            skipped[index] = true;
+            continue;
+        }
+        let name = element.name.as_str();
+
+        let Some(mut node) = Node::from_element_name(name) else {
+            // Stylesheets and animations are handled outside of Skia, and elements of other
+            // namespaces are skipped silently.
+            if name != "style" && !is_animation_element(name) && !name.contains(':') {
+                warnings.push(Warning {
+                    kind: WarningKind::UnsupportedElement { name: name.into() },
+                    position: element.position,
+                });
+            }
+            skipped[index] = true;
+            continue;
+        };
+
+        for (attribute, value) in &element.attributes {
+            if is_ignored_attribute(attribute) {
+                continue;
+            }
+            let mut declarations = Vec::new();
+            if attribute == "style" {
+                declarations.extend(
+                    value
+                        .split(';')
+                        .filter_map(|d| d.split_once(':'))
+                        .map(|(n, v)| (n.trim(), v.trim())),
+                );
+            } else {
+                declarations.push((attribute.as_str(), value.as_str()));
+            }
+
+            for (attr_name, attr_value) in declarations {
+                if !node.parse_and_set_attribute(attr_name, attr_value) {
+                    warnings.push(Warning {
+                        kind: WarningKind::UnsupportedAttribute {
+                            element: name.into(),
+                            name: attr_name.into(),
+                            value: attr_value.into(),
+                        },
+                        position: element.position,
+                    });
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        FontMgr,
+        interop::{MemoryStream, NativeStreamBase},
+        modules::svg::xml,
+        prelude::*,
+        resources::{CachingResourceProvider, NativeResourceProvider},
+    };
+
+    #[test]
+    fn unsupported_elements_and_attributes_are_reported() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="x" width="10">
+            <title>Title</title>
+            <foreignObject><rect unknown="1"/></foreignObject>
+            <rect id="r" width="10" frobnicate="1" inkscape:label="l" style="fill: red; wobble: 2"/>
+        </svg>"#;
+
+        let provider: NativeResourceProvider = FontMgr::new_empty().into();
+        let (_, document) = xml::load(
+            MemoryStream::from_bytes(svg).native_mut().as_stream_mut(),
+            &provider.font_mgr(),
+            &CachingResourceProvider::new(&provider),
+        );
+        let warnings = warnings(&document);
+        let kinds: Vec<_> = warnings.iter().map(|w| &w.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &WarningKind::UnsupportedElement {
+                    name: "title".into()
+                },
+                &WarningKind::UnsupportedElement {
+                    name: "foreignObject".into()
+                },
+                &WarningKind::UnsupportedAttribute {
+                    element: "rect".into(),
+                    name: "frobnicate".into(),
+                    value: "1".into()
+                },
+                &WarningKind::UnsupportedAttribute {
+                    element: "rect".into(),
+                    name: "wobble".into(),
+                    value: "2".into()
+                },
+            ]
+        );
+        assert_eq!(warnings[0].position.line, 2);
+        // Attributes are reported at the position of their element.
+        assert_eq!(warnings[2].position.line, 4);
+    }
+}
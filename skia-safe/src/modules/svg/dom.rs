@@ -1,20 +1,41 @@
 use std::{
+    collections::HashMap,
     error::Error,
-    fmt,
-    io::{self},
+    ffi::{CStr, CString, c_char},
+    fmt, io, ptr,
 };
 
 use crate::{
-    Canvas, Size,
-    interop::{MemoryStream, NativeStreamBase, RustStream},
+    Canvas, ImageFilter, Path, Picture, Point, Rect, Size,
+    interop::{self, MemoryStream, NativeStreamBase, RustStream},
     prelude::*,
-    resources::NativeResourceProvider,
+    resources::{CachingResourceProvider, NativeResourceProvider},
 };
-use skia_bindings::{self as sb, SkRefCntBase};
+use skia_bindings::{self as sb, SkRefCntBase, SkStream};
 
-use super::Svg;
+use super::{
+    Filter, Node, Stylesheet, Svg, Warning,
+    animation::{self, Animations},
+    css, diagnostics, image, text, writer,
+    xml::{self, Document, TextPosition, XmlError},
+};
 
-pub type Dom = RCHandle<sb::SkSVGDOM>;
+pub struct Dom {
+    inner: RCHandle<sb::SkSVGDOM>,
+    /// The SMIL animations, which Skia's parser skips.
+    animations: Option<Animations>,
+    /// The ids of the nodes. Nodes do not store their ids, Skia's parser only registers them in
+    /// the id mapper of the DOM, so they are taken from the elements after loading and then
+    /// changed with [`Dom::set_node_id()`]. Nodes that are removed from the tree keep their ids
+    /// here, but are not found until they are added again.
+    ids: Vec<(Node, CString)>,
+    /// The ids Skia's id mapper was set up with while parsing, sorted. The mapper can not be
+    /// extended, so only these ids are updated in the mapper before rendering.
+    mapped_ids: Vec<CString>,
+    /// The recorded SVG documents that are referenced by `<image>` elements, with the unique ids
+    /// of the images that stand in for them while Skia renders the document.
+    image_documents: Vec<(u32, Picture)>,
+}
 require_base_type!(sb::SkSVGDOM, sb::SkRefCnt);
 
 impl NativeRefCountedBase for sb::SkSVGDOM {
@@ -27,6 +48,32 @@ impl fmt::Debug for Dom {
     }
 }
 
+impl AsRef<RCHandle<sb::SkSVGDOM>> for Dom {
+    fn as_ref(&self) -> &RCHandle<sb::SkSVGDOM> {
+        &self.inner
+    }
+}
+
+/// Wraps a DOM that was created elsewhere. The `Dom` has no animations and no ids, so
+/// [`Dom::find_node_by_id()`] and [`Dom::seek()`] do not find anything.
+impl From<RCHandle<sb::SkSVGDOM>> for Dom {
+    fn from(inner: RCHandle<sb::SkSVGDOM>) -> Self {
+        Self {
+            inner,
+            animations: None,
+            ids: Vec::new(),
+            mapped_ids: Vec::new(),
+            image_documents: Vec::new(),
+        }
+    }
+}
+
+impl From<Dom> for RCHandle<sb::SkSVGDOM> {
+    fn from(dom: Dom) -> Self {
+        dom.inner
+    }
+}
+
 /// This type represents an SVG as a node-based data structure.
 ///
 /// To convert an SVG to a `Dom`, a [`NativeResourceProvider`] is required.
@@ -50,18 +97,72 @@ impl fmt::Debug for Dom {
 /// ### Custom Resource Providers
 ///
 /// If you need more customization, you can implement the trait [`crate::resources::ResourceProvider`].
+///
+/// ### Migrating from `RCHandle<SkSVGDOM>`
+///
+/// `Dom` used to be an alias of `RCHandle<sb::SkSVGDOM>`. It is now a struct, because it also
+/// keeps the animations and the ids of the document, which Skia's DOM does not store. The handle
+/// is available through [`AsRef`] and can be converted from and into a `Dom` with [`From`]. A
+/// `Dom` that is converted from a handle has no animations and no ids.
+///
+/// `Dom` does not implement [`Clone`]: a clone would share the nodes with the original, and
+/// seeking or editing one would change the other.
+///
+/// ### Images
+///
+/// The `href` of `<image>` elements is loaded through the resource provider. Raster images are
+/// decoded by Skia. SVG documents are loaded when the document is loaded and rendered as vector
+/// graphics into the viewport of the `<image>` element, which stays in the tree with its `href`.
+/// Inside of patterns, they are rasterized at their own size. Like in browsers, they are rendered
+/// as separate documents: they do not inherit styles and can not reference the ids of the
+/// document, and their animations are not evaluated.
+///
+/// To restrict which resources can be loaded, for example to render untrusted documents without
+/// fetching anything from the network, wrap the resource provider in a
+/// [`crate::resources::RestrictedResourceProvider`].
+///
+/// ### Stylesheets
+///
+/// The CSS rules of `<style>` elements are applied to the elements they select while the
+/// document is loaded. An additional [`Stylesheet`], for example to theme icons, can be passed to
+/// [`Dom::read_with_stylesheet()`] and [`Dom::from_bytes_with_stylesheet()`]. Its rules take
+/// precedence over the rules of the document that have the same specificity.
+///
+/// ### Animations
+///
+/// SMIL animations defined by `<animate>`, `<set>` and `<animateTransform>` elements are
+/// evaluated with [`Dom::seek()`] and [`Dom::seek_time()`] before the document is rendered.
 impl Dom {
     pub fn read<R: io::Read>(
-        mut reader: R,
+        reader: R,
         resource_provider: impl Into<NativeResourceProvider>,
     ) -> Result<Self, LoadError> {
-        let mut reader = RustStream::new(&mut reader);
-        let stream = reader.stream_mut();
-        let resource_provider = resource_provider.into();
+        let (dom, _) = Self::read_document(reader, None, resource_provider.into())?;
+        Ok(dom)
+    }
 
-        let out = unsafe { sb::C_SkSVGDOM_MakeFromStream(stream, resource_provider.into_ptr()) };
+    /// Reads and parses an SVG document and returns the [`Warning`]s about elements and
+    /// attributes that were skipped.
+    ///
+    /// If the document can not be loaded, the returned [`ParseError`] describes the reason and
+    /// the position of the problem.
+    pub fn read_with_warnings<R: io::Read>(
+        reader: R,
+        resource_provider: impl Into<NativeResourceProvider>,
+    ) -> Result<(Self, Vec<Warning>), ParseError> {
+        let (dom, document) = Self::read_document(reader, None, resource_provider.into())?;
+        Ok((dom, diagnostics::warnings(&document)))
+    }
 
-        Self::from_ptr(out).ok_or(LoadError)
+    /// Reads and parses an SVG document and applies the rules of `stylesheet` in addition to the
+    /// stylesheets of the document.
+    pub fn read_with_stylesheet<R: io::Read>(
+        reader: R,
+        stylesheet: &Stylesheet,
+        resource_provider: impl Into<NativeResourceProvider>,
+    ) -> Result<Self, LoadError> {
+        let (dom, _) = Self::read_document(reader, Some(stylesheet), resource_provider.into())?;
+        Ok(dom)
     }
 
     pub fn from_str(
@@ -75,16 +176,129 @@ impl Dom {
         svg: &[u8],
         resource_provider: impl Into<NativeResourceProvider>,
     ) -> Result<Self, LoadError> {
+        let (dom, _) = Self::load_bytes(svg, None, resource_provider.into())?;
+        Ok(dom)
+    }
+
+    /// Parses an SVG document and returns the [`Warning`]s about elements and attributes that
+    /// were skipped.
+    ///
+    /// If the document can not be loaded, the returned [`ParseError`] describes the reason and
+    /// the position of the problem.
+    pub fn from_bytes_with_warnings(
+        svg: &[u8],
+        resource_provider: impl Into<NativeResourceProvider>,
+    ) -> Result<(Self, Vec<Warning>), ParseError> {
+        let (dom, document) = Self::load_bytes(svg, None, resource_provider.into())?;
+        Ok((dom, diagnostics::warnings(&document)))
+    }
+
+    /// Parses an SVG document and applies the rules of `stylesheet` in addition to the
+    /// stylesheets of the document.
+    pub fn from_bytes_with_stylesheet(
+        svg: &[u8],
+        stylesheet: &Stylesheet,
+        resource_provider: impl Into<NativeResourceProvider>,
+    ) -> Result<Self, LoadError> {
+        let (dom, _) = Self::load_bytes(svg, Some(stylesheet), resource_provider.into())?;
+        Ok(dom)
+    }
+
+    /// Loads an SVG document that is referenced by an `<image>` element of a document at
+    /// `depth - 1`.
+    pub(crate) fn load_nested(
+        svg: &[u8],
+        font_provider: &NativeResourceProvider,
+        resource_provider: &CachingResourceProvider,
+        depth: usize,
+    ) -> Result<Self, ParseError> {
         let mut ms = MemoryStream::from_bytes(svg);
-        let resource_provider = resource_provider.into();
+        let (dom, _) = Self::load_stream(
+            ms.native_mut().as_stream_mut(),
+            None,
+            font_provider,
+            resource_provider,
+            depth,
+        )?;
+        Ok(dom)
+    }
 
-        let out = unsafe {
-            sb::C_SkSVGDOM_MakeFromStream(
-                ms.native_mut().as_stream_mut(),
-                resource_provider.into_ptr(),
-            )
+    fn read_document<R: io::Read>(
+        reader: R,
+        stylesheet: Option<&Stylesheet>,
+        resource_provider: NativeResourceProvider,
+    ) -> Result<(Self, Document), ParseError> {
+        let mut reader = CheckedReader {
+            reader,
+            error: None,
         };
-        Self::from_ptr(out).ok_or(LoadError)
+        let mut stream = RustStream::new(&mut reader);
+        let loaded = Self::load_stream(
+            stream.stream_mut(),
+            stylesheet,
+            &resource_provider,
+            &CachingResourceProvider::new(&resource_provider),
+            0,
+        );
+        drop(stream);
+
+        match reader.error {
+            Some(error) => Err(ParseError::new(ParseErrorKind::Io(error), None)),
+            None => loaded,
+        }
+    }
+
+    fn load_bytes(
+        svg: &[u8],
+        stylesheet: Option<&Stylesheet>,
+        resource_provider: NativeResourceProvider,
+    ) -> Result<(Self, Document), ParseError> {
+        let mut ms = MemoryStream::from_bytes(svg);
+        Self::load_stream(
+            ms.native_mut().as_stream_mut(),
+            stylesheet,
+            &resource_provider,
+            &CachingResourceProvider::new(&resource_provider),
+            0,
+        )
+    }
+
+    /// Loads a document and applies what Skia's parser leaves out: the stylesheets, the
+    /// animations, and the SVG documents referenced by `<image>` elements.
+    ///
+    /// Skia loads the images through `resource_provider`, which caches them, except for the
+    /// images that stand in for SVG documents. The font manager and the SVG documents are loaded
+    /// through `font_provider`.
+    fn load_stream(
+        stream: &mut SkStream,
+        stylesheet: Option<&Stylesheet>,
+        font_provider: &NativeResourceProvider,
+        resource_provider: &CachingResourceProvider,
+        depth: usize,
+    ) -> Result<(Self, Document), ParseError> {
+        let mut resources = image::DocumentResourceProvider::new(resource_provider);
+        let (dom, document) = xml::load(
+            stream,
+            &font_provider.font_mgr(),
+            resources.resource_provider(),
+        );
+        let mut dom = dom?;
+
+        let mut nodes = document.nodes(dom.root().into_node());
+        text::rebuild_text_elements(&document, &mut nodes);
+        dom.ids = collect_ids(&document, &nodes);
+        dom.mapped_ids = dom.collect_mapped_ids(&document);
+        let styles = css::apply(&document, &nodes, stylesheet);
+        dom.animations = animation::prepare(&document, &nodes, &styles);
+        dom.seek_time(0.0);
+        dom.image_documents = image::load_svg_images(
+            &nodes,
+            &mut resources,
+            font_provider,
+            resource_provider,
+            depth,
+        );
+        Ok((dom, document))
     }
 
     pub fn root(&self) -> Svg {
@@ -94,23 +308,368 @@ impl Dom {
         }
     }
 
+    /// Returns the node of the tree that has the id `id`. If several nodes have this id, the
+    /// first one in document order is returned.
+    pub fn find_node_by_id(&self, id: impl AsRef<str>) -> Option<Node> {
+        let id = CString::new(id.as_ref()).ok()?;
+        self.node_by_id(&id)
+    }
+
+    fn node_by_id(&self, id: &CStr) -> Option<Node> {
+        self.tree_ids()
+            .into_iter()
+            .find(|(node_id, _)| *node_id == id)
+            .map(|(_, node)| node)
+    }
+
+    /// Returns all ids of the nodes of the tree and their nodes, sorted by id.
+    pub fn node_ids(&self) -> Vec<(String, Node)> {
+        self.sorted_tree_ids()
+            .into_iter()
+            .map(|(id, node)| (id.to_string_lossy().into_owned(), node))
+            .collect()
+    }
+
+    /// Sets the id of `node`, or removes it if `id` is `None`. The node can be looked up by its id
+    /// as soon as it is part of the tree.
+    ///
+    /// Skia resolves `<use>` elements, references like `url(#id)` and [`Self::render_node()`]
+    /// only with the ids of the parsed document. These ids refer to the nodes that have them now,
+    /// but nodes with other ids can not be referenced.
+    ///
+    /// Returns `false` if `id` contains a NUL character.
+    pub fn set_node_id(&mut self, node: &Node, id: Option<&str>) -> bool {
+        let id = match id.map(CString::new).transpose() {
+            Ok(id) => id,
+            Err(_) => return false,
+        };
+        self.ids.retain(|(id_node, _)| !id_node.ptr_eq(node));
+        if let Some(id) = id {
+            self.ids.push((node.clone(), id));
+        }
+        true
+    }
+
+    /// Returns the ids of the elements of `document` that Skia registered in its id mapper.
+    fn collect_mapped_ids(&self, document: &Document) -> Vec<CString> {
+        let mut ids: Vec<CString> = document
+            .elements
+            .iter()
+            .filter_map(|element| CString::new(element.attribute("id")?).ok())
+            .filter(|id| {
+                Node::from_ptr(unsafe {
+                    sb::C_SkSVGDOM_findNodeById(self.inner.native_mut_force(), id.as_ptr())
+                })
+                .is_some()
+            })
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// Returns the ids of the nodes that are currently part of the tree, in document order.
+    fn tree_ids(&self) -> Vec<(&CStr, Node)> {
+        let ids: HashMap<*const sb::SkSVGNode, &CStr> = self
+            .ids
+            .iter()
+            .map(|(node, id)| (node.native() as *const _, id.as_c_str()))
+            .collect();
+
+        let mut tree_ids = Vec::new();
+        let mut stack = vec![self.root().into_node()];
+        while let Some(node) = stack.pop() {
+            if let Some(id) = ids.get(&(node.native() as *const _)) {
+                tree_ids.push((*id, node.clone()));
+            }
+            stack.extend(node.child_nodes().into_iter().rev());
+        }
+        tree_ids
+    }
+
+    /// Returns the ids of the nodes of the tree sorted by id. Ids that are used more than once
+    /// refer to the first node in document order.
+    fn sorted_tree_ids(&self) -> Vec<(&CStr, Node)> {
+        let mut ids = self.tree_ids();
+        ids.sort_by(|(a, _), (b, _)| a.cmp(b));
+        ids.dedup_by(|(a, _), (b, _)| a == b);
+        ids
+    }
+
+    /// Makes the ids of Skia's id mapper refer to the nodes of the tree that have them now, or to
+    /// no node.
+    fn update_id_mapper(&self) {
+        let tree_ids = self.sorted_tree_ids();
+        for id in &self.mapped_ids {
+            let node = tree_ids
+                .binary_search_by(|(probe, _)| (*probe).cmp(id.as_c_str()))
+                .map_or(ptr::null_mut(), |index| unsafe {
+                    tree_ids[index].1.native_mut_force()
+                });
+            unsafe { sb::C_SkSVGDOM_setNodeById(self.inner.native_mut_force(), id.as_ptr(), node) }
+        }
+    }
+
+    /// Writes the document as SVG.
+    ///
+    /// The element structure, ids, and the attributes that are stored in the nodes are
+    /// preserved, including references to gradients, patterns, filters and `<use>` targets.
+    /// Attributes that Skia does not support are not written.
+    ///
+    /// The nodes do not keep `<style>` elements, `class` attributes and animation elements, so
+    /// they are not written either. The declarations of stylesheets are written as the attributes
+    /// of the elements they were applied to, and animated attributes with their values at the
+    /// current time of the animations.
+    pub fn write(&self, writer: impl io::Write) -> io::Result<()> {
+        writer::write(self, writer)
+    }
+
     pub fn render(&self, canvas: &Canvas) {
         // TODO: may be we should init ICU whenever we expose a Canvas?
         #[cfg(all(feature = "embed-icudtl", feature = "textlayout"))]
         crate::icu::init();
 
-        unsafe { sb::SkSVGDOM::render(self.native() as &_, canvas.native_mut()) }
+        self.update_id_mapper();
+        let (image_ids, pictures) = self.image_documents();
+        unsafe {
+            sb::C_SkSVGDOM_render(
+                self.native(),
+                canvas.native_mut(),
+                image_ids.as_ptr(),
+                pictures.as_ptr(),
+                image_ids.len(),
+            )
+        }
+    }
+
+    /// Renders only the node with the id `id` and its descendants, positioned as they are in the
+    /// document.
+    ///
+    /// Returns `false` if there is no node with the id `id` in the tree, or if `id` is not one of
+    /// the ids of the parsed document, see [`Self::set_node_id()`].
+    pub fn render_node(&self, canvas: &Canvas, id: impl AsRef<str>) -> bool {
+        let Ok(id) = CString::new(id.as_ref()) else {
+            return false;
+        };
+        if self.mapped_ids.binary_search(&id).is_err() || self.node_by_id(&id).is_none() {
+            return false;
+        }
+
+        #[cfg(all(feature = "embed-icudtl", feature = "textlayout"))]
+        crate::icu::init();
+
+        self.update_id_mapper();
+        let (image_ids, pictures) = self.image_documents();
+        unsafe {
+            sb::C_SkSVGDOM_renderNode(
+                self.native(),
+                canvas.native_mut(),
+                id.as_ptr(),
+                image_ids.as_ptr(),
+                pictures.as_ptr(),
+                image_ids.len(),
+            )
+        }
+        true
+    }
+
+    /// Returns the unique ids of the images that stand in for the SVG documents referenced by
+    /// `<image>` elements, and the pictures of these documents.
+    fn image_documents(&self) -> (Vec<u32>, Vec<*mut sb::SkPicture>) {
+        self.image_documents
+            .iter()
+            .map(|(id, picture)| (*id, picture.native() as *const _ as *mut _))
+            .unzip()
+    }
+
+    /// Returns the outline of `node` in document coordinates.
+    ///
+    /// Shapes are converted to paths and images to their viewport rectangles. For containers like
+    /// `<g>` or `<use>`, the outlines of all rendered descendants are combined. Strokes are not
+    /// included, and only the clip paths of the graphics elements themselves are applied.
+    ///
+    /// Text is not outlined, because Skia does not convert text elements to paths yet. Text
+    /// elements contribute an empty outline.
+    ///
+    /// Returns `None` if `node` is not rendered as part of this document.
+    pub fn node_path(&self, node: &Node) -> Option<Path> {
+        self.node_geometry(node).map(|(path, _)| path)
+    }
+
+    /// Returns the bounds of the area `node` paints to in document coordinates, including
+    /// strokes.
+    ///
+    /// Like with [`Self::node_path()`], text elements do not contribute to the bounds.
+    ///
+    /// Returns `None` if `node` is not rendered as part of this document.
+    pub fn node_bounds(&self, node: &Node) -> Option<Rect> {
+        self.node_geometry(node).map(|(_, bounds)| bounds)
+    }
+
+    fn node_geometry(&self, node: &Node) -> Option<(Path, Rect)> {
+        self.update_id_mapper();
+        let mut bounds = Rect::default();
+        let path = Path::try_construct(|path| unsafe {
+            sb::C_SkSVGDOM_nodeGeometry(self.native(), node.native(), path, bounds.native_mut())
+        })?;
+        Some((path, bounds))
+    }
+
+    /// Returns the topmost graphics element whose fill or stroke contains `point`, which is
+    /// specified in document coordinates.
+    ///
+    /// Text elements are never hit, see [`Self::node_path()`].
+    pub fn node_at(&self, point: impl Into<Point>) -> Option<Node> {
+        self.hit(point).map(|(node, _)| node)
+    }
+
+    /// Returns the id of the topmost graphics element that contains `point`, or if that element
+    /// has no id, the id of its nearest ancestor that has one.
+    ///
+    /// Returns `None` if no element was hit, or neither the element nor one of its ancestors has
+    /// an id.
+    pub fn hit_test(&self, point: impl Into<Point>) -> Option<String> {
+        self.hit(point).and_then(|(_, id)| id)
+    }
+
+    fn hit(&self, point: impl Into<Point>) -> Option<(Node, Option<String>)> {
+        self.update_id_mapper();
+        let tree_ids = self.sorted_tree_ids();
+        let ids: Vec<*const c_char> = tree_ids.iter().map(|(id, _)| id.as_ptr()).collect();
+        let nodes: Vec<*mut sb::SkSVGNode> = tree_ids
+            .iter()
+            .map(|(_, node)| unsafe { node.native_mut_force() })
+            .collect();
+        let mut id = interop::String::default();
+        let node = Node::from_ptr(unsafe {
+            sb::C_SkSVGDOM_nodeAt(
+                self.native(),
+                ids.as_ptr(),
+                nodes.as_ptr(),
+                ids.len(),
+                point.into().into_native(),
+                id.native_mut(),
+            )
+        })?;
+        let id = (!id.as_str().is_empty()).then(|| id.as_str().into());
+        Some((node, id))
+    }
+
+    /// Compiles the filter effects of `filter` into an [`ImageFilter`] that can be applied to
+    /// any drawing, for example with [`crate::Paint::set_image_filter()`].
+    ///
+    /// `object_bounds` is the bounding box of the filtered content, in the coordinates the
+    /// image filter is applied in. It resolves the filter region and the primitive subregions
+    /// that use `objectBoundingBox` units. Lengths in `userSpaceOnUse` units resolve against the
+    /// container size of this document, and `<feImage>` references are looked up in this
+    /// document.
+    ///
+    /// Returns `None` if the filter has no effect, or if the document has no root.
+    pub fn filter_image_filter(
+        &self,
+        filter: &Filter,
+        object_bounds: impl AsRef<Rect>,
+    ) -> Option<ImageFilter> {
+        self.update_id_mapper();
+        ImageFilter::from_ptr(unsafe {
+            sb::C_SkSVGDOM_filterImageFilter(
+                self.native(),
+                filter.native(),
+                object_bounds.as_ref().native(),
+            )
+        })
+    }
+
+    /// Returns the duration of the animations of the document in seconds, or `0` if the
+    /// document is not animated.
+    ///
+    /// Animations that repeat indefinitely are accounted for with their first iteration.
+    pub fn duration(&self) -> f32 {
+        self.animations
+            .as_ref()
+            .map_or(0.0, |animations| animations.duration() as f32)
+    }
+
+    /// Seeks to a normalized position in the animations of the document.
+    ///
+    /// `t` is in the range `[0, 1]`, where 1 is [`Self::duration()`].
+    pub fn seek(&mut self, t: f32) {
+        let duration = self.duration();
+        self.seek_time(f64::from(t * duration));
+    }
+
+    /// Seeks to a specific time in seconds.
+    ///
+    /// The attributes that are animated by `<animate>`, `<set>` and `<animateTransform>` elements
+    /// are set to their values at `time`, and keep them until the next seek. Times beyond
+    /// [`Self::duration()`] continue animations that repeat indefinitely. After loading, the
+    /// document is at time 0.
+    pub fn seek_time(&mut self, time: f64) {
+        if let Some(animations) = &mut self.animations {
+            animations.seek(time);
+        }
     }
 
     pub fn set_container_size(&mut self, size: impl Into<Size>) {
         let size = size.into();
         unsafe { sb::C_SkSVGDOM_setContainerSize(self.native_mut(), size.native()) }
     }
+
+    fn native(&self) -> &sb::SkSVGDOM {
+        self.inner.native()
+    }
+
+    pub(crate) fn native_mut(&mut self) -> &mut sb::SkSVGDOM {
+        self.inner.native_mut()
+    }
+
+    pub(crate) fn from_ptr(dom: *mut sb::SkSVGDOM) -> Option<Self> {
+        RCHandle::from_ptr(dom).map(Self::from)
+    }
+}
+
+/// Returns the ids of the elements of `document` and the nodes of the elements, indexed like
+/// the elements.
+fn collect_ids(document: &Document, nodes: &[Option<Node>]) -> Vec<(Node, CString)> {
+    document
+        .elements
+        .iter()
+        .zip(nodes)
+        .filter_map(|(element, node)| {
+            let id = CString::new(element.attribute("id")?).ok()?;
+            Some((node.clone()?, id))
+        })
+        .collect()
+}
+
+/// Forwards the reads to `reader` and keeps the first error, which [`RustStream`] would report
+/// as the end of the stream.
+struct CheckedReader<R> {
+    reader: R,
+    error: Option<io::Error>,
+}
+
+impl<R: io::Read> io::Read for CheckedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.error.is_some() {
+            return Ok(0);
+        }
+        loop {
+            match self.reader.read(buf) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.error = Some(e);
+                    return Ok(0);
+                }
+                result => return result,
+            }
+        }
+    }
 }
 
-/// Error when something goes wrong when loading an SVG file. Sadly, Skia doesn't give further
-/// details so we can't return a more expressive error type, but we still use this instead of
-/// `Option` to express the intent and allow for `Try`.
+/// Error when something goes wrong when loading an SVG file. It does not give further details
+/// and is kept for compatibility. The functions that return [`Warning`]s report a
+/// [`ParseError`] instead, which describes the reason.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LoadError;
 
@@ -131,3 +690,86 @@ impl From<LoadError> for io::Error {
         io::Error::other(other)
     }
 }
+
+/// Describes why an SVG document could not be loaded, and where.
+#[derive(Debug)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    position: Option<TextPosition>,
+}
+
+#[derive(Debug)]
+pub enum ParseErrorKind {
+    /// Reading the document failed.
+    Io(io::Error),
+    /// The document is not well-formed XML.
+    Xml(XmlError),
+    /// The document declares an entity. Skia rejects these documents to prevent entity
+    /// expansion attacks.
+    EntityDeclaration,
+    /// The root element is not `<svg>`.
+    RootNotSvg { name: String },
+    /// Skia rejected the document for a reason that could not be determined.
+    Unknown,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, position: Option<TextPosition>) -> Self {
+        Self { kind, position }
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    /// The position in the document the error refers to, if known.
+    pub fn position(&self) -> Option<TextPosition> {
+        self.position
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Failed to load svg: ")?;
+        match &self.kind {
+            ParseErrorKind::Io(error) => write!(f, "{error}")?,
+            ParseErrorKind::Xml(error) => write!(f, "{error}")?,
+            ParseErrorKind::EntityDeclaration => {
+                write!(f, "entity declarations are not supported")?
+            }
+            ParseErrorKind::RootNotSvg { name } => {
+                write!(f, "root element is <{name}>, expected <svg>")?
+            }
+            ParseErrorKind::Unknown => write!(f, "reason unknown")?,
+        }
+        if let Some(position) = &self.position {
+            write!(f, " at {position}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ParseErrorKind::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for LoadError {
+    fn from(_: ParseError) -> Self {
+        LoadError
+    }
+}
+
+impl From<ParseError> for io::Error {
+    fn from(other: ParseError) -> Self {
+        match other.kind {
+            // Errors of the reader are passed on unchanged, they have no position.
+            ParseErrorKind::Io(error) => error,
+            _ => io::Error::new(io::ErrorKind::InvalidData, other),
+        }
+    }
+}
@@ -49,7 +49,7 @@ impl DebugAttributes for Fe {
 
 impl Fe {
     // TODO: Wrap IsFilterEffect (via typed)
-    // TODO: Wrap makeImageFilter()
+    // TODO: Wrap makeImageFilter(). Dom::filter_image_filter() builds the filter as a whole.
     // TODO: Wrap resolveFilterSubregion()
     // TODO: Wrap resolveColorSpace()
     // TODO: Wrap applyProperties()
@@ -28,7 +28,7 @@ impl_default_make!(Filter, sb::C_SkSVGFilter_Make);
 
 impl Filter {
     // TODO: wrap applyProperties()
-    // TODO: wrap buildFilterDAG
+    // TODO: wrap buildFilterDAG() without a Dom. See Dom::filter_image_filter()
 
     skia_svg_macros::attrs! {
         SkSVGFilter => {
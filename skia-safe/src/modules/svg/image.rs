@@ -1,11 +1,19 @@
-use super::{DebugAttributes, Iri, Length, NodeSubtype, PreserveAspectRatio};
-use crate::{impl_default_make, prelude::*};
+use std::ffi::CString;
+
+use super::{
+    DebugAttributes, Dom, Iri, IriKind, Length, Node, NodeSubtype, PreserveAspectRatio, TypedNode,
+};
+use crate::{
+    ColorSpace, Picture, PictureRecorder, Rect, Size, images, impl_default_make,
+    prelude::*,
+    resources::{CachingResourceProvider, NativeResourceProvider},
+};
 use skia_bindings as sb;
 
 pub type Image = RCHandle<sb::SkSVGImage>;
 
 impl NodeSubtype for sb::SkSVGImage {
-    type Base = sb::SkSVGContainer;
+    type Base = sb::SkSVGTransformableNode;
 }
 
 impl_default_make!(Image, sb::C_SkSVGImage_Make);
@@ -27,7 +35,8 @@ impl DebugAttributes for Image {
 }
 
 impl Image {
-    // TODO: wrap LoadImage
+    // TODO: wrap LoadImage. Images that are SVG documents are recorded when the Dom is loaded,
+    // see `load_svg_images()`.
 
     skia_svg_macros::attrs! {
         SkSVGImage => {
@@ -40,3 +49,178 @@ impl Image {
         }
     }
 }
+
+/// Limits the nesting of SVG documents that are referenced by `<image>` elements, which also
+/// stops documents that reference themselves.
+const MAX_NESTED_DOCUMENTS: usize = 8;
+
+/// The size of SVG documents that have neither a size nor a `viewBox`. This is the default size
+/// of replaced elements in CSS.
+const DEFAULT_DOCUMENT_SIZE: Size = Size::new(300.0, 150.0);
+
+/// The resource provider a document is loaded with. It serves the images that stand in for the
+/// SVG documents referenced by `<image>` elements and forwards everything else to a
+/// [`CachingResourceProvider`].
+pub(crate) struct DocumentResourceProvider(CachingResourceProvider);
+
+impl DocumentResourceProvider {
+    pub fn new(resource_provider: &CachingResourceProvider) -> Self {
+        Self(
+            CachingResourceProvider::from_ptr(unsafe {
+                sb::C_SvgImageResourceProvider_Make(resource_provider.clone().into_ptr())
+            })
+            .unwrap(),
+        )
+    }
+
+    pub fn resource_provider(&self) -> &CachingResourceProvider {
+        &self.0
+    }
+
+    fn add_image(&mut self, path: &str, name: &str, image: &crate::Image) -> Option<()> {
+        let path = CString::new(path).ok()?;
+        let name = CString::new(name).ok()?;
+        unsafe {
+            sb::C_SvgImageResourceProvider_addImage(
+                self.0.native_mut(),
+                path.as_ptr(),
+                name.as_ptr(),
+                image.clone().into_ptr(),
+            )
+        }
+        Some(())
+    }
+}
+
+/// Loads the SVG documents that are referenced by `<image>` elements and records them into
+/// pictures, so that they are rendered as vector graphics and not rasterized.
+///
+/// The `<image>` nodes stay in the tree. Skia lays them out with images of the size of the
+/// documents, which `resources` serves, and the returned pictures are drawn in place of these
+/// images, see [`Dom::render()`]. The pictures are returned with the unique ids of the images.
+///
+/// The `href` is looked up like Skia does when it renders the image, through the same caching
+/// `resource_provider`, so raster images are loaded once and left to Skia. Only resources that
+/// can not be decoded as images are loaded again and checked for an SVG document.
+pub(crate) fn load_svg_images(
+    nodes: &[Option<Node>],
+    resources: &mut DocumentResourceProvider,
+    font_provider: &NativeResourceProvider,
+    resource_provider: &CachingResourceProvider,
+    depth: usize,
+) -> Vec<(u32, Picture)> {
+    let mut pictures = Vec::new();
+    if depth >= MAX_NESTED_DOCUMENTS {
+        return pictures;
+    }
+
+    for node in nodes.iter().flatten() {
+        let TypedNode::Image(image) = node.clone().typed() else {
+            continue;
+        };
+        let href = image.href();
+        let Some((path, name, id)) = resource_location(&href) else {
+            continue;
+        };
+        // Raster images, and documents that are already loaded for another `<image>` element.
+        if resources
+            .resource_provider()
+            .load_image_asset(&path, &name, &id)
+            .is_some()
+        {
+            continue;
+        }
+        let Some(data) = font_provider.load(&path, &name) else {
+            continue;
+        };
+        if !is_svg(&data) {
+            continue;
+        }
+        let Ok(mut nested) = Dom::load_nested(&data, font_provider, resource_provider, depth + 1)
+        else {
+            continue;
+        };
+        let Some((image, picture)) = record(&mut nested) else {
+            continue;
+        };
+        if resources.add_image(&path, &name, &image).is_some() {
+            pictures.push((image.unique_id(), picture));
+        }
+    }
+    pictures
+}
+
+/// Records `dom` at the size of the document into a picture, and creates the image that stands
+/// in for the picture.
+///
+/// Like browsers do for SVG images, the document is rendered on its own, with its ids and its
+/// container size, and does not inherit anything from the referencing document.
+fn record(dom: &mut Dom) -> Option<(crate::Image, Picture)> {
+    let root = dom.root();
+    let mut size = root.intrinsic_size();
+    if size.is_empty() {
+        size = root
+            .view_box()
+            .map(|view_box| view_box.size())
+            .unwrap_or_default();
+    }
+    if size.is_empty() {
+        size = DEFAULT_DOCUMENT_SIZE;
+    }
+    dom.set_container_size(size);
+
+    // Images have integral dimensions, the document is scaled to fill them.
+    let dimensions = size.to_ceil();
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(Rect::from_isize(dimensions), false);
+    canvas.scale((
+        dimensions.width as f32 / size.width,
+        dimensions.height as f32 / size.height,
+    ));
+    dom.render(canvas);
+    let picture = recorder.finish_recording_as_picture(None)?;
+
+    let image = images::deferred_from_picture(
+        picture.clone(),
+        dimensions,
+        None,
+        None,
+        images::BitDepth::U8,
+        ColorSpace::new_srgb(),
+        None,
+    )?;
+    Some((image, picture))
+}
+
+/// Returns the resource path, name and id Skia passes to the resource provider to load the
+/// image at `href`.
+fn resource_location(href: &Iri) -> Option<(String, String, String)> {
+    let iri = href.data();
+    match href.native().fType {
+        IriKind::DataURI => Some((String::new(), iri.into(), String::new())),
+        IriKind::Nonlocal => {
+            let (dirname, basename) = split_path(iri);
+            Some((dirname.into(), basename.into(), basename.into()))
+        }
+        _ => None,
+    }
+}
+
+/// Splits `path` into the directory and the file name, like `SkOSPath::Dirname()` and
+/// `SkOSPath::Basename()` do.
+fn split_path(path: &str) -> (&str, &str) {
+    const SEPARATOR: char = if cfg!(windows) { '\\' } else { '/' };
+
+    match path.rfind(SEPARATOR) {
+        // The root directory keeps its separator.
+        Some(0) => (&path[..1], &path[1..]),
+        Some(i) => (&path[..i], &path[i + 1..]),
+        None => ("", path),
+    }
+}
+
+/// Sniffs for an XML document, raster image formats never start with a `<`.
+fn is_svg(data: &[u8]) -> bool {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    data.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'<')
+}
@@ -1,11 +1,13 @@
 use super::{
-    Circle, ClipPath, ColorSpace, DebugAttributes, Defs, Display, Ellipse, Fill, FillRule, Filter,
-    FontFamily, FontSize, FontStyle, FontWeight, G, Image, IriFunc, Length, Line, LineCap,
-    LineJoin, LinearGradient, Mask, Paint, Path, Poly, RadialGradient, Rect, Stop, TSpan, Text,
-    TextAnchor, TextLiteral, TextPath, Use, Visibility, fe, pattern::Pattern, svg_::Svg,
+    Circle, ClipPath, ColorSpace, Container, DebugAttributes, Defs, Display, Ellipse, Fill,
+    FillRule, Filter, FontFamily, FontSize, FontStyle, FontWeight, G, Image, IriFunc, Length, Line,
+    LineCap, LineJoin, LinearGradient, Mask, Paint, Path, Poly, RadialGradient, Rect, Stop, TSpan,
+    Text, TextAnchor, TextLiteral, TextPath, Use, Visibility, fe, pattern::Pattern, svg_::Svg,
+    text::TextContainer,
 };
 use crate::{Color, prelude::*, scalar};
 use skia_bindings as sb;
+use std::ffi::CString;
 
 pub type NodeTag = sb::SkSVGTag;
 
@@ -66,10 +68,69 @@ impl Node {
         unsafe { sb::C_SkSVGNode_tag(self.native()) }
     }
 
-    // TODO: wrap appendChild()
-    // TODO: wrap render(), asPaint(), asPath(), objectBoundingBox()
+    /// Creates a node for the SVG element named `name`, for example `"rect"` or
+    /// `"linearGradient"`.
+    ///
+    /// Returns `None` if the element is not supported.
+    pub fn from_element_name(name: impl AsRef<str>) -> Option<Node> {
+        let name = CString::new(name.as_ref()).ok()?;
+        Node::from_ptr(unsafe { sb::C_SkSVGNode_MakeFromElementName(name.as_ptr()) })
+    }
+
+    // TODO: wrap render(), asPaint(). See Dom::node_path() and Dom::node_bounds() for asPath()
+    // and objectBoundingBox().
     // TODO: wrap setAttribute().
-    // TODO: wrap parseAndSetAttribute()
+
+    /// Parses `value` and sets it to the attribute or presentation attribute `name`, as if it
+    /// were specified in an SVG document.
+    ///
+    /// Returns `false` if this node does not support the attribute or the value could not be
+    /// parsed.
+    pub fn parse_and_set_attribute(
+        &mut self,
+        name: impl AsRef<str>,
+        value: impl AsRef<str>,
+    ) -> bool {
+        let (Ok(name), Ok(value)) = (CString::new(name.as_ref()), CString::new(value.as_ref()))
+        else {
+            return false;
+        };
+        unsafe {
+            sb::C_SkSVGNode_parseAndSetAttribute(self.native_mut(), name.as_ptr(), value.as_ptr())
+        }
+    }
+
+    /// Returns this node as a [`Container`] if it derives from `SkSVGContainer`.
+    pub(crate) fn as_container_mut(&mut self) -> Option<&mut Container> {
+        is_container(self.tag()).then(|| unsafe { transmute_ref_mut::<Node, Container>(self) })
+    }
+
+    pub(crate) fn as_text_container_mut(&mut self) -> Option<&mut TextContainer> {
+        matches!(
+            self.tag(),
+            NodeTag::Text | NodeTag::TSpan | NodeTag::TextPath
+        )
+        .then(|| unsafe { transmute_ref_mut::<Node, TextContainer>(self) })
+    }
+
+    /// Returns `true` if `self` and `other` refer to the same node.
+    pub fn ptr_eq(&self, other: &Node) -> bool {
+        std::ptr::eq(self.native(), other.native())
+    }
+
+    /// Returns the children of containers and text elements. All other nodes have no children.
+    pub(crate) fn child_nodes(&self) -> Vec<Node> {
+        let tag = self.tag();
+        if is_container(tag) {
+            unsafe { transmute_ref::<Node, Container>(self) }
+                .children()
+                .to_vec()
+        } else if matches!(tag, NodeTag::Text | NodeTag::TSpan | NodeTag::TextPath) {
+            unsafe { transmute_ref::<Node, TextContainer>(self) }.children()
+        } else {
+            Vec::new()
+        }
+    }
 
     pub fn typed(self) -> TypedNode {
         TypedNode::from_ptr(self.into_ptr())
@@ -113,6 +174,45 @@ impl Node {
     }
 }
 
+/// Returns `true` for the nodes that derive from `SkSVGContainer`.
+fn is_container(tag: NodeTag) -> bool {
+    matches!(
+        tag,
+        NodeTag::ClipPath
+            | NodeTag::Defs
+            | NodeTag::FeBlend
+            | NodeTag::FeColorMatrix
+            | NodeTag::FeComponentTransfer
+            | NodeTag::FeComposite
+            | NodeTag::FeDiffuseLighting
+            | NodeTag::FeDisplacementMap
+            | NodeTag::FeDistantLight
+            | NodeTag::FeFlood
+            | NodeTag::FeFuncA
+            | NodeTag::FeFuncR
+            | NodeTag::FeFuncG
+            | NodeTag::FeFuncB
+            | NodeTag::FeGaussianBlur
+            | NodeTag::FeImage
+            | NodeTag::FeMerge
+            | NodeTag::FeMergeNode
+            | NodeTag::FeMorphology
+            | NodeTag::FeOffset
+            | NodeTag::FePointLight
+            | NodeTag::FeSpecularLighting
+            | NodeTag::FeSpotLight
+            | NodeTag::FeTurbulence
+            | NodeTag::Filter
+            | NodeTag::G
+            | NodeTag::LinearGradient
+            | NodeTag::Mask
+            | NodeTag::Pattern
+            | NodeTag::RadialGradient
+            | NodeTag::Stop
+            | NodeTag::Svg
+    )
+}
+
 #[derive(Debug, Clone)]
 pub enum TypedNode {
     Circle(Circle),
@@ -1,11 +1,26 @@
-use super::{DebugAttributes, Iri, Length, NodeSubtype, XmlSpace};
+use std::{cell::RefCell, collections::HashMap};
+
+use super::{
+    DebugAttributes, Iri, Length, Node, NodeSubtype, NodeTag, XmlSpace, css,
+    xml::{Content, Document},
+};
 use crate::{impl_default_make, interop, prelude::*, scalar};
 use skia_bindings as sb;
 
-type TextContainer = RCHandle<sb::SkSVGTextContainer>;
+pub(crate) type TextContainer = RCHandle<sb::SkSVGTextContainer>;
+
+thread_local! {
+    /// The children of the text containers, which Skia keeps private. They are recorded when they
+    /// are appended with [`TextContainer::append_child()`].
+    ///
+    /// An entry keeps its container alive, so that its address is not reused. Entries of
+    /// containers that are not referenced anywhere else are removed when children are appended.
+    static TEXT_CHILDREN: RefCell<HashMap<*const sb::SkSVGTextContainer, (TextContainer, Vec<Node>)>> =
+        RefCell::default();
+}
 
 impl NodeSubtype for sb::SkSVGTextContainer {
-    type Base = sb::SkSVGContainer;
+    type Base = sb::SkSVGTransformableNode;
 }
 
 impl DebugAttributes for TextContainer {
@@ -25,6 +40,41 @@ impl DebugAttributes for TextContainer {
 }
 
 impl TextContainer {
+    /// Appends a text literal, `<tspan>` or `<textPath>` node. Other nodes are ignored.
+    pub fn append_child(&mut self, node: impl Into<Node>) {
+        let node = node.into();
+        if is_text_content(node.tag()) {
+            TEXT_CHILDREN.with_borrow_mut(|text_children| {
+                // Dropping an entry may leave the entries of its children unreferenced.
+                let mut len = usize::MAX;
+                while text_children.len() < len {
+                    len = text_children.len();
+                    text_children.retain(|_, (container, _)| !container.native().unique());
+                }
+                text_children
+                    .entry(self.native() as *const _)
+                    .or_insert_with(|| (self.clone(), Vec::new()))
+                    .1
+                    .push(node.clone());
+            });
+        }
+        unsafe { sb::C_SkSVGTextContainer_appendChild(self.native_mut(), node.into_ptr()) }
+    }
+
+    /// Returns the text literal, `<tspan>` and `<textPath>` nodes of this element.
+    ///
+    /// Skia does not expose the children of text elements, only the children that were appended
+    /// with [`Self::append_child()`] on this thread are known. The text elements of loaded
+    /// documents are built this way.
+    pub fn children(&self) -> Vec<Node> {
+        TEXT_CHILDREN.with_borrow(|text_children| {
+            text_children
+                .get(&(self.native() as *const _))
+                .map(|(_, children)| children.clone())
+                .unwrap_or_default()
+        })
+    }
+
     pub fn x(&self) -> &[Length] {
         unsafe {
             safer::from_raw_parts(
@@ -163,3 +213,91 @@ impl TextPath {
         }
     }
 }
+
+/// Returns `true` if text elements keep a child node with the tag `tag`.
+fn is_text_content(tag: NodeTag) -> bool {
+    matches!(
+        tag,
+        NodeTag::TextLiteral | NodeTag::TSpan | NodeTag::TextPath
+    )
+}
+
+/// Replaces the `<text>` nodes Skia loaded with nodes that are built from the elements of
+/// `document` like Skia's parser builds them, so that their children are known, and updates
+/// `nodes`, which is indexed like the elements.
+pub(crate) fn rebuild_text_elements(document: &Document, nodes: &mut [Option<Node>]) {
+    for index in 0..document.elements.len() {
+        let Some(loaded) = nodes[index]
+            .clone()
+            .filter(|node| node.tag() == NodeTag::Text)
+        else {
+            continue;
+        };
+        let Some(mut parent) = document.elements[index]
+            .parent
+            .and_then(|parent| nodes[parent].clone())
+        else {
+            continue;
+        };
+        let Some(container) = parent.as_container_mut() else {
+            continue;
+        };
+        let Some(position) = container
+            .children()
+            .iter()
+            .position(|child| child.ptr_eq(&loaded))
+        else {
+            continue;
+        };
+        let Some(text) = build_node(document, index, nodes) else {
+            continue;
+        };
+        container.remove_child(position);
+        container.insert_child(position, text.clone());
+        nodes[index] = Some(text);
+    }
+}
+
+/// Builds the node of the element at `index` and the text content of text elements. The nodes
+/// of the text content are stored in `nodes`.
+fn build_node(document: &Document, index: usize, nodes: &mut [Option<Node>]) -> Option<Node> {
+    let element = &document.elements[index];
+    let mut node = Node::from_element_name(&element.name)?;
+    // Skia's parser registers ids in its id mapper and sets the declarations of `style`
+    // attributes one by one.
+    for (name, value) in &element.attributes {
+        match name.as_str() {
+            "id" => {}
+            "style" => {
+                for (name, value) in css::style_declarations(value) {
+                    node.parse_and_set_attribute(name, value);
+                }
+            }
+            _ => {
+                node.parse_and_set_attribute(name, value);
+            }
+        }
+    }
+
+    if let Some(container) = node.as_text_container_mut() {
+        for content in &element.content {
+            match content {
+                Content::Text(text) => {
+                    let mut literal = TextLiteral::default();
+                    literal.set_text(text);
+                    container.append_child(literal);
+                }
+                Content::Element(child) => {
+                    let Some(child_node) = build_node(document, *child, nodes)
+                        .filter(|child_node| is_text_content(child_node.tag()))
+                    else {
+                        continue;
+                    };
+                    container.append_child(child_node.clone());
+                    nodes[*child] = Some(child_node);
+                }
+            }
+        }
+    }
+    Some(node)
+}
@@ -0,0 +1,917 @@
+//! Serializes the node tree of a [`Dom`] back to SVG.
+//!
+//! Elements, ids and attributes are written as they are stored in the nodes, so that references
+//! like `url(#gradient)` or `xlink:href="#shape"` are preserved.
+
+use std::{collections::HashMap, fmt, io};
+
+use skia_bindings as sb;
+
+use super::{
+    BoundingBoxUnits, ColorKind, ColorSpace, Display, Dom, Fe, Fill, FillRule, FontStyle,
+    FontWeight, Iri, IriFunc, IriFuncKind, IriKind, Length, LengthUnit, LineCap, LineJoin, Node,
+    Paint, PreserveAspectRatio, SpreadMethod, TextAnchor, TransformableNode, TypedNode, Visibility,
+    XmlSpace,
+    fe::{
+        self, BlendMode, ChannelSelector, ColorMatrixKind, CompositeOperator, FuncKind, InputType,
+        TurbulenceType, morphology::Operator as MorphologyOperator,
+    },
+    preserve_aspect_ratio::{Align, Scale},
+    text::TextContainer,
+};
+use crate::{Color, Matrix, Point, prelude::*, scalar};
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+pub(crate) fn write(dom: &Dom, out: impl io::Write) -> io::Result<()> {
+    let ids = dom
+        .node_ids()
+        .into_iter()
+        .map(|(id, node)| (node.native() as *const _, id))
+        .collect();
+
+    let mut writer = Writer { out, ids, depth: 0 };
+    writer
+        .out
+        .write_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n")?;
+    writer.node(&dom.root().into_node(), true, false)?;
+    writer.out.flush()
+}
+
+struct Writer<W> {
+    out: W,
+    ids: HashMap<*const sb::SkSVGNode, String>,
+    depth: usize,
+}
+
+impl<W: io::Write> Writer<W> {
+    /// Writes `node` and its children. Inside of text elements, `inline` is set and no whitespace
+    /// is added, because it would become part of the text.
+    fn node(&mut self, node: &Node, root: bool, inline: bool) -> io::Result<()> {
+        let typed = node.clone().typed();
+        if let TypedNode::TextLiteral(literal) = &typed {
+            return self.out.write_all(escape(literal.text(), false).as_bytes());
+        }
+
+        let mut attributes = Attributes::default();
+        if root {
+            attributes.add("xmlns", SVG_NAMESPACE);
+            attributes.add("xmlns:xlink", XLINK_NAMESPACE);
+        }
+        if let Some(id) = self.ids.get(&(node.native() as *const _)) {
+            attributes.add("id", id);
+        }
+        let name = element(&typed, &mut attributes);
+        transform_attribute(node, &mut attributes);
+        presentation_attributes(node, &mut attributes);
+
+        if !inline {
+            self.indent()?;
+        }
+        write!(self.out, "<{name}")?;
+        for (name, value) in &attributes.0 {
+            write!(self.out, " {name}=\"{}\"", escape(value, true))?;
+        }
+
+        let children = node.child_nodes();
+        if children.is_empty() {
+            self.out.write_all(b"/>")?;
+        } else {
+            let inline_children = inline || matches!(typed, TypedNode::Text(_));
+            self.out.write_all(b">")?;
+            if !inline_children {
+                self.out.write_all(b"\n")?;
+            }
+            self.depth += 1;
+            for child in &children {
+                self.node(child, false, inline_children)?;
+            }
+            self.depth -= 1;
+            if !inline_children {
+                self.indent()?;
+            }
+            write!(self.out, "</{name}>")?;
+        }
+
+        if !inline {
+            self.out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn indent(&mut self) -> io::Result<()> {
+        for _ in 0..self.depth {
+            self.out.write_all(b"  ")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Attributes(Vec<(&'static str, String)>);
+
+impl Attributes {
+    fn add(&mut self, name: &'static str, value: impl Into<String>) {
+        self.0.push((name, value.into()));
+    }
+
+    fn number(&mut self, name: &'static str, value: scalar) {
+        self.add(name, value.to_string());
+    }
+
+    fn opt_number(&mut self, name: &'static str, value: Option<scalar>) {
+        if let Some(value) = value {
+            self.number(name, value);
+        }
+    }
+
+    fn numbers(&mut self, name: &'static str, values: &[scalar]) {
+        if !values.is_empty() {
+            self.add(name, join(values.iter()));
+        }
+    }
+
+    fn length(&mut self, name: &'static str, length: &Length) {
+        self.add(name, format_length(length));
+    }
+
+    fn opt_length(&mut self, name: &'static str, length: Option<&Length>) {
+        if let Some(length) = length {
+            self.length(name, length);
+        }
+    }
+
+    fn lengths(&mut self, name: &'static str, lengths: &[Length]) {
+        if !lengths.is_empty() {
+            self.add(name, join(lengths.iter().map(format_length)));
+        }
+    }
+
+    fn href(&mut self, iri: &Iri) {
+        if !iri.data().is_empty() {
+            self.add("xlink:href", format_iri(iri));
+        }
+    }
+
+    fn matrix(&mut self, name: &'static str, matrix: &Matrix) {
+        if !matrix.is_identity() {
+            self.add(name, format_matrix(matrix));
+        }
+    }
+
+    fn preserve_aspect_ratio(&mut self, value: &PreserveAspectRatio) {
+        if value.align != Align::XMidYMid || value.scale != Scale::Meet {
+            let align = value.align.keyword();
+            let value = match value.scale {
+                Scale::Meet => align.to_owned(),
+                scale => format!("{align} {}", scale.keyword()),
+            };
+            self.add("preserveAspectRatio", value);
+        }
+    }
+
+    fn filter_primitive(&mut self, fe: &Fe) {
+        if let Some(input) = format_input(fe.input()) {
+            self.add("in", input);
+        }
+        if !fe.result().as_str().is_empty() {
+            self.add("result", fe.result().as_str());
+        }
+        self.opt_length("x", fe.x());
+        self.opt_length("y", fe.y());
+        self.opt_length("width", fe.width());
+        self.opt_length("height", fe.height());
+    }
+
+    fn lighting(&mut self, lighting: &fe::Lighting) {
+        self.filter_primitive(lighting);
+        self.number("surfaceScale", lighting.surface_scale());
+        if let Some(length) = lighting.kernel_unit_length() {
+            self.add("kernelUnitLength", format!("{} {}", length.dx, length.dy));
+        }
+    }
+
+    fn gradient(&mut self, gradient: &super::Gradient) {
+        self.href(gradient.href());
+        self.matrix("gradientTransform", gradient.gradient_transform());
+        self.add("spreadMethod", gradient.spread_method().keyword());
+        self.add("gradientUnits", gradient.gradient_units().keyword());
+    }
+
+    fn text_container(&mut self, text: &TextContainer) {
+        self.lengths("x", text.x());
+        self.lengths("y", text.y());
+        self.lengths("dx", text.dx());
+        self.lengths("dy", text.dy());
+        self.numbers("rotate", text.rotate());
+        if *text.xml_space() != XmlSpace::Default {
+            self.add("xml:space", text.xml_space().keyword());
+        }
+    }
+}
+
+/// Adds the attributes that are specific to the type of the node and returns the element name.
+fn element(node: &TypedNode, a: &mut Attributes) -> &'static str {
+    match node {
+        TypedNode::Circle(circle) => {
+            a.length("cx", circle.cx());
+            a.length("cy", circle.cy());
+            a.length("r", circle.r());
+            "circle"
+        }
+        TypedNode::ClipPath(clip_path) => {
+            a.add("clipPathUnits", clip_path.clip_path_units().keyword());
+            "clipPath"
+        }
+        TypedNode::Defs(_) => "defs",
+        TypedNode::Ellipse(ellipse) => {
+            a.length("cx", ellipse.cx());
+            a.length("cy", ellipse.cy());
+            a.opt_length("rx", ellipse.rx());
+            a.opt_length("ry", ellipse.ry());
+            "ellipse"
+        }
+        TypedNode::FeBlend(blend) => {
+            a.filter_primitive(blend);
+            if let Some(input) = format_input(blend.input2()) {
+                a.add("in2", input);
+            }
+            a.add("mode", blend.mode().keyword());
+            "feBlend"
+        }
+        TypedNode::FeColorMatrix(color_matrix) => {
+            a.filter_primitive(color_matrix);
+            a.add("type", color_matrix.kind().keyword());
+            a.numbers("values", color_matrix.values());
+            "feColorMatrix"
+        }
+        TypedNode::FeComponentTransfer(component_transfer) => {
+            a.filter_primitive(component_transfer);
+            "feComponentTransfer"
+        }
+        TypedNode::FeComposite(composite) => {
+            a.filter_primitive(composite);
+            if let Some(input) = format_input(composite.input2()) {
+                a.add("in2", input);
+            }
+            a.add("operator", composite.operator().keyword());
+            if *composite.operator() == fe::CompositeOperator::Arithmetic {
+                a.number("k1", composite.k1());
+                a.number("k2", composite.k2());
+                a.number("k3", composite.k3());
+                a.number("k4", composite.k4());
+            }
+            "feComposite"
+        }
+        TypedNode::FeDiffuseLighting(diffuse) => {
+            a.lighting(diffuse);
+            a.number("diffuseConstant", diffuse.diffuse_constant());
+            "feDiffuseLighting"
+        }
+        TypedNode::FeDisplacementMap(displacement_map) => {
+            a.filter_primitive(displacement_map);
+            if let Some(input) = format_input(displacement_map.input2()) {
+                a.add("in2", input);
+            }
+            a.add(
+                "xChannelSelector",
+                displacement_map.x_channel_selector().keyword(),
+            );
+            a.add(
+                "yChannelSelector",
+                displacement_map.y_channel_selector().keyword(),
+            );
+            a.number("scale", *displacement_map.scale());
+            "feDisplacementMap"
+        }
+        TypedNode::FeDistantLight(light) => {
+            a.number("azimuth", light.azimuth());
+            a.number("elevation", light.elevation());
+            "feDistantLight"
+        }
+        TypedNode::FeFlood(flood) => {
+            a.filter_primitive(flood);
+            "feFlood"
+        }
+        TypedNode::FeFuncA(func)
+        | TypedNode::FeFuncR(func)
+        | TypedNode::FeFuncG(func)
+        | TypedNode::FeFuncB(func) => {
+            a.add("type", func.kind().keyword());
+            a.numbers("tableValues", func.table_values());
+            a.number("slope", func.slope());
+            a.number("intercept", func.intercept());
+            a.number("amplitude", func.amplitude());
+            a.number("exponent", func.exponent());
+            a.number("offset", func.offset());
+            match node {
+                TypedNode::FeFuncA(_) => "feFuncA",
+                TypedNode::FeFuncR(_) => "feFuncR",
+                TypedNode::FeFuncG(_) => "feFuncG",
+                _ => "feFuncB",
+            }
+        }
+        TypedNode::FeGaussianBlur(blur) => {
+            a.filter_primitive(blur);
+            let std_deviation = blur.std_deviation();
+            a.add(
+                "stdDeviation",
+                format!("{} {}", std_deviation.x, std_deviation.y),
+            );
+            "feGaussianBlur"
+        }
+        TypedNode::FeImage(image) => {
+            a.filter_primitive(image);
+            a.href(image.href());
+            a.preserve_aspect_ratio(image.preserve_aspect_ratio());
+            "feImage"
+        }
+        TypedNode::FeMerge(merge) => {
+            a.filter_primitive(merge);
+            "feMerge"
+        }
+        TypedNode::FeMergeNode(merge_node) => {
+            if let Some(input) = format_input(merge_node.input()) {
+                a.add("in", input);
+            }
+            "feMergeNode"
+        }
+        TypedNode::FeMorphology(morphology) => {
+            a.filter_primitive(morphology);
+            a.add("operator", morphology.operator().keyword());
+            let radius = morphology.radius();
+            a.add("radius", format!("{} {}", radius.x, radius.y));
+            "feMorphology"
+        }
+        TypedNode::FeOffset(offset) => {
+            a.filter_primitive(offset);
+            a.number("dx", offset.dx());
+            a.number("dy", offset.dy());
+            "feOffset"
+        }
+        TypedNode::FePointLight(light) => {
+            a.number("x", light.x());
+            a.number("y", light.y());
+            a.number("z", light.z());
+            "fePointLight"
+        }
+        TypedNode::FeSpecularLighting(specular) => {
+            a.lighting(specular);
+            a.number("specularConstant", specular.specular_constant());
+            a.number("specularExponent", specular.specular_exponent());
+            "feSpecularLighting"
+        }
+        TypedNode::FeSpotLight(light) => {
+            a.number("x", light.x());
+            a.number("y", light.y());
+            a.number("z", light.z());
+            a.number("pointsAtX", light.points_at_x());
+            a.number("pointsAtY", light.points_at_y());
+            a.number("pointsAtZ", light.points_at_z());
+            a.number("specularExponent", light.specular_exponent());
+            a.opt_number("limitingConeAngle", light.limiting_cone_angle());
+            "feSpotLight"
+        }
+        TypedNode::FeTurbulence(turbulence) => {
+            a.filter_primitive(turbulence);
+            let base_frequency = turbulence.base_frequency();
+            a.add(
+                "baseFrequency",
+                format!("{} {}", base_frequency.x, base_frequency.y),
+            );
+            a.number("numOctaves", turbulence.num_octaves() as scalar);
+            a.number("seed", turbulence.seed());
+            a.add("type", turbulence.turbulence_type().keyword());
+            "feTurbulence"
+        }
+        TypedNode::Filter(filter) => {
+            a.length("x", filter.x());
+            a.length("y", filter.y());
+            a.length("width", filter.width());
+            a.length("height", filter.height());
+            a.add("filterUnits", filter.filter_units().keyword());
+            a.add("primitiveUnits", filter.primitive_units().keyword());
+            "filter"
+        }
+        TypedNode::G(_) => "g",
+        TypedNode::Image(image) => {
+            a.length("x", image.x());
+            a.length("y", image.y());
+            a.length("width", image.width());
+            a.length("height", image.height());
+            a.href(image.href());
+            a.preserve_aspect_ratio(image.preserve_aspect_ratio());
+            "image"
+        }
+        TypedNode::Line(line) => {
+            a.length("x1", line.x1());
+            a.length("y1", line.y1());
+            a.length("x2", line.x2());
+            a.length("y2", line.y2());
+            "line"
+        }
+        TypedNode::LinearGradient(gradient) => {
+            a.gradient(gradient);
+            a.length("x1", gradient.x1());
+            a.length("y1", gradient.y1());
+            a.length("x2", gradient.x2());
+            a.length("y2", gradient.y2());
+            "linearGradient"
+        }
+        TypedNode::Mask(mask) => {
+            a.length("x", mask.x());
+            a.length("y", mask.y());
+            a.length("width", mask.width());
+            a.length("height", mask.height());
+            a.add("maskUnits", mask.mask_units().keyword());
+            a.add("maskContentUnits", mask.mask_content_units().keyword());
+            "mask"
+        }
+        TypedNode::Path(path) => {
+            a.add("d", path.path().to_svg());
+            "path"
+        }
+        TypedNode::Pattern(pattern) => {
+            a.href(pattern.href());
+            a.opt_length("x", pattern.x());
+            a.opt_length("y", pattern.y());
+            a.opt_length("width", pattern.width());
+            a.opt_length("height", pattern.height());
+            if let Some(transform) = pattern.pattern_transform() {
+                a.matrix("patternTransform", transform);
+            }
+            "pattern"
+        }
+        TypedNode::Polygon(poly) | TypedNode::Polyline(poly) => {
+            a.add("points", format_points(poly.points()));
+            if matches!(node, TypedNode::Polygon(_)) {
+                "polygon"
+            } else {
+                "polyline"
+            }
+        }
+        TypedNode::RadialGradient(gradient) => {
+            a.gradient(gradient);
+            a.length("cx", gradient.cx());
+            a.length("cy", gradient.cy());
+            a.length("r", gradient.r());
+            a.opt_length("fx", gradient.fx());
+            a.opt_length("fy", gradient.fy());
+            "radialGradient"
+        }
+        TypedNode::Rect(rect) => {
+            a.length("x", rect.x());
+            a.length("y", rect.y());
+            a.length("width", rect.width());
+            a.length("height", rect.height());
+            a.opt_length("rx", rect.rx());
+            a.opt_length("ry", rect.ry());
+            "rect"
+        }
+        TypedNode::Stop(stop) => {
+            a.length("offset", stop.offset());
+            "stop"
+        }
+        TypedNode::Svg(svg) => {
+            a.length("x", svg.x());
+            a.length("y", svg.y());
+            a.length("width", svg.width());
+            a.length("height", svg.height());
+            if let Some(view_box) = svg.view_box() {
+                a.add(
+                    "viewBox",
+                    join(
+                        [
+                            view_box.left,
+                            view_box.top,
+                            view_box.width(),
+                            view_box.height(),
+                        ]
+                        .iter(),
+                    ),
+                );
+            }
+            a.preserve_aspect_ratio(svg.preserve_aspect_ratio());
+            "svg"
+        }
+        TypedNode::Text(text) => {
+            a.text_container(text);
+            "text"
+        }
+        TypedNode::TextLiteral(_) => unreachable!("text literals are written as text"),
+        TypedNode::TextPath(text_path) => {
+            a.text_container(text_path);
+            a.href(text_path.href());
+            a.length("startOffset", text_path.start_offset());
+            "textPath"
+        }
+        TypedNode::TSpan(tspan) => {
+            a.text_container(tspan);
+            "tspan"
+        }
+        TypedNode::Use(r#use) => {
+            a.length("x", r#use.x());
+            a.length("y", r#use.y());
+            a.href(r#use.href());
+            "use"
+        }
+    }
+}
+
+fn transform_attribute(node: &Node, a: &mut Attributes) {
+    // All node types derive from `SkSVGTransformableNode`.
+    let node = unsafe { transmute_ref::<Node, TransformableNode>(node) };
+    a.matrix("transform", node.transform());
+}
+
+fn presentation_attributes(node: &Node, a: &mut Attributes) {
+    if let Some(value) = node.clip_rule() {
+        a.add("clip-rule", value.keyword());
+    }
+    if let Some(value) = node.color_interpolation() {
+        a.add("color-interpolation", value.keyword());
+    }
+    if let Some(value) = node.color_interpolation_filters() {
+        a.add("color-interpolation-filters", value.keyword());
+    }
+    if let Some(value) = node.color() {
+        a.add("color", format_color(*value));
+    }
+    if let Some(value) = node.fill_rule() {
+        a.add("fill-rule", value.keyword());
+    }
+    if let Some(value) = node.fill() {
+        a.add("fill", format_paint(value));
+    }
+    a.opt_number("fill-opacity", node.fill_opacity());
+    if let Some(value) = node.font_family() {
+        a.add("font-family", value.family().unwrap_or("inherit"));
+    }
+    if let Some(value) = node.font_size() {
+        a.add(
+            "font-size",
+            value
+                .size()
+                .map(format_length)
+                .unwrap_or_else(|| "inherit".into()),
+        );
+    }
+    if let Some(value) = node.font_style() {
+        a.add("font-style", value.keyword());
+    }
+    if let Some(value) = node.font_weight() {
+        a.add("font-weight", value.keyword());
+    }
+    if let Some(value) = node.stroke() {
+        a.add("stroke", format_paint(value));
+    }
+    if let Some(value) = node.stroke_line_cap() {
+        a.add("stroke-linecap", value.keyword());
+    }
+    if let Some(value) = node.stroke_line_join() {
+        a.add("stroke-linejoin", value.keyword());
+    }
+    a.opt_number("stroke-miterlimit", node.stroke_miter_limit());
+    a.opt_number("stroke-opacity", node.stroke_opacity());
+    a.opt_length("stroke-width", node.stroke_width());
+    if let Some(value) = node.text_anchor() {
+        a.add("text-anchor", value.keyword());
+    }
+    if let Some(value) = node.visibility() {
+        a.add("visibility", value.keyword());
+    }
+    if let Some(value) = node.clip_path() {
+        a.add("clip-path", format_iri_func(value));
+    }
+    if let Some(value) = node.display() {
+        a.add("display", value.keyword());
+    }
+    if let Some(value) = node.mask() {
+        a.add("mask", format_iri_func(value));
+    }
+    if let Some(value) = node.filter() {
+        a.add("filter", format_iri_func(value));
+    }
+    a.opt_number("opacity", node.opacity());
+    if let Some(value) = node.stop_color() {
+        a.add("stop-color", format_fill(value));
+    }
+    a.opt_number("stop-opacity", node.stop_opacity());
+    if let Some(value) = node.flood_color() {
+        a.add("flood-color", format_fill(value));
+    }
+    a.opt_number("flood-opacity", node.flood_opacity());
+    if let Some(value) = node.lighting_color() {
+        a.add("lighting-color", format_fill(value));
+    }
+}
+
+fn format_length(length: &Length) -> String {
+    let unit = match length.unit {
+        LengthUnit::Unknown | LengthUnit::Number => "",
+        LengthUnit::Percentage => "%",
+        LengthUnit::EMS => "em",
+        LengthUnit::EXS => "ex",
+        LengthUnit::PX => "px",
+        LengthUnit::CM => "cm",
+        LengthUnit::MM => "mm",
+        LengthUnit::IN => "in",
+        LengthUnit::PT => "pt",
+        LengthUnit::PC => "pc",
+    };
+    format!("{}{unit}", length.value)
+}
+
+fn format_points(points: &[Point]) -> String {
+    join(points.iter().map(|p| format!("{},{}", p.x, p.y)))
+}
+
+fn format_matrix(matrix: &Matrix) -> String {
+    format!(
+        "matrix({} {} {} {} {} {})",
+        matrix.scale_x(),
+        matrix.skew_y(),
+        matrix.skew_x(),
+        matrix.scale_y(),
+        matrix.translate_x(),
+        matrix.translate_y()
+    )
+}
+
+fn format_color(color: Color) -> String {
+    if color.a() == 0xff {
+        format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+    } else {
+        format!(
+            "rgba({},{},{},{})",
+            color.r(),
+            color.g(),
+            color.b(),
+            color.a() as f32 / 255.0
+        )
+    }
+}
+
+fn format_fill(fill: &Fill) -> String {
+    match (fill.kind(), fill.color()) {
+        (ColorKind::Color, Some(color)) => format_color(color),
+        _ => "currentColor".into(),
+    }
+}
+
+fn format_paint(paint: &Paint) -> String {
+    let native = paint.native();
+    match native.fType {
+        sb::SkSVGPaint_Type::None => "none".into(),
+        sb::SkSVGPaint_Type::Color => format_fill(Fill::from_native_ref(&native.fColor)),
+        sb::SkSVGPaint_Type::IRI => {
+            format!("url({})", format_iri(Iri::from_native_ref(&native.fIRI)))
+        }
+    }
+}
+
+fn format_iri(iri: &Iri) -> String {
+    match iri.native().fType {
+        IriKind::Local => format!("#{}", iri.data()),
+        _ => iri.data().into(),
+    }
+}
+
+fn format_iri_func(func: &IriFunc) -> String {
+    match (func.kind(), func.iri()) {
+        (IriFuncKind::IRI, Some(iri)) => format!("url({})", format_iri(iri)),
+        _ => "none".into(),
+    }
+}
+
+fn format_input(input: &fe::Input) -> Option<String> {
+    match input.kind() {
+        InputType::Unspecified => None,
+        InputType::FilterPrimitiveReference => input.id().map(|id| id.into()),
+        InputType::SourceGraphic => Some("SourceGraphic".into()),
+        InputType::SourceAlpha => Some("SourceAlpha".into()),
+        InputType::BackgroundImage => Some("BackgroundImage".into()),
+        InputType::BackgroundAlpha => Some("BackgroundAlpha".into()),
+        InputType::FillPaint => Some("FillPaint".into()),
+        InputType::StrokePaint => Some("StrokePaint".into()),
+    }
+}
+
+fn join<T: fmt::Display>(values: impl Iterator<Item = T>) -> String {
+    values
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The SVG keyword of an enum variant.
+trait Keyword {
+    fn keyword(self) -> &'static str;
+}
+
+macro_rules! keywords {
+    ($($ty:ident { $($variant:ident => $keyword:literal),* $(,)? })*) => {
+        $(
+            impl Keyword for $ty {
+                fn keyword(self) -> &'static str {
+                    match self {
+                        $($ty::$variant => $keyword,)*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+keywords! {
+    Align {
+        XMinYMin => "xMinYMin",
+        XMidYMin => "xMidYMin",
+        XMaxYMin => "xMaxYMin",
+        XMinYMid => "xMinYMid",
+        XMidYMid => "xMidYMid",
+        XMaxYMid => "xMaxYMid",
+        XMinYMax => "xMinYMax",
+        XMidYMax => "xMidYMax",
+        XMaxYMax => "xMaxYMax",
+        None => "none",
+    }
+    Scale {
+        Meet => "meet",
+        Slice => "slice",
+    }
+    BoundingBoxUnits {
+        UserSpaceOnUse => "userSpaceOnUse",
+        ObjectBoundingBox => "objectBoundingBox",
+    }
+    SpreadMethod {
+        Pad => "pad",
+        Reflect => "reflect",
+        Repeat => "repeat",
+    }
+    XmlSpace {
+        Default => "default",
+        Preserve => "preserve",
+    }
+    FillRule {
+        NonZero => "nonzero",
+        EvenOdd => "evenodd",
+        Inherit => "inherit",
+    }
+    ColorSpace {
+        Auto => "auto",
+        SRGB => "sRGB",
+        LinearRGB => "linearRGB",
+    }
+    FontStyle {
+        Normal => "normal",
+        Italic => "italic",
+        Oblique => "oblique",
+        Inherit => "inherit",
+    }
+    FontWeight {
+        W100 => "100",
+        W200 => "200",
+        W300 => "300",
+        W400 => "400",
+        W500 => "500",
+        W600 => "600",
+        W700 => "700",
+        W800 => "800",
+        W900 => "900",
+        Normal => "normal",
+        Bold => "bold",
+        Bolder => "bolder",
+        Lighter => "lighter",
+        Inherit => "inherit",
+    }
+    LineCap {
+        Butt => "butt",
+        Round => "round",
+        Square => "square",
+    }
+    LineJoin {
+        Miter => "miter",
+        Round => "round",
+        Bevel => "bevel",
+        Inherit => "inherit",
+    }
+    TextAnchor {
+        Start => "start",
+        Middle => "middle",
+        End => "end",
+        Inherit => "inherit",
+    }
+    Visibility {
+        Visible => "visible",
+        Hidden => "hidden",
+        Collapse => "collapse",
+        Inherit => "inherit",
+    }
+    Display {
+        Inline => "inline",
+        None => "none",
+    }
+    BlendMode {
+        Normal => "normal",
+        Multiply => "multiply",
+        Screen => "screen",
+        Darken => "darken",
+        Lighten => "lighten",
+        Overlay => "overlay",
+        ColorDodge => "color-dodge",
+        ColorBurn => "color-burn",
+        HardLight => "hard-light",
+        SoftLight => "soft-light",
+        Difference => "difference",
+        Exclusion => "exclusion",
+        Hue => "hue",
+        Saturation => "saturation",
+        Color => "color",
+        Luminosity => "luminosity",
+    }
+    ColorMatrixKind {
+        Matrix => "matrix",
+        Saturate => "saturate",
+        HueRotate => "hueRotate",
+        LuminanceToAlpha => "luminanceToAlpha",
+    }
+    CompositeOperator {
+        Over => "over",
+        In => "in",
+        Out => "out",
+        Atop => "atop",
+        Xor => "xor",
+        Arithmetic => "arithmetic",
+    }
+    ChannelSelector {
+        R => "R",
+        G => "G",
+        B => "B",
+        A => "A",
+    }
+    FuncKind {
+        Identity => "identity",
+        Table => "table",
+        Discrete => "discrete",
+        Linear => "linear",
+        Gamma => "gamma",
+    }
+    MorphologyOperator {
+        Erode => "erode",
+        Dilate => "dilate",
+    }
+    TurbulenceType {
+        FractalNoise => "fractalNoise",
+        Turbulence => "turbulence",
+    }
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::{BoundingBoxUnits, FillRule, FontWeight, fe::BlendMode};
+
+    #[test]
+    fn keywords() {
+        assert_eq!(FillRule::EvenOdd.keyword(), "evenodd");
+        assert_eq!(
+            BoundingBoxUnits::ObjectBoundingBox.keyword(),
+            "objectBoundingBox"
+        );
+        assert_eq!(BlendMode::ColorDodge.keyword(), "color-dodge");
+        assert_eq!(FontWeight::W700.keyword(), "700");
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(
+            format_length(&Length::new(50.0, LengthUnit::Percentage)),
+            "50%"
+        );
+        assert_eq!(format_color(Color::from_rgb(255, 0, 16)), "#ff0010");
+        assert_eq!(
+            format_matrix(&Matrix::translate((10.0, 20.0))),
+            "matrix(1 0 0 1 10 20)"
+        );
+        assert_eq!(
+            escape("a < \"b\" & c", true),
+            "a &lt; &quot;b&quot; &amp; c"
+        );
+    }
+}
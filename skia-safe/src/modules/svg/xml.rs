@@ -0,0 +1,374 @@
+//! Records the elements of a document while Skia loads it.
+//!
+//! Skia's SVG parser does not report where or why a document failed to parse, and skips the
+//! elements it does not support. While Skia reads a document, the bindings pass the same bytes
+//! to a second expat parser, which reports the elements, their positions, and the first error.
+
+use std::{
+    collections::HashMap,
+    ffi::{CStr, c_char, c_void},
+    fmt, slice,
+};
+
+use super::{Dom, Node, NodeTag, ParseError, ParseErrorKind};
+use crate::{FontMgr, prelude::*, resources::CachingResourceProvider};
+use skia_bindings as sb;
+
+/// A location inside an XML document.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextPosition {
+    /// Byte offset from the start of the document.
+    pub offset: usize,
+    /// One-based line number.
+    pub line: usize,
+    /// One-based column, counted in characters.
+    pub column: usize,
+}
+
+impl fmt::Display for TextPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A well-formedness error reported by expat, the XML parser Skia uses.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct XmlError {
+    code: i32,
+}
+
+impl XmlError {
+    /// The `XML_Error` code of expat.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The description of the error, as provided by expat.
+    pub fn message(&self) -> &'static str {
+        let message = unsafe { sb::C_SvgXmlError_message(self.code) };
+        if message.is_null() {
+            return "unknown error";
+        }
+        unsafe { CStr::from_ptr(message) }
+            .to_str()
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// An element of a document.
+#[derive(Debug)]
+pub(crate) struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    /// The position of the start tag.
+    pub position: TextPosition,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    /// The character data directly inside of the element.
+    pub text: String,
+    /// The child elements and the character data in document order.
+    pub content: Vec<Content>,
+}
+
+/// A part of the content of an element.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Content {
+    Element(usize),
+    /// The character data between two tags.
+    Text(String),
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// The elements of a document, as far as it could be parsed.
+#[derive(Debug, Default)]
+pub(crate) struct Document {
+    /// The elements in document order. The first element is the root.
+    pub elements: Vec<Element>,
+    /// The indices of the elements that are not closed yet.
+    open: Vec<usize>,
+}
+
+impl Document {
+    pub fn root(&self) -> Option<&Element> {
+        self.elements.first()
+    }
+
+    /// Returns the nodes Skia created for the elements, indexed like [`Self::elements`].
+    ///
+    /// The nodes are found by walking the elements and the nodes in parallel. Elements Skia
+    /// skipped, because they are not supported or can not be appended to their parent, have no
+    /// node, and neither have their descendants.
+    pub fn nodes(&self, root: Node) -> Vec<Option<Node>> {
+        let mut nodes = vec![None; self.elements.len()];
+        if self.elements.is_empty() {
+            return nodes;
+        }
+        let mut tags: HashMap<&str, Option<NodeTag>> = HashMap::new();
+        let mut pending = vec![(0, root)];
+
+        while let Some((index, node)) = pending.pop() {
+            let parent_tag = node.tag();
+            let mut children = node
+                .child_nodes()
+                .into_iter()
+                .filter(|child| child.tag() != NodeTag::TextLiteral);
+
+            for &child in &self.elements[index].children {
+                let name = self.elements[child].name.as_str();
+                let tag = *tags
+                    .entry(name)
+                    .or_insert_with(|| Node::from_element_name(name).map(|node| node.tag()));
+                let Some(tag) = tag.filter(|tag| accepts(parent_tag, *tag)) else {
+                    continue;
+                };
+                match children.next() {
+                    Some(child_node) if child_node.tag() == tag => {
+                        pending.push((child, child_node))
+                    }
+                    // The node tree was modified, the remaining children can not be matched.
+                    _ => break,
+                }
+            }
+            nodes[index] = Some(node);
+        }
+
+        nodes
+    }
+
+    fn start_element(
+        &mut self,
+        name: String,
+        attributes: Vec<(String, String)>,
+        position: TextPosition,
+    ) {
+        let index = self.elements.len();
+        let parent = self.open.last().copied();
+        if let Some(parent) = parent {
+            self.elements[parent].children.push(index);
+            self.elements[parent].content.push(Content::Element(index));
+        }
+        self.elements.push(Element {
+            name,
+            attributes,
+            position,
+            parent,
+            children: Vec::new(),
+            text: String::new(),
+            content: Vec::new(),
+        });
+        self.open.push(index);
+    }
+}
+
+/// Returns `true` if a node with the tag `parent` keeps a child node with the tag `child`.
+fn accepts(parent: NodeTag, child: NodeTag) -> bool {
+    match parent {
+        NodeTag::Text | NodeTag::TSpan | NodeTag::TextPath => {
+            matches!(child, NodeTag::TSpan | NodeTag::TextPath)
+        }
+        _ => true,
+    }
+}
+
+/// Loads a document from `stream` with Skia and records its elements.
+pub(crate) fn load(
+    stream: &mut sb::SkStream,
+    font_mgr: &FontMgr,
+    resource_provider: &CachingResourceProvider,
+) -> (Result<Dom, ParseError>, Document) {
+    let mut document = Document::default();
+    let observer = sb::SvgXmlObserver {
+        data: &mut document as *mut Document as *mut c_void,
+        startElement: Some(start_element),
+        endElement: Some(end_element),
+        text: Some(text),
+    };
+    let mut error = sb::SvgXmlError {
+        code: 0,
+        entityDeclaration: false,
+        offset: 0,
+        line: 0,
+        column: 0,
+    };
+
+    let dom = Dom::from_ptr(unsafe {
+        sb::C_SkSVGDOM_MakeFromStream(
+            stream,
+            font_mgr.clone().into_ptr(),
+            resource_provider.clone().into_ptr(),
+            &observer,
+            &mut error,
+        )
+    });
+
+    let dom = dom.ok_or_else(|| {
+        let position = TextPosition {
+            offset: error.offset,
+            line: error.line,
+            column: error.column,
+        };
+        match document.root() {
+            _ if error.entityDeclaration => {
+                ParseError::new(ParseErrorKind::EntityDeclaration, Some(position))
+            }
+            _ if error.code != 0 => ParseError::new(
+                ParseErrorKind::Xml(XmlError { code: error.code }),
+                Some(position),
+            ),
+            Some(root) if root.name != "svg" => ParseError::new(
+                ParseErrorKind::RootNotSvg {
+                    name: root.name.clone(),
+                },
+                Some(root.position),
+            ),
+            _ => ParseError::new(ParseErrorKind::Unknown, None),
+        }
+    });
+
+    (dom, document)
+}
+
+unsafe extern "C" fn start_element(
+    data: *mut c_void,
+    name: *const c_char,
+    attributes: *const *const c_char,
+    offset: usize,
+    line: usize,
+    column: usize,
+) {
+    let document = unsafe { &mut *(data as *mut Document) };
+    let string = |s: *const c_char| unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned();
+
+    // `attributes` holds alternating names and values and ends with a null pointer.
+    let mut len = 0;
+    while !unsafe { *attributes.add(len) }.is_null() {
+        len += 1;
+    }
+    let attributes = unsafe { slice::from_raw_parts(attributes, len) }
+        .chunks_exact(2)
+        .map(|pair| (string(pair[0]), string(pair[1])))
+        .collect();
+
+    document.start_element(
+        string(name),
+        attributes,
+        TextPosition {
+            offset,
+            line,
+            column,
+        },
+    );
+}
+
+unsafe extern "C" fn end_element(data: *mut c_void) {
+    let document = unsafe { &mut *(data as *mut Document) };
+    document.open.pop();
+}
+
+unsafe extern "C" fn text(data: *mut c_void, text: *const c_char, len: usize) {
+    let document = unsafe { &mut *(data as *mut Document) };
+    if let Some(&index) = document.open.last() {
+        let text = unsafe { slice::from_raw_parts(text as *const u8, len) };
+        let text = String::from_utf8_lossy(text);
+        let element = &mut document.elements[index];
+        element.text.push_str(&text);
+        // expat may report character data in several parts.
+        match element.content.last_mut() {
+            Some(Content::Text(content)) => content.push_str(&text),
+            _ => element.content.push(Content::Text(text.into_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        interop::{MemoryStream, NativeStreamBase},
+        resources::NativeResourceProvider,
+    };
+
+    fn load_bytes(svg: &[u8]) -> (Result<Dom, ParseError>, Document) {
+        let mut stream = MemoryStream::from_bytes(svg);
+        let provider: NativeResourceProvider = FontMgr::new_empty().into();
+        load(
+            stream.native_mut().as_stream_mut(),
+            &provider.font_mgr(),
+            &CachingResourceProvider::new(&provider),
+        )
+    }
+
+    #[test]
+    fn elements_are_recorded() {
+        let (dom, document) = load_bytes(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\">\n  <rect id=\"r\" fill=\"&lt;\"/>\n  <text>a&amp;b</text>\n</svg>"
+                .as_bytes(),
+        );
+        assert!(dom.is_ok());
+        let names: Vec<_> = document.elements.iter().map(|e| &e.name).collect();
+        assert_eq!(names, ["svg", "rect", "text"]);
+        let rect = &document.elements[1];
+        assert_eq!(rect.attribute("fill"), Some("<"));
+        assert_eq!(rect.parent, Some(0));
+        assert_eq!(
+            rect.position,
+            TextPosition {
+                offset: 43,
+                line: 2,
+                column: 3
+            }
+        );
+        assert_eq!(document.elements[2].text, "a&b");
+        assert_eq!(document.elements[0].children, [1, 2]);
+        assert_eq!(
+            document.elements[0].content,
+            [
+                Content::Text("\n  ".into()),
+                Content::Element(1),
+                Content::Text("\n  ".into()),
+                Content::Element(2),
+                Content::Text("\n".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn elements_are_mapped_to_nodes() {
+        let (dom, document) = load_bytes(
+            br#"<svg><title>t</title><g><foo><rect/></foo><circle/><text>a<a>b</a><tspan>c</tspan></text></g></svg>"#,
+        );
+        let nodes = document.nodes(dom.unwrap().root().into_node());
+        let tags: Vec<_> = nodes
+            .iter()
+            .map(|node| node.as_ref().map(|node| node.tag()))
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                Some(NodeTag::Svg),
+                None,
+                Some(NodeTag::G),
+                None,
+                None,
+                Some(NodeTag::Circle),
+                Some(NodeTag::Text),
+                None,
+                Some(NodeTag::TSpan),
+            ]
+        );
+    }
+}
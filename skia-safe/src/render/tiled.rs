@@ -0,0 +1,337 @@
+//! Tiled and multithreaded raster rendering of [`Picture`]s.
+//!
+//! [`render()`] splits the output into tiles, renders them in parallel into CPU raster surfaces
+//! and assembles the result into a single pixel buffer. [`render_bands()`] renders one row of tiles
+//! at a time and passes each band to a callback, so that images too large to be kept in memory can
+//! be streamed to an encoder.
+//!
+//! Pictures are immutable and are played back from all threads at the same time. Recording them
+//! with a bounding box hierarchy (see [`crate::PictureRecorder::begin_recording()`]) lets each tile
+//! skip the ops outside of it.
+//!
+//! # Seams
+//!
+//! Tiles differ from each other only by an integer translation, so anti-aliased edges are identical
+//! on both sides of a seam. Effects that read pixels outside of their own bounds, for example
+//! blurs and other image filters, can only see the pixels of the surface they are rendered into.
+//! [`Options::overlap`] renders a margin around every tile that is discarded afterwards and should
+//! be set to at least the distance these effects reach, e.g. three times the sigma of a blur.
+
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{Color4f, IRect, ISize, ImageInfo, Matrix, Picture, Pixmap, SurfaceProps, surfaces};
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// The size of the tiles, `512 × 512` by default.
+    pub tile_size: ISize,
+    /// The number of pixels rendered beyond each edge of a tile, `0` by default.
+    pub overlap: i32,
+    /// The number of threads, [`thread::available_parallelism()`] if `None`.
+    pub threads: Option<NonZeroUsize>,
+    /// The transformation from picture to output coordinates.
+    pub matrix: Matrix,
+    /// The color the output is cleared to before the picture is drawn. Transparent if `None`.
+    pub background: Option<Color4f>,
+    pub surface_props: Option<SurfaceProps>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            tile_size: ISize::new(512, 512),
+            overlap: 0,
+            threads: None,
+            matrix: Matrix::default(),
+            background: None,
+            surface_props: None,
+        }
+    }
+}
+
+/// The pixels rendered by [`render()`].
+#[derive(Clone, Debug)]
+pub struct RenderedImage {
+    info: ImageInfo,
+    row_bytes: usize,
+    pixels: Vec<u8>,
+}
+
+impl RenderedImage {
+    pub fn info(&self) -> &ImageInfo {
+        &self.info
+    }
+
+    pub fn row_bytes(&self) -> usize {
+        self.row_bytes
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    pub fn pixmap(&mut self) -> Pixmap<'_> {
+        Pixmap::new(&self.info, &mut self.pixels, self.row_bytes).unwrap()
+    }
+}
+
+/// Renders `picture` into an image described by `info`.
+///
+/// Returns `None` if `info` is empty or not supported by raster surfaces, or if `options`
+/// specifies an empty tile size.
+pub fn render(picture: &Picture, info: &ImageInfo, options: &Options) -> Option<RenderedImage> {
+    let renderer = Renderer::new(picture, info, options)?;
+    let row_bytes = info.min_row_bytes();
+    let mut pixels = vec![0; info.compute_min_byte_size()];
+
+    let band_bytes = row_bytes * renderer.tile_size.height as usize;
+    for (band, band_pixels) in renderer.bands().zip(pixels.chunks_mut(band_bytes)) {
+        if !renderer.render_band(band, band_pixels, row_bytes) {
+            return None;
+        }
+    }
+
+    Some(RenderedImage {
+        info: info.clone(),
+        row_bytes,
+        pixels,
+    })
+}
+
+/// Renders `picture` into an image described by `info` one band of tiles at a time, from top to
+/// bottom.
+///
+/// `sink` receives the top row of each band in the output and its pixels. Only the pixels of one
+/// band are kept in memory.
+///
+/// Returns `Ok(false)` if `info` is empty or not supported by raster surfaces, or if `options`
+/// specifies an empty tile size, and the first error returned by `sink`.
+pub fn render_bands<E>(
+    picture: &Picture,
+    info: &ImageInfo,
+    options: &Options,
+    mut sink: impl FnMut(i32, &Pixmap) -> Result<(), E>,
+) -> Result<bool, E> {
+    let Some(renderer) = Renderer::new(picture, info, options) else {
+        return Ok(false);
+    };
+    let row_bytes = info.min_row_bytes();
+    let mut pixels = vec![0; row_bytes * renderer.tile_size.height as usize];
+
+    for band in renderer.bands() {
+        let band_info = info.with_dimensions(band.size());
+        let band_pixels = &mut pixels[..band_info.compute_byte_size(row_bytes)];
+        if !renderer.render_band(band, band_pixels, row_bytes) {
+            return Ok(false);
+        }
+        let pixmap = Pixmap::new(&band_info, band_pixels, row_bytes).unwrap();
+        sink(band.top, &pixmap)?;
+    }
+
+    Ok(true)
+}
+
+/// Tiles and their tightly packed pixels.
+type RenderedTiles = Vec<(IRect, Vec<u8>)>;
+
+struct Renderer<'a> {
+    picture: &'a Picture,
+    info: &'a ImageInfo,
+    options: &'a Options,
+    tile_size: ISize,
+    threads: usize,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(picture: &'a Picture, info: &'a ImageInfo, options: &'a Options) -> Option<Self> {
+        let tile_size = options.tile_size;
+        if info.is_empty() || tile_size.is_empty() || options.overlap < 0 {
+            return None;
+        }
+        // Tiles at the bottom and right edges are cut off.
+        let tile_size = ISize::new(
+            tile_size.width.min(info.width()),
+            tile_size.height.min(info.height()),
+        );
+        let threads = options.threads.map_or_else(
+            || thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            NonZeroUsize::get,
+        );
+        Some(Self {
+            picture,
+            info,
+            options,
+            tile_size,
+            threads,
+        })
+    }
+
+    fn bands(&self) -> impl Iterator<Item = IRect> {
+        let (width, height) = (self.info.width(), self.info.height());
+        (0..height)
+            .step_by(self.tile_size.height as usize)
+            .map(move |top| IRect::new(0, top, width, (top + self.tile_size.height).min(height)))
+    }
+
+    /// Renders the tiles of `band` in parallel and copies them into `pixels`, which starts at the
+    /// top left corner of the band.
+    fn render_band(&self, band: IRect, pixels: &mut [u8], row_bytes: usize) -> bool {
+        let tiles: Vec<IRect> = (band.left..band.right)
+            .step_by(self.tile_size.width as usize)
+            .map(|left| {
+                IRect::new(
+                    left,
+                    band.top,
+                    (left + self.tile_size.width).min(band.right),
+                    band.bottom,
+                )
+            })
+            .collect();
+
+        let next = AtomicUsize::new(0);
+        let threads = self.threads.min(tiles.len());
+        let rendered: Vec<Option<RenderedTiles>> = thread::scope(|s| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| s.spawn(|| self.render_tiles(&tiles, &next)))
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().unwrap())
+                .collect()
+        });
+
+        let bytes_per_pixel = self.info.bytes_per_pixel();
+        for tiles in rendered {
+            let Some(tiles) = tiles else {
+                return false;
+            };
+            for (tile, tile_pixels) in tiles {
+                let tile_row_bytes = tile.width() as usize * bytes_per_pixel;
+                for (y, tile_row) in tile_pixels.chunks_exact(tile_row_bytes).enumerate() {
+                    let offset = (tile.top - band.top) as usize * row_bytes
+                        + y * row_bytes
+                        + tile.left as usize * bytes_per_pixel;
+                    pixels[offset..offset + tile_row_bytes].copy_from_slice(tile_row);
+                }
+            }
+        }
+        true
+    }
+
+    /// Renders tiles until all `tiles` are taken. Returns the tiles rendered by this thread with
+    /// tightly packed pixels.
+    fn render_tiles(&self, tiles: &[IRect], next: &AtomicUsize) -> Option<RenderedTiles> {
+        let overlap = self.options.overlap;
+        let surface_info = self.info.with_dimensions((
+            self.tile_size.width + 2 * overlap,
+            self.tile_size.height + 2 * overlap,
+        ));
+        let mut surface =
+            surfaces::raster(&surface_info, None, self.options.surface_props.as_ref())?;
+
+        let mut rendered = Vec::new();
+        while let Some(tile) = tiles.get(next.fetch_add(1, Ordering::Relaxed)) {
+            let canvas = surface.canvas();
+            canvas.clear(self.options.background.unwrap_or(Color4f::TRANSPARENT));
+            canvas.save();
+            canvas.translate(((overlap - tile.left) as f32, (overlap - tile.top) as f32));
+            canvas.draw_picture(self.picture, Some(&self.options.matrix), None);
+            canvas.restore();
+
+            let tile_info = self.info.with_dimensions(tile.size());
+            let tile_row_bytes = tile_info.min_row_bytes();
+            let mut pixels = vec![0; tile_info.compute_min_byte_size()];
+            if !surface.read_pixels(&tile_info, &mut pixels, tile_row_bytes, (overlap, overlap)) {
+                return None;
+            }
+            rendered.push((*tile, pixels));
+        }
+        Some(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, ColorType, Paint, PictureRecorder, Rect, image_filters};
+
+    fn picture() -> Picture {
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(Rect::from_wh(100.0, 100.0), true);
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true).set_color(Color::BLUE);
+        canvas.draw_circle((50.0, 50.0), 33.3, &paint);
+        paint
+            .set_color(Color::RED)
+            .set_image_filter(image_filters::blur((3.0, 3.0), None, None, None));
+        canvas.draw_rect(Rect::new(20.5, 60.25, 80.75, 70.0), &paint);
+        recorder.finish_recording_as_picture(None).unwrap()
+    }
+
+    fn reference(picture: &Picture, info: &ImageInfo) -> Vec<u8> {
+        let mut surface = surfaces::raster(info, None, None).unwrap();
+        surface.canvas().draw_picture(picture, None, None);
+        let mut pixels = vec![0; info.compute_min_byte_size()];
+        assert!(surface.read_pixels(info, &mut pixels, info.min_row_bytes(), (0, 0)));
+        pixels
+    }
+
+    /// Rounding may differ in the last bit after the geometry is translated to a tile.
+    fn assert_close(pixels: &[u8], expected: &[u8]) {
+        assert_eq!(pixels.len(), expected.len());
+        for (i, (a, b)) in pixels.iter().zip(expected).enumerate() {
+            assert!(a.abs_diff(*b) <= 1, "byte {i}: {a} != {b}");
+        }
+    }
+
+    #[test]
+    fn tiles_match_a_single_surface() {
+        let picture = picture();
+        let info = ImageInfo::new_n32_premul((100, 100), None);
+        let options = Options {
+            tile_size: ISize::new(16, 24),
+            overlap: 12,
+            threads: NonZeroUsize::new(3),
+            ..Options::default()
+        };
+
+        let image = render(&picture, &info, &options).unwrap();
+        assert_close(image.pixels(), &reference(&picture, &info));
+    }
+
+    #[test]
+    fn bands_are_streamed_in_order() {
+        let picture = picture();
+        let info = ImageInfo::new_n32_premul((100, 100), None);
+        let options = Options {
+            tile_size: ISize::new(32, 32),
+            overlap: 12,
+            ..Options::default()
+        };
+
+        let mut tops = Vec::new();
+        let mut pixels = Vec::new();
+        let rendered = render_bands(&picture, &info, &options, |top, band| {
+            tops.push(top);
+            pixels.extend_from_slice(band.bytes().unwrap());
+            Ok::<_, ()>(())
+        });
+        assert_eq!(rendered, Ok(true));
+        assert_eq!(tops, [0, 32, 64, 96]);
+        assert_close(&pixels, &reference(&picture, &info));
+    }
+
+    #[test]
+    fn unsupported_info_is_rejected() {
+        let info = ImageInfo::new((10, 10), ColorType::Unknown, crate::AlphaType::Premul, None);
+        assert!(render(&picture(), &info, &Options::default()).is_none());
+    }
+}
@@ -0,0 +1,435 @@
+//! `Serialize` and `Deserialize` implementations for value types that can not derive them.
+//!
+//! Plain structs like [`Point`] or [`Rect`] derive their implementations where they are
+//! defined.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+use skia_bindings as sb;
+
+use crate::{
+    AlphaType, BlendMode, Color, ColorSpace, ColorSpaceTransferFn, ColorType, CubicResampler,
+    FilterMode, FontStyle, ISize, ImageInfo, M44, Matrix, MipmapMode, Path, PathFillType, RRect,
+    Rect, SamplingOptions, TileMode, Vector, font_style::Slant, prelude::*, scalar,
+};
+
+/// Implements serialization of an enum of the bindings as the name of its variant.
+macro_rules! variant_names {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(match self {
+                    $($ty::$variant => stringify!($variant),)*
+                })
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let name = String::deserialize(deserializer)?;
+                match name.as_str() {
+                    $(stringify!($variant) => Ok($ty::$variant),)*
+                    _ => Err(D::Error::unknown_variant(&name, &[$(stringify!($variant)),*])),
+                }
+            }
+        }
+    };
+}
+
+variant_names!(AlphaType {
+    Unknown,
+    Opaque,
+    Premul,
+    Unpremul
+});
+
+variant_names!(BlendMode {
+    Clear,
+    Src,
+    Dst,
+    SrcOver,
+    DstOver,
+    SrcIn,
+    DstIn,
+    SrcOut,
+    DstOut,
+    SrcATop,
+    DstATop,
+    Xor,
+    Plus,
+    Modulate,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Multiply,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+});
+
+variant_names!(FilterMode { Nearest, Linear });
+variant_names!(MipmapMode {
+    None,
+    Nearest,
+    Linear
+});
+variant_names!(PathFillType {
+    Winding,
+    EvenOdd,
+    InverseWinding,
+    InverseEvenOdd
+});
+variant_names!(Slant {
+    Upright,
+    Italic,
+    Oblique
+});
+variant_names!(TileMode {
+    Clamp,
+    Repeat,
+    Mirror,
+    Decal
+});
+
+/// A hex string in the format `#AARRGGBB`.
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("#{:08X}", self.native()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        hex.strip_prefix('#')
+            .filter(|digits| digits.len() == 8 && digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .map(Color::from)
+            .ok_or_else(|| D::Error::custom("expected a color in the format #AARRGGBB"))
+    }
+}
+
+/// The 9 values of the matrix in row-major order.
+impl Serialize for Matrix {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut values = [0.0; 9];
+        self.get_9(&mut values);
+        values.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Matrix {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = <[scalar; 9]>::deserialize(deserializer)?;
+        let mut matrix = Matrix::default();
+        matrix.set_9(&values);
+        Ok(matrix)
+    }
+}
+
+/// The 16 values of the matrix in row-major order.
+impl Serialize for M44 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut values = [0.0; 16];
+        self.get_row_major(&mut values);
+        values.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for M44 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = <[scalar; 16]>::deserialize(deserializer)?;
+        Ok(M44::row_major(&values))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RRectRepr {
+    rect: Rect,
+    /// The radii of the upper-left, upper-right, lower-right and lower-left corners.
+    radii: [Vector; 4],
+}
+
+impl Serialize for RRect {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RRectRepr {
+            rect: *self.rect(),
+            radii: *self.radii_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RRect {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RRectRepr { rect, radii } = RRectRepr::deserialize(deserializer)?;
+        Ok(RRect::new_rect_radii(rect, &radii))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PathRepr {
+    fill_type: PathFillType,
+    /// The verbs and points of the path as SVG path data.
+    svg: String,
+}
+
+impl Serialize for Path {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PathRepr {
+            fill_type: self.fill_type(),
+            svg: self.to_svg(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Path {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let PathRepr { fill_type, svg } = PathRepr::deserialize(deserializer)?;
+        let mut path =
+            Path::from_svg(&svg).ok_or_else(|| D::Error::custom("invalid SVG path data"))?;
+        path.set_fill_type(fill_type);
+        Ok(path)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct FontStyleRepr {
+    weight: i32,
+    width: i32,
+    slant: Slant,
+}
+
+impl Serialize for FontStyle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FontStyleRepr {
+            weight: *self.weight(),
+            width: *self.width(),
+            slant: self.slant(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FontStyle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let FontStyleRepr {
+            weight,
+            width,
+            slant,
+        } = FontStyleRepr::deserialize(deserializer)?;
+        Ok(FontStyle::new(weight.into(), width.into(), slant))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SamplingOptionsRepr {
+    filter: FilterMode,
+    mipmap: MipmapMode,
+    cubic: Option<CubicResampler>,
+    max_aniso: i32,
+}
+
+impl Serialize for SamplingOptions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SamplingOptionsRepr {
+            filter: self.filter,
+            mipmap: self.mipmap,
+            cubic: self.use_cubic.then_some(self.cubic),
+            max_aniso: self.max_aniso,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SamplingOptions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = SamplingOptionsRepr::deserialize(deserializer)?;
+        Ok(SamplingOptions {
+            max_aniso: repr.max_aniso,
+            use_cubic: repr.cubic.is_some(),
+            cubic: repr.cubic.unwrap_or(CubicResampler { b: 0.0, c: 0.0 }),
+            filter: repr.filter,
+            mipmap: repr.mipmap,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ColorSpaceRepr {
+    transfer_fn: ColorSpaceTransferFn,
+    /// The 9 values of the matrix from the gamut to XYZ D50 in row-major order.
+    to_xyzd50: [f32; 9],
+}
+
+/// The transfer function and the gamut of the color space.
+///
+/// The values are the ones Skia stores for the color space. Skia may represent the same color
+/// space with slightly different values in other versions, so the format is not guaranteed to be
+/// stable between versions of Skia.
+impl Serialize for ColorSpace {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut to_xyzd50 = [0.0; 9];
+        unsafe { sb::C_SkColorSpace_toXYZD50(self.native(), to_xyzd50.as_mut_ptr()) };
+        ColorSpaceRepr {
+            transfer_fn: self.transfer_fn(),
+            to_xyzd50,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorSpace {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ColorSpaceRepr {
+            transfer_fn,
+            to_xyzd50,
+        } = ColorSpaceRepr::deserialize(deserializer)?;
+        ColorSpace::from_ptr(unsafe {
+            sb::C_SkColorSpace_MakeRGB(transfer_fn.native(), to_xyzd50.as_ptr())
+        })
+        .ok_or_else(|| D::Error::custom("invalid color space"))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ImageInfoRepr {
+    width: i32,
+    height: i32,
+    color_type: ColorType,
+    alpha_type: AlphaType,
+    color_space: Option<ColorSpace>,
+}
+
+impl Serialize for ImageInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ImageInfoRepr {
+            width: self.width(),
+            height: self.height(),
+            color_type: self.color_type(),
+            alpha_type: self.alpha_type(),
+            color_space: self.color_space(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ImageInfo {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ImageInfoRepr::deserialize(deserializer)?;
+        Ok(ImageInfo::new(
+            ISize::new(repr.width, repr.height),
+            repr.color_type,
+            repr.alpha_type,
+            repr.color_space,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Serialize, de::DeserializeOwned};
+
+    use crate::{
+        AlphaType, BlendMode, Color, Color4f, ColorSpace, ColorType, CubicResampler, FontStyle,
+        ImageInfo, M44, Matrix, Path, PathFillType, Point, RRect, Rect, SamplingOptions, TileMode,
+    };
+
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> (String, T) {
+        let json = serde_json::to_string(value).unwrap();
+        let value = serde_json::from_str(&json).unwrap();
+        (json, value)
+    }
+
+    #[test]
+    fn value_types() {
+        assert_eq!(
+            round_trip(&Color::RED),
+            (r##""#FFFF0000""##.into(), Color::RED)
+        );
+        let color = Color::from_argb(0x80, 0x0a, 0xbc, 0xde);
+        assert_eq!(round_trip(&color), (r##""#800ABCDE""##.into(), color));
+        assert!(serde_json::from_str::<Color>(r#""FF0000""#).is_err());
+        assert!(serde_json::from_str::<Color>("4294901760").is_err());
+        let color = Color4f::new(0.25, 0.5, 0.75, 1.0);
+        assert_eq!(round_trip(&color).1, color);
+        let point = Point::new(1.0, 2.0);
+        assert_eq!(round_trip(&point), (r#"{"x":1.0,"y":2.0}"#.into(), point));
+        let rect = Rect::new(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(round_trip(&rect).1, rect);
+        let rrect = RRect::new_rect_xy(Rect::new(0.0, 0.0, 10.0, 20.0), 2.0, 3.0);
+        assert_eq!(round_trip(&rrect).1, rrect);
+        assert_eq!(round_trip(&BlendMode::SrcOver).0, r#""SrcOver""#);
+        assert_eq!(round_trip(&TileMode::Decal).1, TileMode::Decal);
+        assert!(serde_json::from_str::<TileMode>(r#""Wrap""#).is_err());
+    }
+
+    #[test]
+    fn matrices() {
+        let matrix = Matrix::translate((10.0, 20.0));
+        let (json, read) = round_trip(&matrix);
+        assert_eq!(json, "[1.0,0.0,10.0,0.0,1.0,20.0,0.0,0.0,1.0]");
+        assert_eq!(read, matrix);
+        assert!(read.is_translate());
+
+        let m44 = M44::translate(1.0, 2.0, 3.0);
+        assert_eq!(round_trip(&m44).1, m44);
+    }
+
+    #[test]
+    fn composite_types() {
+        let mut path = Path::rect(Rect::new(0.0, 0.0, 10.0, 10.0), None);
+        path.set_fill_type(PathFillType::EvenOdd);
+        let (json, read) = round_trip(&path);
+        assert_eq!(
+            json,
+            format!(r#"{{"fill_type":"EvenOdd","svg":{:?}}}"#, path.to_svg())
+        );
+        assert_eq!(read.bounds(), path.bounds());
+        assert_eq!(read.fill_type(), PathFillType::EvenOdd);
+        assert!(
+            serde_json::from_str::<Path>(r#"{"fill_type":"Winding","svg":"M 0 0 X"}"#).is_err()
+        );
+
+        let style = FontStyle::bold_italic();
+        assert_eq!(round_trip(&style).1, style);
+
+        let sampling = SamplingOptions::from(CubicResampler::mitchell());
+        assert_eq!(round_trip(&sampling).1, sampling);
+        assert_eq!(
+            round_trip(&SamplingOptions::default()).0,
+            r#"{"filter":"Nearest","mipmap":"None","cubic":null,"max_aniso":0}"#
+        );
+
+        let info = ImageInfo::new(
+            (10, 20),
+            ColorType::RGBA8888,
+            AlphaType::Premul,
+            ColorSpace::new_srgb_linear(),
+        );
+        assert_eq!(round_trip(&info).1, info);
+        let (json, read) = round_trip(&ColorSpace::new_srgb_linear());
+        assert!(json.starts_with(
+            r#"{"transfer_fn":{"g":1.0,"a":1.0,"b":0.0,"c":0.0,"d":0.0,"e":0.0,"f":0.0},"to_xyzd50":["#
+        ));
+        assert_eq!(read, ColorSpace::new_srgb_linear());
+        let color_space = ColorSpace::new_srgb().with_color_spin();
+        assert_eq!(round_trip(&color_space).1, color_space);
+        assert!(
+            serde_json::from_str::<ColorSpace>(
+                r#"{"transfer_fn":{"g":1.0,"a":-1.0,"b":0.0,"c":0.0,"d":0.0,"e":0.0,"f":0.0},"to_xyzd50":[1.0,0.0,0.0,0.0,1.0,0.0,0.0,0.0,1.0]}"#
+            )
+            .is_err()
+        );
+        let info = ImageInfo::new_a8((3, 4));
+        assert_eq!(round_trip(&info).1, info);
+    }
+}
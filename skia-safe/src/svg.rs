@@ -1,6 +1,6 @@
 pub mod canvas;
 
-pub use self::canvas::Canvas;
+pub use self::canvas::{Canvas, WriterStream};
 
 #[cfg(feature = "svg")]
 pub use crate::modules::svg::*;
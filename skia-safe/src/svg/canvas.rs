@@ -1,15 +1,29 @@
-use std::{fmt, ops::Deref, pin::Pin, ptr};
+use std::{
+    fmt, io,
+    mem::ManuallyDrop,
+    ops::Deref,
+    pin::Pin,
+    ptr::{self, NonNull},
+};
 
-use skia_bindings::{self as sb, SkCanvas};
+use skia_bindings::{self as sb, SkCanvas, SkWStream};
 
-use crate::{Data, Rect, interop::DynamicMemoryWStream, prelude::*};
+use crate::{
+    Data, Rect,
+    interop::{DynamicMemoryWStream, RustWStream},
+    prelude::*,
+};
 
-pub struct Canvas {
+/// A canvas that records drawing commands as SVG.
+///
+/// By default, the SVG is buffered in memory and returned by [`Canvas::end()`]. A canvas created
+/// with [`Canvas::from_writer()`] streams the SVG to an [`io::Write`] instead.
+pub struct Canvas<Stream = DynamicMemoryWStream> {
     canvas: *mut SkCanvas,
-    stream: Pin<Box<DynamicMemoryWStream>>,
+    stream: Pin<Box<Stream>>,
 }
 
-impl Drop for Canvas {
+impl<Stream> Drop for Canvas<Stream> {
     fn drop(&mut self) {
         unsafe {
             sb::C_SkCanvas_delete(self.canvas);
@@ -17,7 +31,7 @@ impl Drop for Canvas {
     }
 }
 
-impl Deref for Canvas {
+impl<Stream> Deref for Canvas<Stream> {
     type Target = crate::Canvas;
 
     fn deref(&self) -> &Self::Target {
@@ -34,7 +48,7 @@ bitflags! {
     }
 }
 
-impl fmt::Debug for Canvas {
+impl<Stream: fmt::Debug> fmt::Debug for Canvas<Stream> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Canvas")
             .field(
@@ -76,6 +90,118 @@ impl Canvas {
     }
 }
 
+impl<'a> Canvas<WriterStream<'a>> {
+    /// Creates a new SVG canvas that writes the SVG to `writer` while it is drawn to.
+    ///
+    /// Only small parts of the document are buffered, so memory use does not depend on the
+    /// number of drawing commands. Use a buffered writer, because the SVG is written in small
+    /// pieces.
+    pub fn from_writer(
+        bounds: impl AsRef<Rect>,
+        flags: impl Into<Option<Flags>>,
+        writer: impl io::Write + 'a,
+    ) -> Self {
+        let bounds = bounds.as_ref();
+        let flags = flags.into().unwrap_or_default();
+        let mut stream = Box::pin(WriterStream::new(writer));
+        let canvas =
+            unsafe { sb::C_SkSVGCanvas_Make(bounds.native(), stream.stream_mut(), flags.bits()) };
+        Canvas { canvas, stream }
+    }
+
+    /// Ends the Canvas drawing, writes the remaining SVG and flushes the writer.
+    ///
+    /// Returns the first error the writer reported. After an error, nothing more is written.
+    pub fn end(mut self) -> io::Result<()> {
+        // Deleting the canvas writes the closing tags.
+        unsafe {
+            sb::C_SkCanvas_delete(self.canvas);
+        }
+        self.canvas = ptr::null_mut();
+        let sink = self.stream.sink();
+        io::Write::flush(sink)?;
+        match sink.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The stream of a [`Canvas`] that writes to an [`io::Write`].
+pub struct WriterStream<'a> {
+    stream: ManuallyDrop<RustWStream<'a>>,
+    // Owned, referenced by `stream`.
+    sink: NonNull<Sink<'a>>,
+}
+
+impl Drop for WriterStream<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            ManuallyDrop::drop(&mut self.stream);
+            drop(Box::from_raw(self.sink.as_ptr()));
+        }
+    }
+}
+
+impl fmt::Debug for WriterStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriterStream").finish()
+    }
+}
+
+impl<'a> WriterStream<'a> {
+    fn new(writer: impl io::Write + 'a) -> Self {
+        let sink = NonNull::from(Box::leak(Box::new(Sink {
+            writer: Box::new(writer),
+            error: None,
+        })));
+        let stream = RustWStream::new(unsafe { &mut *sink.as_ptr() });
+        Self {
+            stream: ManuallyDrop::new(stream),
+            sink,
+        }
+    }
+
+    fn stream_mut(&mut self) -> &mut SkWStream {
+        self.stream.stream_mut()
+    }
+
+    fn sink(&mut self) -> &mut Sink<'a> {
+        unsafe { &mut *self.sink.as_ptr() }
+    }
+}
+
+/// Remembers the first error of the writer, because `SkWStream` can only report failure.
+struct Sink<'a> {
+    writer: Box<dyn io::Write + 'a>,
+    error: Option<io::Error>,
+}
+
+impl io::Write for Sink<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.error.is_some() {
+            return Err(io::ErrorKind::Other.into());
+        }
+        match self.writer.write_all(buf) {
+            Ok(()) => Ok(buf.len()),
+            Err(error) => {
+                self.error = Some(error);
+                Err(io::ErrorKind::Other.into())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.error.is_some() {
+            return Ok(());
+        }
+        if let Err(error) = self.writer.flush() {
+            self.error = Some(error);
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Canvas;
@@ -95,6 +221,38 @@ mod tests {
         assert!(contents.contains(r#"</svg>"#));
     }
 
+    #[test]
+    fn test_svg_to_writer() {
+        use crate::Paint;
+
+        let mut svg = Vec::new();
+        let canvas = Canvas::from_writer(Rect::from_size((20, 20)), None, &mut svg);
+        canvas.draw_circle((10, 10), 10.0, &Paint::default());
+        canvas.end().unwrap();
+        let contents = String::from_utf8(svg).unwrap();
+        assert!(contents.contains(r#"<ellipse cx="10" cy="10" rx="10" ry="10"/>"#));
+        assert!(contents.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_svg_to_failing_writer() {
+        struct Failing;
+
+        impl std::io::Write for Failing {
+            fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::StorageFull.into())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let canvas = Canvas::from_writer(Rect::from_size((20, 20)), None, Failing);
+        let error = canvas.end().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::StorageFull);
+    }
+
     #[test]
     fn test_svg_without_ending() {
         use crate::Paint;
@@ -1,12 +1,18 @@
 mod camera;
+mod canvas_backend;
 mod custom_typeface;
 mod null_canvas;
+mod nway_canvas;
 mod ordered_font_mgr;
+mod paint_filter_canvas;
 pub mod parse_path;
 pub mod shadow_utils;
 pub mod text_utils;
 
 pub use camera::*;
+pub use canvas_backend::*;
 pub use custom_typeface::*;
 pub use null_canvas::*;
+pub use nway_canvas::*;
 pub use ordered_font_mgr::*;
+pub use paint_filter_canvas::*;
@@ -0,0 +1,967 @@
+use std::{borrow::Cow, ffi::CStr, mem, os::raw};
+
+use skia_bindings::{
+    self as sb, RustCanvas_Param, SkBlendMode, SkCanvas, SkClipOp, SkData, SkDrawable, SkImage,
+    SkM44, SkMatrix, SkPaint, SkPath, SkPicture, SkPoint, SkRRect, SkRect, SkRegion,
+    SkSamplingOptions, SkShader, SkTextBlob, SkVertices, TraitObject,
+};
+
+use crate::{
+    BlendMode, Canvas, ClipOp, Data, Drawable, IRect, ISize, Image, M44, Matrix, OwnedCanvas,
+    Paint, Path, Picture, Point, RRect, Rect, Region, SamplingOptions, Shader, TextBlob, Vertices,
+    canvas::{PointMode, SaveLayerFlags, SrcRectConstraint},
+    prelude::*,
+    scalar,
+};
+
+/// Receives the drawing calls of a [`Canvas`] created with [`Canvas::from_backend()`].
+///
+/// This is the Rust counterpart of subclassing `SkNoDrawCanvas`, and can be used to implement
+/// exporters to custom formats or to instrument drawing code.
+///
+/// All functions do nothing by default. The canvas keeps track of the matrix and the clip, which
+/// can be queried through the [`CanvasState`] passed to every call. Draws that are not forwarded
+/// with their arguments, like patches, atlases, lattices, meshes, or shadows, are reported to
+/// [`Self::on_unsupported_draw()`].
+///
+/// Text drawn with [`Canvas::draw_str()`], [`Canvas::draw_glyphs_at()`] and similar functions
+/// is forwarded to [`Self::on_draw_text_blob()`].
+#[allow(unused_variables)]
+pub trait CanvasBackend {
+    /// Saves are deferred until the matrix or the clip is modified, so saves that are restored
+    /// without any changes are not reported, and neither are their restores.
+    fn will_save(&mut self, state: &CanvasState) {}
+
+    /// Called by [`Canvas::save_layer()`] and its variants. The layer is not allocated.
+    fn on_save_layer(
+        &mut self,
+        state: &CanvasState,
+        bounds: Option<&Rect>,
+        paint: Option<&Paint>,
+        flags: SaveLayerFlags,
+    ) {
+    }
+
+    fn will_restore(&mut self, state: &CanvasState) {}
+
+    /// Called after `matrix` was concatenated to the current matrix.
+    fn did_concat(&mut self, state: &CanvasState, matrix: &M44) {}
+
+    /// Called after the current matrix was replaced with `matrix`.
+    fn did_set_matrix(&mut self, state: &CanvasState, matrix: &M44) {}
+
+    /// Called before the clip is modified.
+    fn on_clip_rect(&mut self, state: &CanvasState, rect: &Rect, op: ClipOp, anti_alias: bool) {}
+
+    fn on_clip_rrect(&mut self, state: &CanvasState, rrect: &RRect, op: ClipOp, anti_alias: bool) {}
+
+    fn on_clip_path(&mut self, state: &CanvasState, path: &Path, op: ClipOp, anti_alias: bool) {}
+
+    fn on_clip_shader(&mut self, state: &CanvasState, shader: &Shader, op: ClipOp) {}
+
+    /// `device_region` is in device coordinates.
+    fn on_clip_region(&mut self, state: &CanvasState, device_region: &Region, op: ClipOp) {}
+
+    fn on_draw_paint(&mut self, state: &CanvasState, paint: &Paint) {}
+
+    fn on_draw_points(
+        &mut self,
+        state: &CanvasState,
+        mode: PointMode,
+        points: &[Point],
+        paint: &Paint,
+    ) {
+    }
+
+    fn on_draw_rect(&mut self, state: &CanvasState, rect: &Rect, paint: &Paint) {}
+
+    fn on_draw_rrect(&mut self, state: &CanvasState, rrect: &RRect, paint: &Paint) {}
+
+    fn on_draw_drrect(&mut self, state: &CanvasState, outer: &RRect, inner: &RRect, paint: &Paint) {
+    }
+
+    fn on_draw_oval(&mut self, state: &CanvasState, oval: &Rect, paint: &Paint) {}
+
+    fn on_draw_arc(
+        &mut self,
+        state: &CanvasState,
+        oval: &Rect,
+        start_angle: scalar,
+        sweep_angle: scalar,
+        use_center: bool,
+        paint: &Paint,
+    ) {
+    }
+
+    fn on_draw_path(&mut self, state: &CanvasState, path: &Path, paint: &Paint) {}
+
+    fn on_draw_region(&mut self, state: &CanvasState, region: &Region, paint: &Paint) {}
+
+    fn on_draw_text_blob(
+        &mut self,
+        state: &CanvasState,
+        blob: &TextBlob,
+        origin: Point,
+        paint: &Paint,
+    ) {
+    }
+
+    fn on_draw_image(
+        &mut self,
+        state: &CanvasState,
+        image: &Image,
+        left_top: Point,
+        sampling: &SamplingOptions,
+        paint: Option<&Paint>,
+    ) {
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_draw_image_rect(
+        &mut self,
+        state: &CanvasState,
+        image: &Image,
+        src: &Rect,
+        dst: &Rect,
+        sampling: &SamplingOptions,
+        paint: Option<&Paint>,
+        constraint: SrcRectConstraint,
+    ) {
+    }
+
+    fn on_draw_vertices(
+        &mut self,
+        state: &CanvasState,
+        vertices: &Vertices,
+        mode: BlendMode,
+        paint: &Paint,
+    ) {
+    }
+
+    fn on_draw_annotation(
+        &mut self,
+        state: &CanvasState,
+        rect: &Rect,
+        key: &str,
+        value: Option<&Data>,
+    ) {
+    }
+
+    /// Returns `true` if the picture was handled. If `false` is returned, the picture is played
+    /// back into the canvas, which forwards its content to this backend.
+    ///
+    /// Pictures with only a few operations are always played back.
+    fn on_draw_picture(
+        &mut self,
+        state: &CanvasState,
+        picture: &Picture,
+        matrix: Option<&Matrix>,
+        paint: Option<&Paint>,
+    ) -> bool {
+        false
+    }
+
+    /// Returns `true` if the drawable was handled. If `false` is returned, the drawable is drawn
+    /// into the canvas, which forwards its content to this backend.
+    fn on_draw_drawable(
+        &mut self,
+        state: &CanvasState,
+        drawable: &Drawable,
+        matrix: Option<&Matrix>,
+    ) -> bool {
+        false
+    }
+
+    /// Called for draws that are not forwarded with their arguments. Nothing is drawn.
+    fn on_unsupported_draw(&mut self, state: &CanvasState, draw: UnsupportedDraw) {}
+}
+
+impl<T: CanvasBackend + ?Sized> CanvasBackend for &mut T {
+    fn will_save(&mut self, state: &CanvasState) {
+        (**self).will_save(state)
+    }
+
+    fn on_save_layer(
+        &mut self,
+        state: &CanvasState,
+        bounds: Option<&Rect>,
+        paint: Option<&Paint>,
+        flags: SaveLayerFlags,
+    ) {
+        (**self).on_save_layer(state, bounds, paint, flags)
+    }
+
+    fn will_restore(&mut self, state: &CanvasState) {
+        (**self).will_restore(state)
+    }
+
+    fn did_concat(&mut self, state: &CanvasState, matrix: &M44) {
+        (**self).did_concat(state, matrix)
+    }
+
+    fn did_set_matrix(&mut self, state: &CanvasState, matrix: &M44) {
+        (**self).did_set_matrix(state, matrix)
+    }
+
+    fn on_clip_rect(&mut self, state: &CanvasState, rect: &Rect, op: ClipOp, anti_alias: bool) {
+        (**self).on_clip_rect(state, rect, op, anti_alias)
+    }
+
+    fn on_clip_rrect(&mut self, state: &CanvasState, rrect: &RRect, op: ClipOp, anti_alias: bool) {
+        (**self).on_clip_rrect(state, rrect, op, anti_alias)
+    }
+
+    fn on_clip_path(&mut self, state: &CanvasState, path: &Path, op: ClipOp, anti_alias: bool) {
+        (**self).on_clip_path(state, path, op, anti_alias)
+    }
+
+    fn on_clip_shader(&mut self, state: &CanvasState, shader: &Shader, op: ClipOp) {
+        (**self).on_clip_shader(state, shader, op)
+    }
+
+    fn on_clip_region(&mut self, state: &CanvasState, device_region: &Region, op: ClipOp) {
+        (**self).on_clip_region(state, device_region, op)
+    }
+
+    fn on_draw_paint(&mut self, state: &CanvasState, paint: &Paint) {
+        (**self).on_draw_paint(state, paint)
+    }
+
+    fn on_draw_points(
+        &mut self,
+        state: &CanvasState,
+        mode: PointMode,
+        points: &[Point],
+        paint: &Paint,
+    ) {
+        (**self).on_draw_points(state, mode, points, paint)
+    }
+
+    fn on_draw_rect(&mut self, state: &CanvasState, rect: &Rect, paint: &Paint) {
+        (**self).on_draw_rect(state, rect, paint)
+    }
+
+    fn on_draw_rrect(&mut self, state: &CanvasState, rrect: &RRect, paint: &Paint) {
+        (**self).on_draw_rrect(state, rrect, paint)
+    }
+
+    fn on_draw_drrect(&mut self, state: &CanvasState, outer: &RRect, inner: &RRect, paint: &Paint) {
+        (**self).on_draw_drrect(state, outer, inner, paint)
+    }
+
+    fn on_draw_oval(&mut self, state: &CanvasState, oval: &Rect, paint: &Paint) {
+        (**self).on_draw_oval(state, oval, paint)
+    }
+
+    fn on_draw_arc(
+        &mut self,
+        state: &CanvasState,
+        oval: &Rect,
+        start_angle: scalar,
+        sweep_angle: scalar,
+        use_center: bool,
+        paint: &Paint,
+    ) {
+        (**self).on_draw_arc(state, oval, start_angle, sweep_angle, use_center, paint)
+    }
+
+    fn on_draw_path(&mut self, state: &CanvasState, path: &Path, paint: &Paint) {
+        (**self).on_draw_path(state, path, paint)
+    }
+
+    fn on_draw_region(&mut self, state: &CanvasState, region: &Region, paint: &Paint) {
+        (**self).on_draw_region(state, region, paint)
+    }
+
+    fn on_draw_text_blob(
+        &mut self,
+        state: &CanvasState,
+        blob: &TextBlob,
+        origin: Point,
+        paint: &Paint,
+    ) {
+        (**self).on_draw_text_blob(state, blob, origin, paint)
+    }
+
+    fn on_draw_image(
+        &mut self,
+        state: &CanvasState,
+        image: &Image,
+        left_top: Point,
+        sampling: &SamplingOptions,
+        paint: Option<&Paint>,
+    ) {
+        (**self).on_draw_image(state, image, left_top, sampling, paint)
+    }
+
+    fn on_draw_image_rect(
+        &mut self,
+        state: &CanvasState,
+        image: &Image,
+        src: &Rect,
+        dst: &Rect,
+        sampling: &SamplingOptions,
+        paint: Option<&Paint>,
+        constraint: SrcRectConstraint,
+    ) {
+        (**self).on_draw_image_rect(state, image, src, dst, sampling, paint, constraint)
+    }
+
+    fn on_draw_vertices(
+        &mut self,
+        state: &CanvasState,
+        vertices: &Vertices,
+        mode: BlendMode,
+        paint: &Paint,
+    ) {
+        (**self).on_draw_vertices(state, vertices, mode, paint)
+    }
+
+    fn on_draw_annotation(
+        &mut self,
+        state: &CanvasState,
+        rect: &Rect,
+        key: &str,
+        value: Option<&Data>,
+    ) {
+        (**self).on_draw_annotation(state, rect, key, value)
+    }
+
+    fn on_draw_picture(
+        &mut self,
+        state: &CanvasState,
+        picture: &Picture,
+        matrix: Option<&Matrix>,
+        paint: Option<&Paint>,
+    ) -> bool {
+        (**self).on_draw_picture(state, picture, matrix, paint)
+    }
+
+    fn on_draw_drawable(
+        &mut self,
+        state: &CanvasState,
+        drawable: &Drawable,
+        matrix: Option<&Matrix>,
+    ) -> bool {
+        (**self).on_draw_drawable(state, drawable, matrix)
+    }
+
+    fn on_unsupported_draw(&mut self, state: &CanvasState, draw: UnsupportedDraw) {
+        (**self).on_unsupported_draw(state, draw)
+    }
+}
+
+/// The kinds of draws that are reported to [`CanvasBackend::on_unsupported_draw()`].
+///
+/// [`UnsupportedDraw::ImageLattice`] is also reported for [`Canvas::draw_image_nine()`], and
+/// [`UnsupportedDraw::Behind`] for `SkCanvas::drawBehind()`, which is used by some image filters.
+pub type UnsupportedDraw = sb::RustCanvas_UnsupportedDraw;
+variant_name!(UnsupportedDraw::EdgeAAImageSet);
+
+/// Read-only access to the state of the [`Canvas`] a [`CanvasBackend`] receives calls from.
+#[derive(Debug)]
+pub struct CanvasState<'a>(&'a Canvas);
+
+impl CanvasState<'_> {
+    /// See [`Canvas::local_to_device()`].
+    pub fn local_to_device(&self) -> M44 {
+        self.0.local_to_device()
+    }
+
+    /// See [`Canvas::local_to_device_as_3x3()`].
+    pub fn local_to_device_as_3x3(&self) -> Matrix {
+        self.0.local_to_device_as_3x3()
+    }
+
+    /// See [`Canvas::local_clip_bounds()`].
+    pub fn local_clip_bounds(&self) -> Option<Rect> {
+        self.0.local_clip_bounds()
+    }
+
+    /// See [`Canvas::device_clip_bounds()`].
+    pub fn device_clip_bounds(&self) -> Option<IRect> {
+        self.0.device_clip_bounds()
+    }
+
+    /// See [`Canvas::save_count()`].
+    pub fn save_count(&self) -> usize {
+        self.0.save_count()
+    }
+
+    /// See [`Canvas::base_layer_size()`].
+    pub fn base_layer_size(&self) -> ISize {
+        self.0.base_layer_size()
+    }
+}
+
+impl Canvas {
+    /// Creates a [`Canvas`] of the given size that forwards all drawing calls to `backend`.
+    ///
+    /// The backend is dropped when the canvas is dropped. To access the backend afterwards, pass
+    /// a mutable reference.
+    pub fn from_backend<'a>(
+        size: impl Into<ISize>,
+        backend: impl CanvasBackend + 'a,
+    ) -> OwnedCanvas<'a> {
+        Self::from_backend_with_bounds(IRect::from_size(size), backend)
+    }
+
+    /// Creates a [`Canvas`] with the given device bounds that forwards all drawing calls to
+    /// `backend`. Unlike [`Self::from_backend()`], the device may start at a negative origin.
+    pub fn from_backend_with_bounds<'a>(
+        bounds: impl AsRef<IRect>,
+        backend: impl CanvasBackend + 'a,
+    ) -> OwnedCanvas<'a> {
+        let bounds = bounds.as_ref();
+        let backend: Box<dyn CanvasBackend + 'a> = Box::new(backend);
+        let param = RustCanvas_Param {
+            trait_: unsafe { mem::transmute::<Box<dyn CanvasBackend + 'a>, TraitObject>(backend) },
+            drop: Some(drop),
+            willSave: Some(will_save),
+            saveLayer: Some(save_layer),
+            willRestore: Some(will_restore),
+            didConcat: Some(did_concat),
+            didSetMatrix: Some(did_set_matrix),
+            clipRect: Some(clip_rect),
+            clipRRect: Some(clip_rrect),
+            clipPath: Some(clip_path),
+            clipShader: Some(clip_shader),
+            clipRegion: Some(clip_region),
+            drawPaint: Some(draw_paint),
+            drawPoints: Some(draw_points),
+            drawRect: Some(draw_rect),
+            drawRRect: Some(draw_rrect),
+            drawDRRect: Some(draw_drrect),
+            drawOval: Some(draw_oval),
+            drawArc: Some(draw_arc),
+            drawPath: Some(draw_path),
+            drawRegion: Some(draw_region),
+            drawTextBlob: Some(draw_text_blob),
+            drawImage: Some(draw_image),
+            drawImageRect: Some(draw_image_rect),
+            drawVertices: Some(draw_vertices),
+            drawAnnotation: Some(draw_annotation),
+            drawPicture: Some(draw_picture),
+            drawDrawable: Some(draw_drawable),
+            unsupportedDraw: Some(unsupported_draw),
+        };
+
+        let canvas =
+            Canvas::own_from_native_ptr(unsafe { sb::C_RustCanvas_new(bounds.native(), &param) })
+                .unwrap();
+        return canvas;
+
+        extern "C" fn drop(backend: TraitObject) {
+            mem::drop(unsafe { mem::transmute::<TraitObject, Box<dyn CanvasBackend>>(backend) });
+        }
+
+        extern "C" fn will_save(backend: TraitObject, canvas: *const SkCanvas) {
+            with_backend(backend, canvas, |b, s| b.will_save(s))
+        }
+
+        extern "C" fn save_layer(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            bounds: *const SkRect,
+            paint: *const SkPaint,
+            flags: sb::SkCanvas_SaveLayerFlags,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_save_layer(
+                    s,
+                    unsafe { bounds.as_ref() }.map(Rect::from_native_ref),
+                    unsafe { paint.as_ref() }.map(Paint::from_native_ref),
+                    SaveLayerFlags::from_bits_truncate(flags),
+                )
+            })
+        }
+
+        extern "C" fn will_restore(backend: TraitObject, canvas: *const SkCanvas) {
+            with_backend(backend, canvas, |b, s| b.will_restore(s))
+        }
+
+        extern "C" fn did_concat(backend: TraitObject, canvas: *const SkCanvas, m: *const SkM44) {
+            with_backend(backend, canvas, |b, s| {
+                b.did_concat(s, M44::from_native_ref(unsafe { &*m }))
+            })
+        }
+
+        extern "C" fn did_set_matrix(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            m: *const SkM44,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.did_set_matrix(s, M44::from_native_ref(unsafe { &*m }))
+            })
+        }
+
+        extern "C" fn clip_rect(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            rect: *const SkRect,
+            op: SkClipOp,
+            anti_alias: bool,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_clip_rect(s, Rect::from_native_ref(unsafe { &*rect }), op, anti_alias)
+            })
+        }
+
+        extern "C" fn clip_rrect(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            rrect: *const SkRRect,
+            op: SkClipOp,
+            anti_alias: bool,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_clip_rrect(
+                    s,
+                    RRect::from_native_ref(unsafe { &*rrect }),
+                    op,
+                    anti_alias,
+                )
+            })
+        }
+
+        extern "C" fn clip_path(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            path: *const SkPath,
+            op: SkClipOp,
+            anti_alias: bool,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_clip_path(s, Path::from_native_ref(unsafe { &*path }), op, anti_alias)
+            })
+        }
+
+        extern "C" fn clip_shader(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            shader: *mut SkShader,
+            op: SkClipOp,
+        ) {
+            if let Some(shader) = Shader::from_unshared_ptr(shader) {
+                with_backend(backend, canvas, |b, s| b.on_clip_shader(s, &shader, op))
+            }
+        }
+
+        extern "C" fn clip_region(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            region: *const SkRegion,
+            op: SkClipOp,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_clip_region(s, Region::from_native_ref(unsafe { &*region }), op)
+            })
+        }
+
+        extern "C" fn draw_paint(backend: TraitObject, canvas: *const SkCanvas, p: *const SkPaint) {
+            with_backend(backend, canvas, |b, s| b.on_draw_paint(s, paint(p)))
+        }
+
+        extern "C" fn draw_points(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            mode: PointMode,
+            points: *const SkPoint,
+            count: usize,
+            p: *const SkPaint,
+        ) {
+            let points: &[Point] = unsafe { safer::from_raw_parts(points as *const Point, count) };
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_points(s, mode, points, paint(p))
+            })
+        }
+
+        extern "C" fn draw_rect(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            rect: *const SkRect,
+            p: *const SkPaint,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_rect(s, Rect::from_native_ref(unsafe { &*rect }), paint(p))
+            })
+        }
+
+        extern "C" fn draw_rrect(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            rrect: *const SkRRect,
+            p: *const SkPaint,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_rrect(s, RRect::from_native_ref(unsafe { &*rrect }), paint(p))
+            })
+        }
+
+        extern "C" fn draw_drrect(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            outer: *const SkRRect,
+            inner: *const SkRRect,
+            p: *const SkPaint,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_drrect(
+                    s,
+                    RRect::from_native_ref(unsafe { &*outer }),
+                    RRect::from_native_ref(unsafe { &*inner }),
+                    paint(p),
+                )
+            })
+        }
+
+        extern "C" fn draw_oval(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            oval: *const SkRect,
+            p: *const SkPaint,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_oval(s, Rect::from_native_ref(unsafe { &*oval }), paint(p))
+            })
+        }
+
+        extern "C" fn draw_arc(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            oval: *const SkRect,
+            start_angle: scalar,
+            sweep_angle: scalar,
+            use_center: bool,
+            p: *const SkPaint,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_arc(
+                    s,
+                    Rect::from_native_ref(unsafe { &*oval }),
+                    start_angle,
+                    sweep_angle,
+                    use_center,
+                    paint(p),
+                )
+            })
+        }
+
+        extern "C" fn draw_path(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            path: *const SkPath,
+            p: *const SkPaint,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_path(s, Path::from_native_ref(unsafe { &*path }), paint(p))
+            })
+        }
+
+        extern "C" fn draw_region(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            region: *const SkRegion,
+            p: *const SkPaint,
+        ) {
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_region(s, Region::from_native_ref(unsafe { &*region }), paint(p))
+            })
+        }
+
+        extern "C" fn draw_text_blob(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            blob: *const SkTextBlob,
+            x: scalar,
+            y: scalar,
+            p: *const SkPaint,
+        ) {
+            if let Some(blob) = TextBlob::from_unshared_ptr(blob as *mut _) {
+                with_backend(backend, canvas, |b, s| {
+                    b.on_draw_text_blob(s, &blob, Point::new(x, y), paint(p))
+                })
+            }
+        }
+
+        extern "C" fn draw_image(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            image: *const SkImage,
+            x: scalar,
+            y: scalar,
+            sampling: *const SkSamplingOptions,
+            p: *const SkPaint,
+        ) {
+            if let Some(image) = Image::from_unshared_ptr(image as *mut _) {
+                with_backend(backend, canvas, |b, s| {
+                    b.on_draw_image(
+                        s,
+                        &image,
+                        Point::new(x, y),
+                        SamplingOptions::from_native_ref(unsafe { &*sampling }),
+                        optional_paint(p),
+                    )
+                })
+            }
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        extern "C" fn draw_image_rect(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            image: *const SkImage,
+            src: *const SkRect,
+            dst: *const SkRect,
+            sampling: *const SkSamplingOptions,
+            p: *const SkPaint,
+            constraint: SrcRectConstraint,
+        ) {
+            if let Some(image) = Image::from_unshared_ptr(image as *mut _) {
+                with_backend(backend, canvas, |b, s| {
+                    b.on_draw_image_rect(
+                        s,
+                        &image,
+                        Rect::from_native_ref(unsafe { &*src }),
+                        Rect::from_native_ref(unsafe { &*dst }),
+                        SamplingOptions::from_native_ref(unsafe { &*sampling }),
+                        optional_paint(p),
+                        constraint,
+                    )
+                })
+            }
+        }
+
+        extern "C" fn draw_vertices(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            vertices: *const SkVertices,
+            mode: SkBlendMode,
+            p: *const SkPaint,
+        ) {
+            if let Some(vertices) = Vertices::from_unshared_ptr(vertices as *mut _) {
+                with_backend(backend, canvas, |b, s| {
+                    b.on_draw_vertices(s, &vertices, mode, paint(p))
+                })
+            }
+        }
+
+        extern "C" fn draw_annotation(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            rect: *const SkRect,
+            key: *const raw::c_char,
+            value: *mut SkData,
+        ) {
+            let key = unsafe { uncstr(key) };
+            let value = Data::from_unshared_ptr(value);
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_annotation(
+                    s,
+                    Rect::from_native_ref(unsafe { &*rect }),
+                    &key,
+                    value.as_ref(),
+                )
+            })
+        }
+
+        extern "C" fn draw_picture(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            picture: *const SkPicture,
+            matrix: *const SkMatrix,
+            p: *const SkPaint,
+        ) -> bool {
+            let Some(picture) = Picture::from_unshared_ptr(picture as *mut _) else {
+                return true;
+            };
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_picture(
+                    s,
+                    &picture,
+                    unsafe { matrix.as_ref() }.map(Matrix::from_native_ref),
+                    optional_paint(p),
+                )
+            })
+        }
+
+        extern "C" fn draw_drawable(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            drawable: *mut SkDrawable,
+            matrix: *const SkMatrix,
+        ) -> bool {
+            let Some(drawable) = Drawable::from_unshared_ptr(drawable) else {
+                return true;
+            };
+            with_backend(backend, canvas, |b, s| {
+                b.on_draw_drawable(
+                    s,
+                    &drawable,
+                    unsafe { matrix.as_ref() }.map(Matrix::from_native_ref),
+                )
+            })
+        }
+
+        extern "C" fn unsupported_draw(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            draw: UnsupportedDraw,
+        ) {
+            with_backend(backend, canvas, |b, s| b.on_unsupported_draw(s, draw))
+        }
+
+        fn with_backend<R>(
+            backend: TraitObject,
+            canvas: *const SkCanvas,
+            f: impl FnOnce(&mut dyn CanvasBackend, &CanvasState) -> R,
+        ) -> R {
+            let backend = unsafe { mem::transmute::<TraitObject, &mut dyn CanvasBackend>(backend) };
+            let state = CanvasState(Canvas::borrow_from_native(unsafe { &*canvas }));
+            f(backend, &state)
+        }
+
+        fn paint<'a>(paint: *const SkPaint) -> &'a Paint {
+            Paint::from_native_ref(unsafe { &*paint })
+        }
+
+        fn optional_paint<'a>(paint: *const SkPaint) -> Option<&'a Paint> {
+            unsafe { paint.as_ref() }.map(Paint::from_native_ref)
+        }
+
+        unsafe fn uncstr(ptr: *const raw::c_char) -> Cow<'static, str> {
+            if !ptr.is_null() {
+                return unsafe { CStr::from_ptr(ptr).to_string_lossy() };
+            }
+            "".into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CanvasBackend, CanvasState, UnsupportedDraw};
+    use crate::{
+        BlendMode, Canvas, ClipOp, M44, Paint, Path, PictureRecorder, Point, Rect,
+        canvas::SaveLayerFlags,
+    };
+
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    impl CanvasBackend for Log {
+        fn will_save(&mut self, state: &CanvasState) {
+            self.0.push(format!("save {}", state.save_count()));
+        }
+
+        fn on_save_layer(
+            &mut self,
+            _state: &CanvasState,
+            bounds: Option<&Rect>,
+            _paint: Option<&Paint>,
+            _flags: SaveLayerFlags,
+        ) {
+            self.0
+                .push(format!("save_layer {:?}", bounds.map(|b| b.width())));
+        }
+
+        fn will_restore(&mut self, _state: &CanvasState) {
+            self.0.push("restore".into());
+        }
+
+        fn did_concat(&mut self, _state: &CanvasState, matrix: &M44) {
+            self.0.push(format!("concat {}", matrix.row(0).w));
+        }
+
+        fn on_clip_rect(&mut self, _state: &CanvasState, rect: &Rect, op: ClipOp, _aa: bool) {
+            self.0.push(format!("clip_rect {} {op:?}", rect.width()));
+        }
+
+        fn on_draw_rect(&mut self, state: &CanvasState, rect: &Rect, _paint: &Paint) {
+            let device = state.local_to_device_as_3x3().map_rect(rect).0;
+            self.0.push(format!("rect {} {}", device.left, device.top));
+        }
+
+        fn on_draw_path(&mut self, _state: &CanvasState, path: &Path, _paint: &Paint) {
+            self.0.push(format!("path {}", path.count_points()));
+        }
+
+        fn on_draw_points(
+            &mut self,
+            _state: &CanvasState,
+            _mode: crate::canvas::PointMode,
+            points: &[Point],
+            _paint: &Paint,
+        ) {
+            self.0.push(format!("points {}", points.len()));
+        }
+
+        fn on_unsupported_draw(&mut self, _state: &CanvasState, draw: UnsupportedDraw) {
+            self.0.push(format!("unsupported {draw:?}"));
+        }
+    }
+
+    #[test]
+    fn forwards_drawing_calls() {
+        let mut log = Log::default();
+        {
+            let canvas = Canvas::from_backend((100, 100), &mut log);
+            let paint = Paint::default();
+            canvas.save();
+            canvas.translate((10.0, 0.0));
+            canvas.clip_rect(Rect::new(0.0, 0.0, 50.0, 50.0), None, None);
+            canvas.draw_rect(Rect::new(1.0, 2.0, 3.0, 4.0), &paint);
+            canvas.restore();
+            canvas.save_layer(&Default::default());
+            canvas.draw_path(&Path::circle((5.0, 5.0), 5.0, None), &paint);
+            canvas.draw_points(
+                crate::canvas::PointMode::Points,
+                &[Point::new(1.0, 1.0), Point::new(2.0, 2.0)],
+                &paint,
+            );
+            canvas.restore();
+        }
+        let log: Vec<&str> = log.0.iter().map(|s| s.as_str()).collect();
+        assert_eq!(
+            log[..5],
+            [
+                "save 2",
+                "concat 10",
+                "clip_rect 50 Intersect",
+                "rect 11 2",
+                "restore"
+            ]
+        );
+        assert_eq!(log[5], "save_layer None");
+        assert!(log[6].starts_with("path "));
+        assert_eq!(log[7..], ["points 2", "restore"]);
+    }
+
+    #[test]
+    fn plays_back_pictures() {
+        let mut recorder = PictureRecorder::new();
+        let recording = recorder.begin_recording(Rect::new(0.0, 0.0, 10.0, 10.0), false);
+        recording.draw_rect(Rect::new(1.0, 1.0, 2.0, 2.0), &Paint::default());
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+        let mut log = Log::default();
+        {
+            let canvas = Canvas::from_backend((10, 10), &mut log);
+            canvas.translate((5.0, 5.0));
+            canvas.draw_picture(&picture, None, None);
+        }
+        assert!(log.0.contains(&"rect 6 6".to_string()));
+    }
+
+    #[test]
+    fn reports_unsupported_draws() {
+        let mut log = Log::default();
+        {
+            let canvas = Canvas::from_backend((10, 10), &mut log);
+            let cubics = [Point::new(1.0, 1.0); 12];
+            canvas.draw_patch(&cubics, None, None, BlendMode::SrcOver, &Paint::default());
+        }
+        assert_eq!(log.0, ["unsupported Patch"]);
+    }
+}
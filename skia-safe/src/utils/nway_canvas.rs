@@ -0,0 +1,78 @@
+use std::{fmt, ops::Deref};
+
+use skia_bindings as sb;
+
+use crate::{Canvas, ISize, OwnedCanvas};
+
+/// A [`Canvas`] that forwards all drawing calls to a number of other canvases, for example to
+/// draw to the screen and to a document at the same time.
+///
+/// The canvases are not owned and must outlive the [`NWayCanvas`].
+pub struct NWayCanvas<'a>(OwnedCanvas<'a>);
+
+impl Deref for NWayCanvas<'_> {
+    type Target = Canvas;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Debug for NWayCanvas<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NWayCanvas").field(&self.0).finish()
+    }
+}
+
+impl<'a> NWayCanvas<'a> {
+    /// Creates an [`NWayCanvas`] with no canvases to forward to. `size` is used to reject
+    /// draws outside of it and should cover the sizes of the canvases added.
+    pub fn new(size: impl Into<ISize>) -> Self {
+        let size = size.into();
+        Self(
+            Canvas::own_from_native_ptr(unsafe { sb::C_SkNWayCanvas_new(size.width, size.height) })
+                .unwrap(),
+        )
+    }
+
+    pub fn add_canvas(&mut self, canvas: &'a Canvas) -> &mut Self {
+        unsafe { sb::C_SkNWayCanvas_addCanvas(self.0.native_mut(), canvas.native_mut()) }
+        self
+    }
+
+    pub fn remove_canvas(&mut self, canvas: &Canvas) -> &mut Self {
+        unsafe { sb::C_SkNWayCanvas_removeCanvas(self.0.native_mut(), canvas.native_mut()) }
+        self
+    }
+
+    pub fn remove_all(&mut self) -> &mut Self {
+        unsafe { sb::C_SkNWayCanvas_removeAll(self.0.native_mut()) }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NWayCanvas;
+    use crate::{Bitmap, Canvas, Color};
+
+    #[test]
+    fn forwards_to_all_canvases() {
+        let mut bitmaps = [Bitmap::new(), Bitmap::new()];
+        for bitmap in &mut bitmaps {
+            bitmap.alloc_n32_pixels((2, 2), None);
+            bitmap.erase_color(Color::TRANSPARENT);
+        }
+        {
+            let first = Canvas::from_bitmap(&bitmaps[0], None).unwrap();
+            let second = Canvas::from_bitmap(&bitmaps[1], None).unwrap();
+            let mut canvas = NWayCanvas::new((2, 2));
+            canvas.add_canvas(&first).add_canvas(&second);
+            canvas.clear(Color::RED);
+            canvas.remove_canvas(&first);
+            canvas.clear(Color::BLUE);
+        }
+        assert_eq!(bitmaps[0].get_color((1, 1)), Color::RED);
+        assert_eq!(bitmaps[1].get_color((1, 1)), Color::BLUE);
+    }
+}
@@ -0,0 +1,90 @@
+use std::{fmt, mem, ops::Deref};
+
+use skia_bindings::{self as sb, RustPaintFilterCanvas_Param, SkPaint, TraitObject};
+
+use crate::{Canvas, OwnedCanvas, Paint, prelude::*};
+
+/// A [`Canvas`] that passes the paint of every draw call through a filter before forwarding
+/// the call to another canvas.
+///
+/// Draw calls without a paint are filtered with a default paint.
+pub struct PaintFilterCanvas<'a>(OwnedCanvas<'a>);
+
+impl Deref for PaintFilterCanvas<'_> {
+    type Target = Canvas;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Debug for PaintFilterCanvas<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PaintFilterCanvas").field(&self.0).finish()
+    }
+}
+
+impl<'a> PaintFilterCanvas<'a> {
+    /// Creates a [`PaintFilterCanvas`] that draws to `canvas`.
+    ///
+    /// `filter` may modify the paint and returns `false` to skip the draw call.
+    pub fn new(canvas: &'a Canvas, filter: impl FnMut(&mut Paint) -> bool + 'a) -> Self {
+        let filter: Box<dyn FnMut(&mut Paint) -> bool + 'a> = Box::new(filter);
+        let param = RustPaintFilterCanvas_Param {
+            trait_: unsafe {
+                mem::transmute::<Box<dyn FnMut(&mut Paint) -> bool + 'a>, TraitObject>(filter)
+            },
+            drop: Some(drop),
+            filter: Some(filter_paint),
+        };
+
+        let canvas = Canvas::own_from_native_ptr(unsafe {
+            sb::C_RustPaintFilterCanvas_new(canvas.native_mut(), &param)
+        })
+        .unwrap();
+        return Self(canvas);
+
+        extern "C" fn drop(filter: TraitObject) {
+            mem::drop(unsafe {
+                mem::transmute::<TraitObject, Box<dyn FnMut(&mut Paint) -> bool>>(filter)
+            });
+        }
+
+        extern "C" fn filter_paint(filter: TraitObject, paint: *mut SkPaint) -> bool {
+            let filter = unsafe {
+                mem::transmute::<TraitObject, &mut dyn FnMut(&mut Paint) -> bool>(filter)
+            };
+            filter(Paint::from_native_ref_mut(unsafe { &mut *paint }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PaintFilterCanvas;
+    use crate::{Bitmap, Canvas, Color, Paint, Rect};
+
+    #[test]
+    fn filters_and_skips_paints() {
+        let mut bitmap = Bitmap::new();
+        bitmap.alloc_n32_pixels((2, 2), None);
+        bitmap.erase_color(Color::TRANSPARENT);
+        let mut filtered = 0;
+        {
+            let target = Canvas::from_bitmap(&bitmap, None).unwrap();
+            let canvas = PaintFilterCanvas::new(&target, |paint| {
+                filtered += 1;
+                if paint.color() == Color::GREEN {
+                    return false;
+                }
+                paint.set_color(Color::RED);
+                true
+            });
+            let rect = Rect::new(0.0, 0.0, 2.0, 2.0);
+            canvas.draw_rect(rect, &Paint::default());
+            canvas.draw_rect(rect, Paint::default().set_color(Color::GREEN));
+        }
+        assert_eq!(filtered, 2);
+        assert_eq!(bitmap.get_color((1, 1)), Color::RED);
+    }
+}
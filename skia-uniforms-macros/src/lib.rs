@@ -0,0 +1,113 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use std::collections::HashSet;
+use syn::{
+    Data, DeriveInput, Fields, LitStr, Path, parse_macro_input, parse_quote, spanned::Spanned,
+};
+
+/// Implements `skia_safe::runtime_effect::Uniforms` for a struct with named fields.
+///
+/// Every field binds to the SkSL uniform of the same name. The name can be overridden with
+/// `#[uniform(name = "...")]`. The path to the `skia_safe` crate can be overridden with
+/// `#[uniforms(crate = "...")]` on the struct.
+#[proc_macro_derive(Uniforms, attributes(uniform, uniforms))]
+pub fn derive_uniforms(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive_uniforms2(input)
+        .unwrap_or_else(|error| error.into_compile_error())
+        .into()
+}
+
+fn derive_uniforms2(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let krate = crate_path(&input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "`Uniforms` can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "`Uniforms` can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut names = HashSet::new();
+    let mut declarations = Vec::new();
+    let mut writes = Vec::new();
+
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let name = uniform_name(field)?.unwrap_or_else(|| ident.to_string());
+
+        if !names.insert(name.clone()) {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("uniform `{name}` is bound more than once"),
+            ));
+        }
+
+        declarations.push(quote! {
+            #krate::runtime_effect::UniformField::of::<#ty>(#name)
+        });
+        writes.push(quote! {
+            writer.write(&self.#ident);
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #krate::runtime_effect::Uniforms for #ident #ty_generics #where_clause {
+            const FIELDS: &'static [#krate::runtime_effect::UniformField] = &[
+                #(#declarations),*
+            ];
+
+            fn write_fields(&self, writer: &mut #krate::runtime_effect::UniformWriter<'_>) {
+                #(#writes)*
+            }
+        }
+    })
+}
+
+fn crate_path(input: &DeriveInput) -> syn::Result<Path> {
+    let mut path = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("uniforms")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                let value: LitStr = meta.value()?.parse()?;
+                path = Some(value.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `crate`"))
+            }
+        })?;
+    }
+    Ok(path.unwrap_or_else(|| parse_quote!(::skia_safe)))
+}
+
+fn uniform_name(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut name = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("uniform")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let value: LitStr = meta.value()?.parse()?;
+                name = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `name`"))
+            }
+        })?;
+    }
+    Ok(name)
+}